@@ -5,18 +5,26 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod registry;
 pub mod traits;
 pub mod types;
 
 pub use acurast_common::is_valid_script;
 pub use pallet::*;
+pub use registry::*;
 pub use types::*;
 
 #[frame_support::pallet]
 pub mod pallet {
-    use crate::Fulfillment;
-    use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
-    use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+    use crate::FulfillmentFor;
+    use acurast_common::{ExecutionId, ListUpdate, ListUpdateOperation, Script};
+    use frame_support::{
+        dispatch::DispatchResultWithPostInfo, pallet_prelude::*, sp_runtime::traits::Saturating,
+    };
+    use frame_system::{
+        ensure_signed,
+        pallet_prelude::{BlockNumberFor, OriginFor},
+    };
     use sp_std::prelude::*;
 
     use crate::traits::*;
@@ -28,6 +36,13 @@ pub mod pallet {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         /// Handler to notify the runtime when a new fulfillment is received.
         type OnFulfillment: OnFulfillment<Self>;
+        /// Barrier for updating the registry of processors allowed to fulfill a [Script].
+        type RegistryUpdateBarrier: RegistryUpdateBarrier<Self>;
+        /// Tells if a processor holds a valid attestation, used for scripts requiring an attestation.
+        type ProcessorAttestation: ProcessorAttestation<Self>;
+        /// The number of blocks the identifier of a fulfilled execution is retained to reject duplicate fulfillments.
+        #[pallet::constant]
+        type FulfillmentRetention: Get<BlockNumberFor<Self>>;
         /// Weight Info for extrinsics.
         type WeightInfo: WeightInfo;
     }
@@ -36,16 +51,69 @@ pub mod pallet {
     #[pallet::generate_store(pub(super) trait Store)]
     pub struct Pallet<T>(_);
 
+    /// The processors allowed to fulfill a script as a map [Script] -> [AccountId] -> `()`.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_allowed_processor)]
+    pub type StoredAllowedProcessor<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, Script, Blake2_128Concat, T::AccountId, ()>;
+
+    /// The scripts whose fulfillments are only accepted from processors with a valid attestation.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_attestation_required)]
+    pub type StoredAttestationRequired<T: Config> =
+        StorageMap<_, Blake2_128Concat, Script, bool, ValueQuery>;
+
+    /// The identifiers of fulfilled executions as a map [ExecutionId] -> `block`, where `block` is the block in which the identifier gets pruned.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_fulfilled_execution)]
+    pub type StoredFulfilledExecution<T: Config> =
+        StorageMap<_, Blake2_128Concat, ExecutionId<T::AccountId>, BlockNumberFor<T>>;
+
+    /// The identifiers of fulfilled executions as a map `block` -> [ExecutionId] -> `()`, where `block` is the block in which the identifier gets pruned.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_fulfilled_execution_expiry)]
+    pub type StoredFulfilledExecutionExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        ExecutionId<T::AccountId>,
+        (),
+    >;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+            let mut pruned: u64 = 0;
+            for (execution_id, _) in <StoredFulfilledExecutionExpiry<T>>::drain_prefix(block_number)
+            {
+                <StoredFulfilledExecution<T>>::remove(&execution_id);
+                pruned += 1;
+            }
+            T::DbWeight::get().reads_writes(pruned, pruned.saturating_mul(2))
+        }
+    }
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        FulfillReceived(T::AccountId, Fulfillment),
+        FulfillReceived(T::AccountId, FulfillmentFor<T>),
+        /// The processors allowed to fulfill a script were updated. [script, updates]
+        AllowedProcessorsUpdated(Script, Vec<ListUpdate<T::AccountId>>),
+        /// The attestation requirement of a script was updated. [script, required]
+        AttestationRequirementUpdated(Script, bool),
     }
 
     // Errors inform users that something went wrong.
     #[pallet::error]
     pub enum Error<T> {
         FulfillmentRejected,
+        /// The origin is not allowed to update the registry of the script.
+        RegistryUpdateNotAllowed,
+        /// The execution was already fulfilled.
+        DuplicateFulfillment,
+        /// The execution identifier names another source than the origin of the fulfillment.
+        ExecutionSourceMismatch,
     }
 
     #[pallet::call]
@@ -55,14 +123,83 @@ pub mod pallet {
         #[pallet::weight(T::WeightInfo::fulfill())]
         pub fn fulfill(
             origin: OriginFor<T>,
-            fulfillment: Fulfillment,
+            fulfillment: FulfillmentFor<T>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
+            if let Some(execution_id) = &fulfillment.execution {
+                ensure!(
+                    execution_id.source == who,
+                    Error::<T>::ExecutionSourceMismatch
+                );
+                ensure!(
+                    !<StoredFulfilledExecution<T>>::contains_key(execution_id),
+                    Error::<T>::DuplicateFulfillment
+                );
+                let expiry = <frame_system::Pallet<T>>::block_number()
+                    .saturating_add(T::FulfillmentRetention::get());
+                <StoredFulfilledExecution<T>>::insert(execution_id, expiry);
+                <StoredFulfilledExecutionExpiry<T>>::insert(expiry, execution_id, ());
+            }
+
             // Notify the runtime about the fulfillment.
             let info = T::OnFulfillment::on_fulfillment(who.clone(), fulfillment.clone())?;
             Self::deposit_event(Event::FulfillReceived(who, fulfillment));
             Ok(info)
         }
+
+        /// Updates the processors allowed to fulfill a [Script]. Only takes effect if the pallet is configured
+        /// with [crate::AllowedProcessorsFulfillment] as its [Config::OnFulfillment] handler.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::update_allowed_processors())]
+        pub fn update_allowed_processors(
+            origin: OriginFor<T>,
+            script: Script,
+            updates: Vec<ListUpdate<T::AccountId>>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::RegistryUpdateBarrier::can_update_registry(&who, &script),
+                Error::<T>::RegistryUpdateNotAllowed
+            );
+
+            for update in &updates {
+                match update.operation {
+                    ListUpdateOperation::Add => {
+                        <StoredAllowedProcessor<T>>::insert(&script, &update.item, ())
+                    }
+                    ListUpdateOperation::Remove => {
+                        <StoredAllowedProcessor<T>>::remove(&script, &update.item)
+                    }
+                }
+            }
+
+            Self::deposit_event(Event::AllowedProcessorsUpdated(script, updates));
+            Ok(().into())
+        }
+
+        /// Sets if fulfillments of a [Script] are only accepted from processors with a valid attestation, as told by [Config::ProcessorAttestation].
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::set_attestation_required())]
+        pub fn set_attestation_required(
+            origin: OriginFor<T>,
+            script: Script,
+            required: bool,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::RegistryUpdateBarrier::can_update_registry(&who, &script),
+                Error::<T>::RegistryUpdateNotAllowed
+            );
+
+            if required {
+                <StoredAttestationRequired<T>>::insert(&script, true);
+            } else {
+                <StoredAttestationRequired<T>>::remove(&script);
+            }
+
+            Self::deposit_event(Event::AttestationRequirementUpdated(script, required));
+            Ok(().into())
+        }
     }
 }
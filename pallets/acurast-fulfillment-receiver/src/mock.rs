@@ -1,4 +1,4 @@
-use crate::types::Fulfillment;
+use crate::types::{Fulfillment, FulfillmentFor};
 use acurast_common::Script;
 use frame_support::sp_runtime::DispatchError;
 use frame_support::{parameter_types, sp_runtime, traits::Everything, weights::Weight, PalletId};
@@ -6,7 +6,7 @@ use hex_literal::hex;
 use sp_runtime::traits::{AccountIdLookup, BlakeTwo256};
 use sp_runtime::{generic, AccountId32};
 
-use crate::traits::OnFulfillment;
+use crate::traits::{OnFulfillment, ProcessorAttestation, RegistryUpdateBarrier};
 
 type AccountId = AccountId32;
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -20,7 +20,7 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>} = 0,
-        AcurastFulfillmentReceiver: crate::{Pallet, Call, Event<T>}
+        AcurastFulfillmentReceiver: crate::{Pallet, Call, Storage, Event<T>}
     }
 );
 
@@ -31,6 +31,9 @@ parameter_types! {
     pub BlockWeights: frame_system::limits::BlockWeights = frame_system::limits::BlockWeights::simple_max(Weight::from_ref_time(1024));
     pub const MinimumPeriod: u64 = 6000;
     pub AllowedFulfillAccounts: Vec<AccountId> = vec![bob_account_id()];
+    pub AllowedRegistryUpdate: Vec<AccountId> = vec![alice_account_id()];
+    pub AttestedProcessors: Vec<AccountId> = vec![bob_account_id()];
+    pub const FulfillmentRetention: BlockNumber = 100;
 }
 parameter_types! {
     pub const MaxReserves: u32 = 50;
@@ -70,14 +73,34 @@ impl frame_system::Config for Test {
 impl crate::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type OnFulfillment = FulfillmentHandler;
+    type RegistryUpdateBarrier = Barrier;
+    type ProcessorAttestation = Attestations;
+    type FulfillmentRetention = FulfillmentRetention;
     type WeightInfo = ();
 }
 
+pub struct Barrier;
+impl RegistryUpdateBarrier<Test> for Barrier {
+    fn can_update_registry(
+        origin: &<Test as frame_system::Config>::AccountId,
+        _script: &Script,
+    ) -> bool {
+        AllowedRegistryUpdate::get().contains(origin)
+    }
+}
+
+pub struct Attestations;
+impl ProcessorAttestation<Test> for Attestations {
+    fn is_attested(processor: &<Test as frame_system::Config>::AccountId) -> bool {
+        AttestedProcessors::get().contains(processor)
+    }
+}
+
 pub struct FulfillmentHandler;
 impl OnFulfillment<Test> for FulfillmentHandler {
     fn on_fulfillment(
         from: <Test as frame_system::Config>::AccountId,
-        _fulfillment: Fulfillment,
+        _fulfillment: FulfillmentFor<Test>,
     ) -> sp_runtime::DispatchResultWithInfo<frame_support::dispatch::PostDispatchInfo> {
         if !AllowedFulfillAccounts::get().contains(&from) {
             return Err(DispatchError::BadOrigin.into());
@@ -94,6 +117,10 @@ pub fn bob_account_id() -> AccountId {
     [1; 32].into()
 }
 
+pub fn charlie_account_id() -> AccountId {
+    [2; 32].into()
+}
+
 pub struct ExtBuilder;
 
 impl ExtBuilder {
@@ -120,10 +147,11 @@ pub fn script() -> Script {
     SCRIPT_BYTES.to_vec().try_into().unwrap()
 }
 
-pub fn fulfillment_for(script: Script) -> Fulfillment {
+pub fn fulfillment_for(script: Script) -> FulfillmentFor<Test> {
     Fulfillment {
         script,
         payload: hex!("00").to_vec(),
+        execution: None,
     }
 }
 
@@ -0,0 +1,35 @@
+use core::marker::PhantomData;
+
+use frame_support::{
+    dispatch::PostDispatchInfo,
+    sp_runtime::{DispatchError, DispatchResultWithInfo},
+};
+
+use crate::{
+    traits::{OnFulfillment, ProcessorAttestation},
+    Config, FulfillmentFor, Pallet,
+};
+
+/// An [OnFulfillment] handler that only accepts fulfillments from processors registered for the fulfilled script with
+/// [Pallet::update_allowed_processors], and that hold a valid attestation if the script requires one. Accepted fulfillments
+/// are passed on to `H`.
+///
+/// Fulfillments for scripts without any allowed processor are rejected.
+pub struct AllowedProcessorsFulfillment<H>(PhantomData<H>);
+
+impl<T: Config, H: OnFulfillment<T>> OnFulfillment<T> for AllowedProcessorsFulfillment<H> {
+    fn on_fulfillment(
+        from: T::AccountId,
+        fulfillment: FulfillmentFor<T>,
+    ) -> DispatchResultWithInfo<PostDispatchInfo> {
+        if <Pallet<T>>::stored_allowed_processor(&fulfillment.script, &from).is_none() {
+            return Err(DispatchError::BadOrigin.into());
+        }
+        if <Pallet<T>>::stored_attestation_required(&fulfillment.script)
+            && !T::ProcessorAttestation::is_attested(&from)
+        {
+            return Err(DispatchError::BadOrigin.into());
+        }
+        H::on_fulfillment(from, fulfillment)
+    }
+}
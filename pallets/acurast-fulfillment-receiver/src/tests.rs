@@ -1,11 +1,14 @@
 #![cfg(test)]
 
-use frame_support::{assert_err, assert_ok, sp_runtime::DispatchError};
+use acurast_common::{ExecutionId, ListUpdate, ListUpdateOperation};
+use frame_support::{assert_err, assert_ok, sp_runtime::DispatchError, traits::Hooks};
 
 use crate::mock::{
-    alice_account_id, bob_account_id, events, fulfillment_for, script, AcurastFulfillmentReceiver,
-    ExtBuilder, RuntimeEvent, RuntimeOrigin,
+    alice_account_id, bob_account_id, charlie_account_id, events, fulfillment_for, script,
+    AcurastFulfillmentReceiver, ExtBuilder, RuntimeEvent, RuntimeOrigin, System, Test,
 };
+use crate::traits::OnFulfillment;
+use crate::{AllowedProcessorsFulfillment, Error};
 
 #[test]
 fn test_job_fulfillment() {
@@ -42,3 +45,160 @@ fn test_job_fulfillment_reject() {
         assert_eq!(events(), []);
     });
 }
+
+#[test]
+fn test_duplicate_fulfillment() {
+    ExtBuilder::default().build().execute_with(|| {
+        let mut fulfillment = fulfillment_for(script());
+        fulfillment.execution = Some(ExecutionId {
+            job_id: (alice_account_id(), script()),
+            source: bob_account_id(),
+            execution: 0,
+        });
+
+        assert_ok!(AcurastFulfillmentReceiver::fulfill(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            fulfillment.clone(),
+        ));
+        assert_err!(
+            AcurastFulfillmentReceiver::fulfill(
+                RuntimeOrigin::signed(bob_account_id()).into(),
+                fulfillment.clone(),
+            ),
+            Error::<Test>::DuplicateFulfillment
+        );
+
+        // other executions of the same job are accepted
+        let mut next_fulfillment = fulfillment.clone();
+        next_fulfillment.execution = Some(ExecutionId {
+            job_id: (alice_account_id(), script()),
+            source: bob_account_id(),
+            execution: 1,
+        });
+        assert_ok!(AcurastFulfillmentReceiver::fulfill(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            next_fulfillment.clone(),
+        ));
+
+        // the execution of another source cannot be fulfilled
+        let mut other_fulfillment = fulfillment.clone();
+        other_fulfillment.execution = Some(ExecutionId {
+            job_id: (alice_account_id(), script()),
+            source: charlie_account_id(),
+            execution: 2,
+        });
+        assert_err!(
+            AcurastFulfillmentReceiver::fulfill(
+                RuntimeOrigin::signed(bob_account_id()).into(),
+                other_fulfillment,
+            ),
+            Error::<Test>::ExecutionSourceMismatch
+        );
+
+        // the identifier is pruned after the retention period
+        let expiry = System::block_number() + 100;
+        assert_eq!(
+            Some(expiry),
+            AcurastFulfillmentReceiver::stored_fulfilled_execution(
+                fulfillment.execution.clone().unwrap()
+            )
+        );
+        AcurastFulfillmentReceiver::on_initialize(expiry);
+        assert_eq!(
+            None,
+            AcurastFulfillmentReceiver::stored_fulfilled_execution(
+                fulfillment.execution.clone().unwrap()
+            )
+        );
+        assert_ok!(AcurastFulfillmentReceiver::fulfill(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            fulfillment.clone(),
+        ));
+    });
+}
+
+#[test]
+fn test_allowed_processors_fulfillment() {
+    ExtBuilder::default().build().execute_with(|| {
+        let fulfillment = fulfillment_for(script());
+        let on_fulfillment = |from| {
+            <AllowedProcessorsFulfillment<()> as OnFulfillment<Test>>::on_fulfillment(
+                from,
+                fulfillment.clone(),
+            )
+        };
+
+        // scripts without allowed processors reject all fulfillments
+        assert_err!(on_fulfillment(bob_account_id()), DispatchError::BadOrigin);
+
+        let updates = vec![
+            ListUpdate {
+                operation: ListUpdateOperation::Add,
+                item: bob_account_id(),
+            },
+            ListUpdate {
+                operation: ListUpdateOperation::Add,
+                item: charlie_account_id(),
+            },
+        ];
+        assert_err!(
+            AcurastFulfillmentReceiver::update_allowed_processors(
+                RuntimeOrigin::signed(bob_account_id()).into(),
+                script(),
+                updates.clone(),
+            ),
+            Error::<Test>::RegistryUpdateNotAllowed
+        );
+        assert_ok!(AcurastFulfillmentReceiver::update_allowed_processors(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            script(),
+            updates.clone(),
+        ));
+
+        assert_ok!(on_fulfillment(bob_account_id()));
+        assert_ok!(on_fulfillment(charlie_account_id()));
+        assert_err!(on_fulfillment(alice_account_id()), DispatchError::BadOrigin);
+
+        // charlie is not attested
+        assert_ok!(AcurastFulfillmentReceiver::set_attestation_required(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            script(),
+            true,
+        ));
+        assert_ok!(on_fulfillment(bob_account_id()));
+        assert_err!(
+            on_fulfillment(charlie_account_id()),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(AcurastFulfillmentReceiver::update_allowed_processors(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            script(),
+            vec![ListUpdate {
+                operation: ListUpdateOperation::Remove,
+                item: bob_account_id(),
+            }],
+        ));
+        assert_err!(on_fulfillment(bob_account_id()), DispatchError::BadOrigin);
+
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::AcurastFulfillmentReceiver(crate::Event::AllowedProcessorsUpdated(
+                    script(),
+                    updates
+                )),
+                RuntimeEvent::AcurastFulfillmentReceiver(
+                    crate::Event::AttestationRequirementUpdated(script(), true)
+                ),
+                RuntimeEvent::AcurastFulfillmentReceiver(crate::Event::AllowedProcessorsUpdated(
+                    script(),
+                    vec![ListUpdate {
+                        operation: ListUpdateOperation::Remove,
+                        item: bob_account_id(),
+                    }]
+                )),
+            ]
+        );
+    });
+}
@@ -3,7 +3,9 @@ use frame_support::{
     sp_runtime::DispatchResultWithInfo,
 };
 
-use crate::{Config, Fulfillment};
+use acurast_common::Script;
+
+use crate::{Config, FulfillmentFor};
 
 /// Handles an acurast job fulfillment.
 ///
@@ -12,16 +14,56 @@ use crate::{Config, Fulfillment};
 pub trait OnFulfillment<T: Config> {
     fn on_fulfillment(
         from: T::AccountId,
-        fulfillment: Fulfillment,
+        fulfillment: FulfillmentFor<T>,
     ) -> DispatchResultWithInfo<PostDispatchInfo>;
 }
 
+/// Accepts all fulfillments without further handling, e.g. to be wrapped by [crate::AllowedProcessorsFulfillment].
+impl<T: Config> OnFulfillment<T> for () {
+    fn on_fulfillment(
+        _from: T::AccountId,
+        _fulfillment: FulfillmentFor<T>,
+    ) -> DispatchResultWithInfo<PostDispatchInfo> {
+        Ok(().into())
+    }
+}
+
+/// Allows to customize who can update the processors allowed to fulfill a [Script] and its attestation requirement.
+pub trait RegistryUpdateBarrier<T: Config> {
+    fn can_update_registry(origin: &T::AccountId, script: &Script) -> bool;
+}
+
+impl<T: Config> RegistryUpdateBarrier<T> for () {
+    fn can_update_registry(_origin: &T::AccountId, _script: &Script) -> bool {
+        false
+    }
+}
+
+/// Tells if a processor holds a valid attestation, e.g. from attestations relayed from the Acurast chain.
+pub trait ProcessorAttestation<T: Config> {
+    fn is_attested(processor: &T::AccountId) -> bool;
+}
+
+impl<T: Config> ProcessorAttestation<T> for () {
+    fn is_attested(_processor: &T::AccountId) -> bool {
+        false
+    }
+}
+
 pub trait WeightInfo {
     fn fulfill() -> Weight;
+    fn update_allowed_processors() -> Weight;
+    fn set_attestation_required() -> Weight;
 }
 
 impl WeightInfo for () {
     fn fulfill() -> Weight {
         Weight::from_ref_time(10_000)
     }
+    fn update_allowed_processors() -> Weight {
+        Weight::from_ref_time(10_000)
+    }
+    fn set_attestation_required() -> Weight {
+        Weight::from_ref_time(10_000)
+    }
 }
@@ -1,12 +1,16 @@
-use acurast_common::Script;
+use acurast_common::{ExecutionId, Script};
 use frame_support::pallet_prelude::*;
 use sp_std::prelude::*;
 
 /// Structure representing a job fulfillment. It contains the script that generated the payload and the actual payload.
 #[derive(RuntimeDebug, Encode, Decode, TypeInfo, Clone, PartialEq)]
-pub struct Fulfillment {
+pub struct Fulfillment<AccountId> {
     /// The script that generated the payload.
     pub script: Script,
     /// The output of a script.
     pub payload: Vec<u8>,
+    /// The execution that produced the payload. Fulfillments with an execution identifier are only accepted once.
+    pub execution: Option<ExecutionId<AccountId>>,
 }
+
+pub type FulfillmentFor<T> = Fulfillment<<T as frame_system::Config>::AccountId>;
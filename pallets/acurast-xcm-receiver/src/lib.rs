@@ -16,8 +16,9 @@ mod benchmarking;
 #[frame_support::pallet]
 pub mod pallet {
     use crate::traits::*;
-    use frame_support::pallet_prelude::*;
-    use frame_system::pallet_prelude::OriginFor;
+    use acurast_common::{ExecutionId, JobId, Script};
+    use frame_support::{pallet_prelude::*, sp_runtime::traits::Saturating};
+    use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
     use sp_std::prelude::*;
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -33,14 +34,58 @@ pub mod pallet {
         type OnFulfillment: OnFulfillment<Self>;
         /// Handle origin validation
         type Barrier: ParachainBarrier<Self>;
+        /// The number of blocks the identifier of a fulfilled execution is retained to reject duplicate fulfillments.
+        #[pallet::constant]
+        type FulfillmentRetention: Get<BlockNumberFor<Self>>;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
     pub struct Pallet<T>(_);
 
-    /// TODO: We may want to add a storage for job identifiers, allowing acurast parachain to send
-    /// the (job identifer + payload). The requester address would be indexed by job identifier.
+    /// The jobs fulfilled through [Pallet::fulfill_job] indexed by requester, as a map [AccountId] `(consumer)` -> [Script] -> `block`,
+    /// where `block` is the block of the last fulfillment received for the job.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_requester_job)]
+    pub type StoredRequesterJob<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        Script,
+        BlockNumberFor<T>,
+    >;
+
+    /// The identifiers of fulfilled executions as a map [ExecutionId] -> `block`, where `block` is the block in which the identifier gets pruned.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_fulfilled_execution)]
+    pub type StoredFulfilledExecution<T: Config> =
+        StorageMap<_, Blake2_128Concat, ExecutionId<T::AccountId>, BlockNumberFor<T>>;
+
+    /// The identifiers of fulfilled executions as a map `block` -> [ExecutionId] -> `()`, where `block` is the block in which the identifier gets pruned.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_fulfilled_execution_expiry)]
+    pub type StoredFulfilledExecutionExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        ExecutionId<T::AccountId>,
+        (),
+    >;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+            let mut pruned: u64 = 0;
+            for (execution_id, _) in <StoredFulfilledExecutionExpiry<T>>::drain_prefix(block_number)
+            {
+                <StoredFulfilledExecution<T>>::remove(&execution_id);
+                pruned += 1;
+            }
+            T::DbWeight::get().reads_writes(pruned, pruned.saturating_mul(2))
+        }
+    }
 
     // Pallets use events to inform users when important changes are made.
     // https://docs.substrate.io/v3/runtime/events-and-errors
@@ -48,11 +93,16 @@ pub mod pallet {
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         FulfillReceived(T::Payload, Option<T::Parameters>),
+        /// A fulfillment of a job was received. [JobId, payload, parameters]
+        JobFulfillReceived(JobId<T::AccountId>, T::Payload, Option<T::Parameters>),
     }
 
     // Errors inform users that something went wrong.
     #[pallet::error]
-    pub enum Error<T> {}
+    pub enum Error<T> {
+        /// The execution was already fulfilled.
+        DuplicateFulfillment,
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -81,5 +131,52 @@ pub mod pallet {
                 }
             }
         }
+
+        /// Dispatchable function that notifies the runtime about a fulfilment of the job `job_id` coming from acurast parachain.
+        ///
+        /// The job is indexed by its requester in [StoredRequesterJob]. Fulfillments carrying the source and index of the `execution` are only accepted once.
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_ref_time(10_000).saturating_add(T::DbWeight::get().reads_writes(1, 4)))]
+        pub fn fulfill_job(
+            origin: OriginFor<T>,
+            job_id: JobId<T::AccountId>,
+            payload: T::Payload,
+            parameters: Option<T::Parameters>,
+            execution: Option<(T::AccountId, u64)>,
+        ) -> DispatchResult {
+            // Check that the extrinsic comes from a trusted xcm channel.
+            T::Barrier::ensure_xcm_origin(origin)?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            if let Some((source, execution)) = execution {
+                let execution_id = ExecutionId {
+                    job_id: job_id.clone(),
+                    source,
+                    execution,
+                };
+                ensure!(
+                    !<StoredFulfilledExecution<T>>::contains_key(&execution_id),
+                    Error::<T>::DuplicateFulfillment
+                );
+                let expiry = now.saturating_add(T::FulfillmentRetention::get());
+                <StoredFulfilledExecution<T>>::insert(&execution_id, expiry);
+                <StoredFulfilledExecutionExpiry<T>>::insert(expiry, &execution_id, ());
+            }
+            <StoredRequesterJob<T>>::insert(&job_id.0, &job_id.1, now);
+
+            // Notify the runtime about the fulfillment.
+            match T::OnFulfillment::fulfill_job(
+                job_id.clone(),
+                payload.clone().into(),
+                parameters.clone().map(|parameters| parameters.into()),
+            ) {
+                Err(err) => Err(err.error),
+                Ok(_) => {
+                    Self::deposit_event(Event::JobFulfillReceived(job_id, payload, parameters));
+
+                    Ok(())
+                }
+            }
+        }
     }
 }
@@ -1,7 +1,8 @@
 use crate as pallet_acurast_xcm_receiver;
 use frame_support::{
     dispatch::{Pays, PostDispatchInfo},
-    traits::{ConstU16, ConstU64, Everything, Nothing},
+    traits::{ConstU128, ConstU16, ConstU32, ConstU64, Everything, Nothing},
+    weights::Weight,
 };
 use frame_system as system;
 use sp_core::H256;
@@ -10,7 +11,7 @@ use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup},
 };
 use sp_std::prelude::*;
-use xcm::v2::Junction::Parachain;
+use xcm::v3::{prelude::Parachain, InteriorMultiLocation};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -23,6 +24,7 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         AcurastReceiver: pallet_acurast_xcm_receiver::{Pallet, Call, Storage, Event<T>},
         PolkadotXcm: pallet_xcm::{Pallet, Storage, Call, Event<T>, Origin, Config},
     }
@@ -30,12 +32,12 @@ frame_support::construct_runtime!(
 
 frame_support::parameter_types! {
     /// The amount of weight an XCM operation takes. This is safe overestimate.
-    pub UnitWeightCost: xcm::v2::Weight = 200_000_000;
+    pub UnitWeightCost: Weight = Weight::from_ref_time(200_000_000);
     /// Maximum number of instructions in a single XCM fragment. A sanity check against
     /// weight caculations getting too crazy.
     pub MaxInstructions: u32 = 100;
-    // The ancestry, defines the multilocation describing this consensus system
-    pub Ancestry: xcm::v2::MultiLocation = Parachain(2001).into();
+    // The universal location, defines the multilocation describing this consensus system
+    pub UniversalLocation: InteriorMultiLocation = Parachain(2001).into();
 }
 
 impl pallet_xcm::Config for Test {
@@ -48,11 +50,28 @@ impl pallet_xcm::Config for Test {
     type XcmTeleportFilter = Nothing;
     type XcmReserveTransferFilter = Everything;
     type Weigher = xcm_builder::FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-    type LocationInverter = xcm_builder::LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
     const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
     type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = ();
+    type TrustedLockers = ();
+    type SovereignAccountOf = ();
+    type MaxLockers = ConstU32<8>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u128;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ConstU32<50>;
+    type MaxReserves = ConstU32<50>;
+    type ReserveIdentifier = [u8; 8];
 }
 
 impl system::Config for Test {
@@ -73,7 +92,7 @@ impl system::Config for Test {
     type BlockHashCount = ConstU64<250>;
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u128>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
@@ -130,6 +149,7 @@ impl pallet_acurast_xcm_receiver::Config for Test {
     type Parameters = Vec<u8>;
     type OnFulfillment = OnAcurastFulfillment;
     type Barrier = ParachainBarrier;
+    type FulfillmentRetention = ConstU64<100>;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -1,6 +1,8 @@
 use crate::mock::*;
-use frame_support::{assert_err, assert_ok};
-use xcm::v2::{Junction::Parachain, Junctions::X1, MultiLocation};
+use acurast_common::Script;
+use codec::{Decode, Encode};
+use frame_support::{assert_err, assert_ok, traits::Hooks};
+use xcm::v3::{Junction::Parachain, Junctions::X1, MultiLocation};
 
 #[test]
 fn test_fulfill() {
@@ -31,3 +33,89 @@ fn test_fulfill() {
         );
     });
 }
+
+#[test]
+fn test_fulfill_job() {
+    let payload = vec![0u8; 128];
+    let script: Script = vec![0u8; 53].try_into().unwrap();
+    let job_id = (1, script.clone());
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let xcm_origin = || {
+            RuntimeOrigin::from(pallet_xcm::Origin::Xcm(MultiLocation {
+                parents: 1,
+                interior: X1(Parachain(2001)),
+            }))
+        };
+
+        assert_ok!(AcurastReceiver::fulfill_job(
+            xcm_origin(),
+            job_id.clone(),
+            payload.clone(),
+            None,
+            Some((1, 0))
+        ));
+        assert_eq!(
+            Some(1),
+            AcurastReceiver::stored_requester_job(1, script.clone())
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::AcurastReceiver(crate::Event::JobFulfillReceived(
+                job_id.clone(),
+                payload.clone(),
+                None
+            ))));
+
+        assert_err!(
+            AcurastReceiver::fulfill_job(
+                xcm_origin(),
+                job_id.clone(),
+                payload.clone(),
+                None,
+                Some((1, 0))
+            ),
+            crate::Error::<Test>::DuplicateFulfillment
+        );
+        assert_ok!(AcurastReceiver::fulfill_job(
+            xcm_origin(),
+            job_id.clone(),
+            payload.clone(),
+            None,
+            Some((1, 1))
+        ));
+        // the same execution of another slot is fulfilled by a different source
+        assert_ok!(AcurastReceiver::fulfill_job(
+            xcm_origin(),
+            job_id.clone(),
+            payload.clone(),
+            None,
+            Some((2, 0))
+        ));
+
+        // the identifier is accepted again after the retention period
+        AcurastReceiver::on_initialize(System::block_number() + 100);
+        assert_ok!(AcurastReceiver::fulfill_job(
+            xcm_origin(),
+            job_id.clone(),
+            payload.clone(),
+            None,
+            Some((1, 0))
+        ));
+    });
+}
+
+#[test]
+fn test_decode_legacy_fulfill() {
+    let payload = vec![0u8; 128];
+    // call index followed by the arguments of the call shape without job identifier
+    let encoded = (0u8, payload.clone(), Option::<Vec<u8>>::None).encode();
+
+    assert_eq!(
+        crate::Call::<Test>::decode(&mut &encoded[..]).unwrap(),
+        crate::Call::<Test>::fulfill {
+            payload,
+            parameters: None
+        }
+    );
+}
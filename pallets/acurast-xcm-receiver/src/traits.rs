@@ -1,4 +1,5 @@
 use crate::Config;
+use acurast_common::JobId;
 use frame_support::{
     dispatch::PostDispatchInfo,
     sp_runtime::{DispatchError, DispatchResultWithInfo},
@@ -12,6 +13,17 @@ pub trait OnFulfillment<T: Config> {
         payload: Vec<u8>,
         parameters: Option<Vec<u8>>,
     ) -> DispatchResultWithInfo<PostDispatchInfo>;
+
+    /// Handles the fulfillment of a job, where `job_id` holds the consumer that registered the job and its script.
+    ///
+    /// Defaults to [OnFulfillment::fulfill], dropping the `job_id`.
+    fn fulfill_job(
+        _job_id: JobId<T::AccountId>,
+        payload: Vec<u8>,
+        parameters: Option<Vec<u8>>,
+    ) -> DispatchResultWithInfo<PostDispatchInfo> {
+        Self::fulfill(payload, parameters)
+    }
 }
 
 /// Allows execution only from trusted origins.
@@ -1,6 +1,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub use types::*;
+
+mod migrations;
+pub mod types;
 
 #[cfg(test)]
 mod mock;
@@ -9,29 +13,180 @@ mod tests;
 
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+    use acurast_common::JobId;
+    use frame_support::{
+        dispatch::DispatchResult,
+        pallet_prelude::*,
+        sp_runtime::traits::{One, Saturating},
+    };
+    use frame_system::{
+        ensure_root, ensure_signed,
+        pallet_prelude::{BlockNumberFor, OriginFor},
+    };
     use sp_std::prelude::*;
-    use xcm::v2::{
-        Instruction::Transact, Junction, MultiLocation, OriginKind, SendError, SendXcm, Xcm,
+    use xcm::v3::{
+        validate_send, Instruction::Transact, Junction, MultiAssets, MultiLocation, OriginKind,
+        SendError, SendXcm, Xcm, XcmHash,
     };
+    use xcm::WrapVersion;
+
+    use crate::XcmExecutionConfig;
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type XcmSender: SendXcm;
+        /// Wraps messages in the XCM version negotiated with their destination, usually `PolkadotXcm`.
+        type VersionWrapper: WrapVersion;
+        /// The location of this chain as seen from the destinations, receiving the fees not used for the execution.
+        type SelfLocation: Get<MultiLocation>;
+        /// The maximum weight of the `Transact` sent to destinations without an [XcmExecutionConfig].
+        #[pallet::constant]
+        type DefaultTransactWeight: Get<Weight>;
+        /// The maximum number of messages kept for a retry after their sending failed.
+        #[pallet::constant]
+        type MaxPendingXcm: Get<u32>;
+        /// The number of failed retries after which a pending message is given up.
+        #[pallet::constant]
+        type MaxXcmRetries: Get<u32>;
+        /// The number of blocks to wait before the first retry of a pending message, doubled after every failed retry.
+        #[pallet::constant]
+        type XcmRetryBackoff: Get<Self::BlockNumber>;
+        /// The weight of validating and delivering a message with [Config::XcmSender], charged for every retry.
+        #[pallet::constant]
+        type XcmSendWeight: Get<Weight>;
     }
 
     #[pallet::error]
     pub enum Error<T> {
         XcmError,
         InvalidDestination,
+        /// The message could not be sent and there is no space left to keep it for a retry.
+        PendingXcmQueueFull,
+        /// There is no pending message with the given id.
+        PendingXcmNotFound,
+        /// Only the caller that sent the message can retry or cancel it.
+        NotPendingXcmCaller,
+    }
+
+    /// The configuration for paying the execution of messages as a map destination -> [XcmExecutionConfig]. Messages to destinations
+    /// without a configuration are sent without paying for their execution.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_xcm_execution_config)]
+    pub type StoredXcmExecutionConfig<T: Config> =
+        StorageMap<_, Blake2_128Concat, MultiLocation, XcmExecutionConfig>;
+
+    /// The messages whose sending failed as a map id -> [PendingXcm], retried in `on_idle` until they are sent or given up.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    #[pallet::getter(fn stored_pending_xcm)]
+    pub type StoredPendingXcm<T: Config> =
+        StorageMap<_, Blake2_128Concat, u64, PendingXcm<T::AccountId, T::BlockNumber>>;
+
+    /// The number of entries in [StoredPendingXcm].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_pending_xcm_count)]
+    pub type StoredPendingXcmCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// The id assigned to the next message added to [StoredPendingXcm].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_next_pending_xcm_id)]
+    pub type StoredNextPendingXcmId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// The ids of the messages in [StoredPendingXcm] as a map block number -> id -> (), indexed by the block from which on they are retried.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_pending_xcm_retry)]
+    pub type StoredPendingXcmRetry<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::BlockNumber, Blake2_128Concat, u64, ()>;
+
+    /// The first block of [StoredPendingXcmRetry] whose messages were not all retried yet, unset while there are no pending messages.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_pending_xcm_retry_cursor)]
+    pub type StoredPendingXcmRetryCursor<T: Config> = StorageValue<_, T::BlockNumber>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::migrate::<T>()
+        }
+
+        fn on_idle(block_number: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::retry_pending(block_number, remaining_weight)
+        }
     }
 
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Sets or removes the [XcmExecutionConfig] for a `destination` chain. Can only be called by root.
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_ref_time(10_000).saturating_add(T::DbWeight::get().writes(1)))]
+        pub fn set_xcm_execution_config(
+            origin: OriginFor<T>,
+            destination: MultiLocation,
+            config: Option<XcmExecutionConfig>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            <StoredXcmExecutionConfig<T>>::set(&destination, config.clone());
+            Self::deposit_event(Event::XcmExecutionConfigUpdated {
+                destination,
+                config,
+            });
+            Ok(())
+        }
+
+        /// Retries sending the pending message `id` immediately. Can only be called by the caller that sent the message.
+        ///
+        /// A failed retry counts towards [Config::MaxXcmRetries] like the retries in `on_idle`.
+        #[pallet::call_index(1)]
+        #[pallet::weight(Pallet::<T>::retry_weight())]
+        pub fn retry_xcm(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let pending = Self::ensure_pending_caller(id, &who)?;
+            Self::retry(id, pending, <frame_system::Pallet<T>>::block_number());
+            Ok(())
+        }
+
+        /// Removes the pending message `id` without sending it. Can only be called by the caller that sent the message.
+        #[pallet::call_index(2)]
+        #[pallet::weight(Weight::from_ref_time(10_000).saturating_add(T::DbWeight::get().reads_writes(2, 3)))]
+        pub fn cancel_xcm(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let pending = Self::ensure_pending_caller(id, &who)?;
+            Self::remove_pending(id, pending.next_retry);
+            Self::deposit_event(Event::XcmCancelled { id });
+            Ok(())
+        }
+    }
+
+    /// A message whose sending failed, kept for a retry.
     #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
-    pub enum ReceiverCall {
+    pub struct PendingXcm<AccountId, BlockNumber> {
+        /// The caller that sent the message.
+        pub caller: AccountId,
+        /// The destination of the message, including the pallet instance of the receiver.
+        pub destination: MultiLocation,
+        /// The call sent to the receiver.
+        pub call: ReceiverCall<AccountId>,
+        /// The error of the last attempt to send the message.
+        pub error: SendError,
+        /// The number of failed retries.
+        pub attempts: u32,
+        /// The block from which on the message is retried.
+        pub next_retry: BlockNumber,
+    }
+
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+    pub enum ReceiverCall<AccountId> {
         #[codec(index = 0u8)]
         Fulfill(Vec<u8>, Option<Vec<u8>>),
+        #[codec(index = 1u8)]
+        FulfillJob(
+            JobId<AccountId>,
+            Vec<u8>,
+            Option<Vec<u8>>,
+            Option<(AccountId, u64)>,
+        ),
     }
 
     fn split_multi_location<T: Config>(
@@ -43,7 +198,11 @@ pub mod pallet {
         }
     }
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     impl<T: Config> Pallet<T> {
@@ -53,37 +212,272 @@ pub mod pallet {
             payload: Vec<u8>,
             parameters: Option<Vec<u8>>,
         ) -> DispatchResult {
+            Self::send_call(
+                caller,
+                destination,
+                ReceiverCall::Fulfill(payload, parameters),
+            )
+        }
+
+        /// Sends the fulfillment of the job `job_id` by `caller`, optionally identifying the fulfilled `execution` to let the receiver reject duplicates.
+        pub fn send_job_fulfillment(
+            caller: T::AccountId,
+            destination: MultiLocation,
+            job_id: JobId<T::AccountId>,
+            payload: Vec<u8>,
+            parameters: Option<Vec<u8>>,
+            execution: Option<u64>,
+        ) -> DispatchResult {
+            let execution = execution.map(|execution| (caller.clone(), execution));
+            Self::send_call(
+                caller,
+                destination,
+                ReceiverCall::FulfillJob(job_id, payload, parameters, execution),
+            )
+        }
+
+        fn send_call(
+            caller: T::AccountId,
+            destination: MultiLocation,
+            call: ReceiverCall<T::AccountId>,
+        ) -> DispatchResult {
+            match Self::send_to(destination, &call)? {
+                Ok((message_hash, fees)) => {
+                    Pallet::<T>::deposit_event(Event::XcmSent {
+                        call,
+                        caller,
+                        message_hash,
+                        fees,
+                    });
+                    Ok(())
+                }
+                Err(error) => {
+                    Pallet::<T>::deposit_event(Event::XcmNotSent {
+                        call: call.clone(),
+                        caller: caller.clone(),
+                        error: error.clone(),
+                    });
+                    // keep the message for a retry instead of failing, which would revert the caller's changes
+                    Self::add_pending(caller, destination, call, error)
+                }
+            }
+        }
+
+        /// Builds the message executing `call` on `destination` and delivers it.
+        ///
+        /// The outer result fails for an invalid `destination`, the inner result for a failed delivery.
+        fn send_to(
+            destination: MultiLocation,
+            call: &ReceiverCall<T::AccountId>,
+        ) -> Result<Result<(XcmHash, MultiAssets), SendError>, Error<T>> {
             let (xcm_destination, pallet_instance) = split_multi_location::<T>(destination)?;
             // Encode extrinsic call version of the call
             // - first byte is the pallet id on the destination chain;
             let mut encoded_call = vec![pallet_instance];
             // - second byte is the position of the extrinsic function being called;
             // - and the remaining bytes are the extrinsic parameter SCALE encoded.
-            let call = ReceiverCall::Fulfill(payload, parameters);
             encoded_call.append(&mut call.encode());
 
-            // Add transact instruction with the fulfill call.
-            let transaction = Transact {
-                origin_type: OriginKind::Xcm,
-                require_weight_at_most: 1_000_000_000u64, // TODO: Review this value
-                call: encoded_call.into(),
+            let message = match <StoredXcmExecutionConfig<T>>::get(&xcm_destination) {
+                // Pay for the execution of the transact instruction with the fulfill call.
+                Some(config) => config.program(encoded_call, T::SelfLocation::get()),
+                // Add transact instruction with the fulfill call.
+                None => Xcm(vec![Transact {
+                    origin_kind: OriginKind::Xcm,
+                    require_weight_at_most: T::DefaultTransactWeight::get(),
+                    call: encoded_call.into(),
+                }]),
             };
 
             // Submit the xcm message
-            match T::XcmSender::send_xcm(xcm_destination, Xcm(vec![transaction])) {
-                Ok(()) => {
-                    Pallet::<T>::deposit_event(Event::XcmSent { call, caller });
-                    Ok(())
+            Ok(Self::deliver(xcm_destination, message))
+        }
+
+        /// Delivers the `message` to `destination`, returning its hash and the delivery fees charged by the router.
+        ///
+        /// Messages are built with XCM v3. Destinations that negotiated v2 receive the message downgraded by the router,
+        /// so it is only sent if it can be expressed in the version negotiated with `destination`.
+        fn deliver(
+            destination: MultiLocation,
+            message: Xcm<()>,
+        ) -> Result<(XcmHash, MultiAssets), SendError> {
+            T::VersionWrapper::wrap_version::<()>(&destination, message.clone())
+                .map_err(|()| SendError::DestinationUnsupported)?;
+            let (ticket, fees) = validate_send::<T::XcmSender>(destination, message)?;
+            let message_hash = T::XcmSender::deliver(ticket)?;
+            Ok((message_hash, fees))
+        }
+
+        fn add_pending(
+            caller: T::AccountId,
+            destination: MultiLocation,
+            call: ReceiverCall<T::AccountId>,
+            error: SendError,
+        ) -> DispatchResult {
+            let count = <StoredPendingXcmCount<T>>::get();
+            ensure!(
+                count < T::MaxPendingXcm::get(),
+                Error::<T>::PendingXcmQueueFull
+            );
+            let id = <StoredNextPendingXcmId<T>>::get();
+            let next_retry =
+                <frame_system::Pallet<T>>::block_number().saturating_add(T::XcmRetryBackoff::get());
+            <StoredPendingXcm<T>>::insert(
+                id,
+                PendingXcm {
+                    caller,
+                    destination,
+                    call,
+                    error,
+                    attempts: 0,
+                    next_retry,
+                },
+            );
+            Self::schedule_retry(id, next_retry);
+            <StoredPendingXcmCount<T>>::put(count.saturating_add(1));
+            <StoredNextPendingXcmId<T>>::put(id.wrapping_add(1));
+            Self::deposit_event(Event::XcmQueued { id, next_retry });
+            Ok(())
+        }
+
+        fn remove_pending(id: u64, next_retry: T::BlockNumber) {
+            <StoredPendingXcm<T>>::remove(id);
+            <StoredPendingXcmRetry<T>>::remove(next_retry, id);
+            <StoredPendingXcmCount<T>>::mutate(|count| *count = count.saturating_sub(1));
+        }
+
+        /// Indexes the pending message `id` at `next_retry`, moving the cursor back if it already passed that block.
+        fn schedule_retry(id: u64, next_retry: T::BlockNumber) {
+            <StoredPendingXcmRetry<T>>::insert(next_retry, id, ());
+            <StoredPendingXcmRetryCursor<T>>::mutate(|cursor| {
+                if let Some(cursor) = cursor {
+                    if next_retry < *cursor {
+                        *cursor = next_retry;
+                    }
                 }
-                Err(error) => {
-                    Pallet::<T>::deposit_event(Event::XcmNotSent {
-                        call,
-                        caller,
-                        error,
+            });
+        }
+
+        /// The weight of a single retry, including the validation and delivery by the router.
+        pub(crate) fn retry_weight() -> Weight {
+            T::XcmSendWeight::get()
+                .saturating_add(Weight::from_ref_time(10_000))
+                .saturating_add(T::DbWeight::get().reads_writes(4, 5))
+        }
+
+        fn ensure_pending_caller(
+            id: u64,
+            who: &T::AccountId,
+        ) -> Result<PendingXcm<T::AccountId, T::BlockNumber>, Error<T>> {
+            let pending = <StoredPendingXcm<T>>::get(id).ok_or(Error::<T>::PendingXcmNotFound)?;
+            ensure!(&pending.caller == who, Error::<T>::NotPendingXcmCaller);
+            Ok(pending)
+        }
+
+        /// Retries the pending messages due at `block_number` within `max_weight`, in the order of the block they are due.
+        ///
+        /// The blocks of [StoredPendingXcmRetry] are visited from [StoredPendingXcmRetryCursor] on, so messages that are not
+        /// due yet are never read and a block whose messages did not all fit into `max_weight` is resumed in the next call.
+        fn retry_pending(block_number: T::BlockNumber, max_weight: Weight) -> Weight {
+            let weight_per_block = T::DbWeight::get().reads(1);
+            let weight_per_retry = Self::retry_weight();
+
+            // the count and cursor reads and the cursor write
+            let mut consumed = T::DbWeight::get().reads_writes(2, 1);
+            if consumed.any_gt(max_weight) {
+                return Weight::zero();
+            }
+            if <StoredPendingXcmCount<T>>::get() == 0 {
+                <StoredPendingXcmRetryCursor<T>>::kill();
+                return consumed;
+            }
+
+            let mut cursor = <StoredPendingXcmRetryCursor<T>>::get().unwrap_or(block_number);
+            let mut due = Vec::new();
+            while cursor <= block_number {
+                if consumed.saturating_add(weight_per_block).any_gt(max_weight) {
+                    break;
+                }
+                consumed = consumed.saturating_add(weight_per_block);
+                let mut ids = <StoredPendingXcmRetry<T>>::iter_key_prefix(cursor);
+                let complete = loop {
+                    match ids.next() {
+                        Some(id) => {
+                            if consumed.saturating_add(weight_per_retry).any_gt(max_weight) {
+                                break false;
+                            }
+                            consumed = consumed.saturating_add(weight_per_retry);
+                            due.push((cursor, id));
+                        }
+                        None => break true,
+                    }
+                };
+                if !complete {
+                    break;
+                }
+                cursor = cursor.saturating_add(One::one());
+            }
+            <StoredPendingXcmRetryCursor<T>>::put(cursor);
+
+            for (block, id) in due {
+                match <StoredPendingXcm<T>>::get(id) {
+                    Some(pending) => Self::retry(id, pending, block_number),
+                    None => <StoredPendingXcmRetry<T>>::remove(block, id),
+                }
+            }
+            consumed
+        }
+
+        /// Sends the pending message `id` again. Failed retries are rescheduled with a doubled backoff until
+        /// [Config::MaxXcmRetries] is reached and the message is given up.
+        fn retry(
+            id: u64,
+            mut pending: PendingXcm<T::AccountId, T::BlockNumber>,
+            block_number: T::BlockNumber,
+        ) {
+            let error = match Self::send_to(pending.destination, &pending.call) {
+                Ok(Ok((message_hash, fees))) => {
+                    Self::remove_pending(id, pending.next_retry);
+                    Self::deposit_event(Event::XcmResent {
+                        id,
+                        message_hash,
+                        fees,
                     });
-                    Err(Error::<T>::XcmError.into())
+                    return;
                 }
+                Ok(Err(error)) => error,
+                // the destination was valid when the message was added, so this only happens in case of a runtime upgrade
+                Err(_) => SendError::NotApplicable,
+            };
+
+            pending.attempts = pending.attempts.saturating_add(1);
+            if pending.attempts >= T::MaxXcmRetries::get() {
+                Self::remove_pending(id, pending.next_retry);
+                Self::deposit_event(Event::XcmGivenUp {
+                    id,
+                    call: pending.call,
+                    caller: pending.caller,
+                    error,
+                });
+                return;
             }
+
+            let backoff = T::XcmRetryBackoff::get().saturating_mul(
+                T::BlockNumber::from(2u32).saturating_pow(pending.attempts as usize),
+            );
+            <StoredPendingXcmRetry<T>>::remove(pending.next_retry, id);
+            pending.next_retry = block_number.saturating_add(backoff);
+            pending.error = error.clone();
+            let next_retry = pending.next_retry;
+            let attempts = pending.attempts;
+            <StoredPendingXcm<T>>::insert(id, pending);
+            Self::schedule_retry(id, next_retry);
+            Self::deposit_event(Event::XcmRetryFailed {
+                id,
+                error,
+                attempts,
+                next_retry,
+            });
         }
     }
 
@@ -91,13 +485,43 @@ pub mod pallet {
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         XcmSent {
-            call: ReceiverCall,
+            call: ReceiverCall<T::AccountId>,
             caller: T::AccountId,
+            message_hash: XcmHash,
+            fees: MultiAssets,
         },
         XcmNotSent {
-            call: ReceiverCall,
+            call: ReceiverCall<T::AccountId>,
+            caller: T::AccountId,
+            error: SendError,
+        },
+        XcmExecutionConfigUpdated {
+            destination: MultiLocation,
+            config: Option<XcmExecutionConfig>,
+        },
+        /// A message that could not be sent was added to the pending messages with `id`, retried from `next_retry` on.
+        XcmQueued { id: u64, next_retry: T::BlockNumber },
+        /// The pending message `id` was sent on a retry.
+        XcmResent {
+            id: u64,
+            message_hash: XcmHash,
+            fees: MultiAssets,
+        },
+        /// A retry of the pending message `id` failed, it is retried again from `next_retry` on.
+        XcmRetryFailed {
+            id: u64,
+            error: SendError,
+            attempts: u32,
+            next_retry: T::BlockNumber,
+        },
+        /// The pending message `id` was removed after [Config::MaxXcmRetries] failed retries.
+        XcmGivenUp {
+            id: u64,
+            call: ReceiverCall<T::AccountId>,
             caller: T::AccountId,
             error: SendError,
         },
+        /// The pending message `id` was cancelled by its caller.
+        XcmCancelled { id: u64 },
     }
 }
@@ -0,0 +1,76 @@
+use frame_support::{
+    traits::{Get, GetStorageVersion},
+    weights::Weight,
+};
+
+use crate::{Config, Pallet};
+
+/// Translates the [crate::StoredXcmExecutionConfig] keys and values from XCM v2 to XCM v3, which is used since version 1.
+///
+/// Configurations whose locations cannot be expressed in XCM v3 are dropped and have to be set again.
+pub mod v1 {
+    use frame_support::{log, pallet_prelude::*, storage_alias};
+    use sp_std::prelude::*;
+    use xcm::{v2::MultiLocation as OldMultiLocation, v3::MultiLocation};
+
+    use super::*;
+    use crate::XcmExecutionConfig;
+
+    /// The execution configuration as stored before version 1.
+    #[derive(Encode, Decode)]
+    pub struct OldXcmExecutionConfig {
+        pub fee_asset: OldMultiLocation,
+        pub fee_amount: u128,
+        pub weight_limit: u64,
+    }
+
+    /// The execution configurations keyed by XCM v2 locations as stored before version 1.
+    #[storage_alias]
+    pub(crate) type StoredXcmExecutionConfig<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, OldMultiLocation, OldXcmExecutionConfig>;
+
+    pub fn migrate<T: Config>() -> Weight {
+        let configs = StoredXcmExecutionConfig::<T>::drain().collect::<Vec<_>>();
+        let count = configs.len() as u64;
+
+        let mut failed: u32 = 0;
+        for (destination, config) in configs {
+            match (
+                MultiLocation::try_from(destination),
+                MultiLocation::try_from(config.fee_asset),
+            ) {
+                (Ok(destination), Ok(fee_asset)) => <crate::StoredXcmExecutionConfig<T>>::insert(
+                    &destination,
+                    XcmExecutionConfig {
+                        fee_asset,
+                        fee_amount: config.fee_amount,
+                        weight_limit: Weight::from_ref_time(config.weight_limit),
+                    },
+                ),
+                _ => failed += 1,
+            }
+        }
+
+        if failed > 0 {
+            log::warn!(
+                target: "runtime::acurast_xcm_sender",
+                "dropped {} execution configs not expressible in XCM v3",
+                failed
+            );
+        }
+        T::DbWeight::get().reads_writes(count, count.saturating_mul(2))
+    }
+}
+
+/// Runs the migrations required to bring the storage of the pallet to its in-code version.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+
+    if on_chain_version < 1 {
+        weight = weight.saturating_add(v1::migrate::<T>());
+    }
+
+    Pallet::<T>::current_storage_version().put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
@@ -2,7 +2,7 @@ use codec::{Decode, Encode};
 use frame_support::traits::Get;
 use frame_support::{
     construct_runtime, parameter_types,
-    traits::{ConstU32, Everything, Nothing},
+    traits::{ConstU32, ConstU64, Everything, Nothing},
     weights::Weight,
     BoundedVec,
 };
@@ -21,11 +21,12 @@ use polkadot_parachain::primitives::{
 };
 use xcm::{latest::prelude::*, VersionedXcm};
 use xcm_builder::{
-    AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds,
-    LocationInverter, NativeAsset, SignedAccountId32AsNative, SignedToAccountId32,
+    AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds, NativeAsset,
+    SignedAccountId32AsNative, SignedToAccountId32,
 };
 use xcm_executor::{Config, XcmExecutor};
 pub type AccountId = AccountId32;
+pub type Balance = u128;
 
 impl frame_system::Config for Runtime {
     type RuntimeOrigin = RuntimeOrigin;
@@ -43,7 +44,7 @@ impl frame_system::Config for Runtime {
     type BlockLength = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type DbWeight = ();
@@ -57,7 +58,7 @@ impl frame_system::Config for Runtime {
 parameter_types! {
     pub const KsmLocation: MultiLocation = MultiLocation::parent();
     pub const RelayNetwork: NetworkId = NetworkId::Kusama;
-    pub Ancestry: MultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
+    pub UniversalLocation: InteriorMultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
 }
 
 pub type XcmOriginToCallOrigin = (
@@ -66,9 +67,10 @@ pub type XcmOriginToCallOrigin = (
 );
 
 parameter_types! {
-    pub const UnitWeightCost: u64 = 1;
-    pub KsmPerSecond: (AssetId, u128) = (Concrete(Parent.into()), 1);
+    pub const UnitWeightCost: Weight = Weight::from_ref_time(1);
+    pub KsmPerSecondPerByte: (AssetId, u128, u128) = (Concrete(Parent.into()), 1, 1);
     pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsIntoHolding: u32 = 64;
 }
 
 pub type XcmRouter = crate::tests::ParachainXcmRouter<MsgQueue>;
@@ -83,14 +85,23 @@ impl Config for XcmConfig {
     type OriginConverter = XcmOriginToCallOrigin;
     type IsReserve = NativeAsset;
     type IsTeleporter = ();
-    type LocationInverter = LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type Barrier = Barrier;
     type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-    type Trader = FixedRateOfFungible<KsmPerSecond, ()>;
+    type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
     type ResponseHandler = ();
     type AssetTrap = ();
     type AssetClaims = ();
     type SubscriptionService = ();
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type PalletInstancesInfo = ();
+    type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+    type FeeManager = ();
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
 }
 
 #[frame_support::pallet]
@@ -163,19 +174,16 @@ pub mod mock_msg_queue {
             max_weight: Weight,
         ) -> Result<Weight, XcmError> {
             let hash = Encode::using_encoded(&xcm, T::Hashing::hash);
+            let message_hash = Encode::using_encoded(&xcm, sp_io::hashing::blake2_256);
             let (result, event) = match Xcm::<T::RuntimeCall>::try_from(xcm) {
                 Ok(xcm) => {
                     let location = (1, Parachain(sender.into()));
-                    match T::XcmExecutor::execute_xcm(location, xcm, max_weight.ref_time()) {
+                    match T::XcmExecutor::execute_xcm(location, xcm, message_hash, max_weight) {
                         Outcome::Error(e) => (Err(e.clone()), Event::Fail(Some(hash), e)),
-                        Outcome::Complete(w) => {
-                            (Ok(Weight::from_ref_time(w)), Event::Success(Some(hash)))
-                        }
+                        Outcome::Complete(w) => (Ok(w), Event::Success(Some(hash))),
                         // As far as the caller is concerned, this was dispatched without error, so
                         // we just report the weight used.
-                        Outcome::Incomplete(w, e) => {
-                            (Ok(Weight::from_ref_time(w)), Event::Fail(Some(hash), e))
-                        }
+                        Outcome::Incomplete(w, e) => (Ok(w), Event::Fail(Some(hash), e)),
                     }
                 }
                 Err(()) => (
@@ -230,8 +238,7 @@ pub mod mock_msg_queue {
                         Self::deposit_event(Event::UnsupportedVersion(id));
                     }
                     Ok(Ok(x)) => {
-                        let outcome =
-                            T::XcmExecutor::execute_xcm(Parent, x.clone(), limit.ref_time());
+                        let outcome = T::XcmExecutor::execute_xcm(Parent, x.clone(), id, limit);
                         <ReceivedDmp<T>>::append(x);
                         Self::deposit_event(Event::ExecutedDownward(id, outcome));
                     }
@@ -259,11 +266,34 @@ impl pallet_xcm::Config for Runtime {
     type XcmTeleportFilter = Nothing;
     type XcmReserveTransferFilter = Everything;
     type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-    type LocationInverter = LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
     const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
     type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = ();
+    type TrustedLockers = ();
+    type SovereignAccountOf = ();
+    type MaxLockers = ConstU32<8>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+    pub const MaxLocks: u32 = 50;
+    pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = MaxLocks;
+    type MaxReserves = MaxReserves;
+    type ReserveIdentifier = [u8; 8];
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -286,6 +316,7 @@ impl pallet_acurast_receiver::Config for Runtime {
     type Parameters = BoundedVec<u8, ConstU32<128>>;
     type OnFulfillment = OnFulfillment;
     type Barrier = AllowAcurastBarrier;
+    type FulfillmentRetention = ConstU64<100>;
 }
 
 pub struct OnFulfillment;
@@ -305,8 +336,9 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         MsgQueue: mock_msg_queue::{Pallet, Storage, Event<T>},
-        PolkadotXcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
+        PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config},
         AcurastReceiver: pallet_acurast_receiver::{Pallet, Call, Storage, Event<T>} = 130
     }
 );
@@ -1,18 +1,19 @@
 use frame_support::{
     construct_runtime, parameter_types,
     sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32},
-    traits::{Everything, Nothing},
+    traits::{ConstU32, Everything, Nothing},
+    weights::Weight,
 };
 use polkadot_runtime_parachains::{configuration, shared, ump};
 use sp_core::H256;
 use xcm::latest::prelude::*;
 use xcm_builder::{
-    AllowUnpaidExecutionFrom, FixedRateOfFungible, FixedWeightBounds, LocationInverter,
-    SignedToAccountId32,
+    AllowUnpaidExecutionFrom, FixedRateOfFungible, FixedWeightBounds, SignedToAccountId32,
 };
 use xcm_executor::{Config, XcmExecutor};
 
 pub type AccountId = AccountId32;
+pub type Balance = u128;
 
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
@@ -34,7 +35,7 @@ impl frame_system::Config for Runtime {
     type BlockLength = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type DbWeight = ();
@@ -54,14 +55,15 @@ impl configuration::Config for Runtime {
 parameter_types! {
     pub const KsmLocation: MultiLocation = Here.into();
     pub const KusamaNetwork: NetworkId = NetworkId::Kusama;
-    pub const AnyNetwork: NetworkId = NetworkId::Any;
-    pub Ancestry: MultiLocation = Here.into();
+    pub const AnyNetwork: Option<NetworkId> = None;
+    pub UniversalLocation: InteriorMultiLocation = Here;
 }
 
 parameter_types! {
-    pub const BaseXcmWeight: u64 = 1_000;
-    pub KsmPerSecond: (AssetId, u128) = (Concrete(KsmLocation::get()), 1);
+    pub const BaseXcmWeight: Weight = Weight::from_ref_time(1_000);
+    pub KsmPerSecondPerByte: (AssetId, u128, u128) = (Concrete(KsmLocation::get()), 1, 1);
     pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsIntoHolding: u32 = 64;
 }
 
 pub type XcmRouter = crate::tests::RelayChainXcmRouter;
@@ -75,14 +77,23 @@ impl Config for XcmConfig {
     type OriginConverter = ();
     type IsReserve = ();
     type IsTeleporter = ();
-    type LocationInverter = LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type Barrier = Barrier;
     type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
-    type Trader = FixedRateOfFungible<KsmPerSecond, ()>;
+    type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
     type ResponseHandler = ();
     type AssetTrap = ();
     type AssetClaims = ();
     type SubscriptionService = ();
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type PalletInstancesInfo = ();
+    type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+    type FeeManager = ();
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
 }
 
 pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, KusamaNetwork>;
@@ -98,11 +109,16 @@ impl pallet_xcm::Config for Runtime {
     type XcmTeleportFilter = Everything;
     type XcmReserveTransferFilter = Everything;
     type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
-    type LocationInverter = LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
     const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
     type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = ();
+    type TrustedLockers = ();
+    type SovereignAccountOf = ();
+    type MaxLockers = ConstU32<8>;
 }
 
 parameter_types! {
@@ -117,6 +133,24 @@ impl ump::Config for Runtime {
     type WeightInfo = ump::TestWeightInfo;
 }
 
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+    pub const MaxLocks: u32 = 50;
+    pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = MaxLocks;
+    type MaxReserves = MaxReserves;
+    type ReserveIdentifier = [u8; 8];
+}
+
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
 type Block = frame_system::mocking::MockBlock<Runtime>;
 
@@ -127,6 +161,7 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         ParasUmp: ump::{Pallet, Call, Storage, Event},
         XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin},
     }
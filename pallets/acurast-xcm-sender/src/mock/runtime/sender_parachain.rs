@@ -7,7 +7,7 @@ use frame_support::{
         traits::{AccountIdLookup, Hash},
         AccountId32,
     },
-    traits::{Everything, Nothing},
+    traits::{ConstU32, Everything, Nothing},
     weights::Weight,
 };
 pub use pallet_acurast_receiver;
@@ -21,11 +21,12 @@ use sp_std::prelude::*;
 use xcm::{latest::prelude::*, VersionedXcm};
 use xcm_builder::{
     AccountId32Aliases, AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedRateOfFungible,
-    FixedWeightBounds, LocationInverter, NativeAsset, ParentIsPreset, SiblingParachainConvertsVia,
+    FixedWeightBounds, NativeAsset, ParentIsPreset, SiblingParachainConvertsVia,
     SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
 };
 use xcm_executor::{Config, XcmExecutor};
 pub type AccountId = AccountId32;
+pub type Balance = u128;
 
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
@@ -47,7 +48,7 @@ impl frame_system::Config for Runtime {
     type BlockLength = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type DbWeight = ();
@@ -61,7 +62,7 @@ impl frame_system::Config for Runtime {
 parameter_types! {
     pub const KsmLocation: MultiLocation = MultiLocation::parent();
     pub const RelayNetwork: NetworkId = NetworkId::Kusama;
-    pub Ancestry: MultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
+    pub UniversalLocation: InteriorMultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
 }
 
 pub type LocationToAccountId = (
@@ -77,9 +78,10 @@ pub type XcmOriginToCallOrigin = (
 );
 
 parameter_types! {
-    pub const UnitWeightCost: u64 = 1;
-    pub KsmPerSecond: (AssetId, u128) = (Concrete(Parent.into()), 1);
+    pub const UnitWeightCost: Weight = Weight::from_ref_time(1);
+    pub KsmPerSecondPerByte: (AssetId, u128, u128) = (Concrete(Parent.into()), 1, 1);
     pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsIntoHolding: u32 = 64;
 }
 
 pub type XcmRouter = crate::tests::ParachainXcmRouter<MsgQueue>;
@@ -94,14 +96,23 @@ impl Config for XcmConfig {
     type OriginConverter = XcmOriginToCallOrigin;
     type IsReserve = NativeAsset;
     type IsTeleporter = ();
-    type LocationInverter = LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type Barrier = Barrier;
     type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-    type Trader = FixedRateOfFungible<KsmPerSecond, ()>;
+    type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
     type ResponseHandler = ();
     type AssetTrap = ();
     type AssetClaims = ();
     type SubscriptionService = ();
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type PalletInstancesInfo = ();
+    type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+    type FeeManager = ();
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
 }
 
 #[frame_support::pallet]
@@ -174,19 +185,16 @@ pub mod mock_msg_queue {
             max_weight: Weight,
         ) -> Result<Weight, XcmError> {
             let hash = Encode::using_encoded(&xcm, T::Hashing::hash);
+            let message_hash = Encode::using_encoded(&xcm, sp_io::hashing::blake2_256);
             let (result, event) = match Xcm::<T::RuntimeCall>::try_from(xcm) {
                 Ok(xcm) => {
                     let location = (1, Parachain(sender.into()));
-                    match T::XcmExecutor::execute_xcm(location, xcm, max_weight.ref_time()) {
+                    match T::XcmExecutor::execute_xcm(location, xcm, message_hash, max_weight) {
                         Outcome::Error(e) => (Err(e.clone()), Event::Fail(Some(hash), e)),
-                        Outcome::Complete(w) => {
-                            (Ok(Weight::from_ref_time(w)), Event::Success(Some(hash)))
-                        }
+                        Outcome::Complete(w) => (Ok(w), Event::Success(Some(hash))),
                         // As far as the caller is concerned, this was dispatched without error, so
                         // we just report the weight used.
-                        Outcome::Incomplete(w, e) => {
-                            (Ok(Weight::from_ref_time(w)), Event::Fail(Some(hash), e))
-                        }
+                        Outcome::Incomplete(w, e) => (Ok(w), Event::Fail(Some(hash), e)),
                     }
                 }
                 Err(()) => (
@@ -241,8 +249,7 @@ pub mod mock_msg_queue {
                         Self::deposit_event(Event::UnsupportedVersion(id));
                     }
                     Ok(Ok(x)) => {
-                        let outcome =
-                            T::XcmExecutor::execute_xcm(Parent, x.clone(), limit.ref_time());
+                        let outcome = T::XcmExecutor::execute_xcm(Parent, x.clone(), id, limit);
                         <ReceivedDmp<T>>::append(x);
                         Self::deposit_event(Event::ExecutedDownward(id, outcome));
                     }
@@ -270,11 +277,34 @@ impl pallet_xcm::Config for Runtime {
     type XcmTeleportFilter = Nothing;
     type XcmReserveTransferFilter = Everything;
     type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-    type LocationInverter = LocationInverter<Ancestry>;
+    type UniversalLocation = UniversalLocation;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
     const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
     type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = ();
+    type TrustedLockers = ();
+    type SovereignAccountOf = LocationToAccountId;
+    type MaxLockers = ConstU32<8>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+    pub const MaxLocks: u32 = 50;
+    pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = MaxLocks;
+    type MaxReserves = MaxReserves;
+    type ReserveIdentifier = [u8; 8];
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -283,11 +313,24 @@ type Block = frame_system::mocking::MockBlock<Runtime>;
 parameter_types! {
     pub const AcurastParachainId: u32 = 2000;
     pub const AcurastReceiverPalletId: u8 = 130;
+    pub SelfLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(MsgQueue::parachain_id().into())));
+    pub const DefaultTransactWeight: Weight = Weight::from_ref_time(1_000_000_000);
+    pub const MaxPendingXcm: u32 = 2;
+    pub const MaxXcmRetries: u32 = 2;
+    pub const XcmRetryBackoff: u64 = 2;
+    pub const XcmSendWeight: Weight = Weight::from_ref_time(1_000_000);
 }
 
 impl crate::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type XcmSender = XcmRouter;
+    type VersionWrapper = PolkadotXcm;
+    type SelfLocation = SelfLocation;
+    type DefaultTransactWeight = DefaultTransactWeight;
+    type MaxPendingXcm = MaxPendingXcm;
+    type MaxXcmRetries = MaxXcmRetries;
+    type XcmRetryBackoff = XcmRetryBackoff;
+    type XcmSendWeight = XcmSendWeight;
 }
 
 construct_runtime!(
@@ -297,8 +340,9 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         MsgQueue: mock_msg_queue::{Pallet, Storage, Event<T>},
-        PolkadotXcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
-        AcurastSender: crate::{Pallet, Event<T>},
+        PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config},
+        AcurastSender: crate::{Pallet, Call, Storage, Event<T>},
     }
 );
@@ -1,3 +1,4 @@
+use frame_support::traits::GenesisBuild;
 use xcm_simulator::{decl_test_network, decl_test_parachain, decl_test_relay_chain};
 
 decl_test_parachain! {
@@ -39,10 +40,19 @@ decl_test_network! {
 pub fn acurast_parachain_ext(para_id: u32) -> sp_io::TestExternalities {
     use crate::mock::runtime::sender_parachain::{MsgQueue, Runtime, System};
 
-    let storage = frame_system::GenesisConfig::default()
+    let mut storage = frame_system::GenesisConfig::default()
         .build_storage::<Runtime>()
         .unwrap();
 
+    // destinations that did not negotiate their version yet receive XCM v2
+    <pallet_xcm::GenesisConfig as GenesisBuild<Runtime>>::assimilate_storage(
+        &pallet_xcm::GenesisConfig {
+            safe_xcm_version: Some(2),
+        },
+        &mut storage,
+    )
+    .unwrap();
+
     let mut ext = sp_io::TestExternalities::new(storage);
     ext.execute_with(|| {
         System::set_block_number(1);
@@ -81,8 +91,8 @@ pub fn relay_ext() -> sp_io::TestExternalities {
 #[cfg(test)]
 mod proxy_calls {
     use super::*;
-    use frame_support::assert_ok;
-    use xcm_simulator::{Junction, TestExt};
+    use frame_support::{assert_err, assert_ok, traits::Hooks, weights::Weight};
+    use xcm_simulator::{Junction, MultiLocation, TestExt};
 
     #[test]
     fn fulfill() {
@@ -118,4 +128,548 @@ mod proxy_calls {
             )));
         });
     }
+
+    #[test]
+    fn fulfill_job() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+        let script: acurast_common::Script = vec![0u8; 53].try_into().unwrap();
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::AcurastSender;
+
+            let payload = [0u8; 10];
+
+            assert_ok!(AcurastSender::send_job_fulfillment(
+                bob.clone(),
+                (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                (bob.clone(), script.clone()),
+                payload.to_vec(),
+                None,
+                Some(0),
+            ));
+        });
+
+        OtherParachain::execute_with(|| {
+            use crate::mock::runtime::receiver_parachain::{AcurastReceiver, RuntimeEvent, System};
+            use pallet_acurast_receiver::Event::JobFulfillReceived;
+
+            // Check emitted events
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastReceiver(JobFulfillReceived(..))
+            )));
+            assert!(AcurastReceiver::stored_requester_job(bob.clone(), script.clone()).is_some());
+        });
+    }
+
+    #[test]
+    fn fulfill_with_execution_config() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+        let config = crate::XcmExecutionConfig {
+            fee_asset: MultiLocation::parent(),
+            fee_amount: 1_000,
+            weight_limit: Weight::from_ref_time(2_000_000_000),
+        };
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, RuntimeEvent, RuntimeOrigin, System,
+            };
+
+            assert_ok!(AcurastSender::set_xcm_execution_config(
+                RuntimeOrigin::root(),
+                (1, Junction::Parachain(2000)).into(),
+                Some(config.clone()),
+            ));
+            assert_eq!(
+                Some(config.clone()),
+                AcurastSender::stored_xcm_execution_config(MultiLocation::from((
+                    1,
+                    Junction::Parachain(2000)
+                )))
+            );
+
+            assert_ok!(AcurastSender::send(
+                bob,
+                (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                [0u8; 10].to_vec(),
+                None,
+            ));
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastSender(crate::Event::XcmSent { .. })
+            )));
+        });
+    }
+
+    #[test]
+    fn migrate_execution_config_to_v3() {
+        use frame_support::traits::{GetStorageVersion, StorageVersion};
+        use xcm::v2::{Junction::Parachain, Junctions::X1};
+
+        Network::reset();
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{AcurastSender, Runtime};
+
+            StorageVersion::new(0).put::<AcurastSender>();
+            crate::migrations::v1::StoredXcmExecutionConfig::<Runtime>::insert(
+                xcm::v2::MultiLocation::new(1, X1(Parachain(2000))),
+                crate::migrations::v1::OldXcmExecutionConfig {
+                    fee_asset: xcm::v2::MultiLocation::parent(),
+                    fee_amount: 1_000,
+                    weight_limit: 2_000_000_000,
+                },
+            );
+
+            crate::migrations::migrate::<Runtime>();
+
+            assert_eq!(
+                Some(crate::XcmExecutionConfig {
+                    fee_asset: MultiLocation::parent(),
+                    fee_amount: 1_000,
+                    weight_limit: Weight::from_ref_time(2_000_000_000),
+                }),
+                AcurastSender::stored_xcm_execution_config(MultiLocation::from((
+                    1,
+                    Junction::Parachain(2000)
+                )))
+            );
+            assert_eq!(
+                StorageVersion::new(1),
+                AcurastSender::on_chain_storage_version()
+            );
+        });
+    }
+
+    #[test]
+    fn fulfill_with_negotiated_version() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeOrigin,
+            };
+
+            assert_ok!(PolkadotXcm::force_xcm_version(
+                RuntimeOrigin::root(),
+                Box::new((1, Junction::Parachain(2000)).into()),
+                3,
+            ));
+
+            assert_ok!(AcurastSender::send(
+                bob,
+                (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                [0u8; 10].to_vec(),
+                None,
+            ));
+        });
+
+        OtherParachain::execute_with(|| {
+            use crate::mock::runtime::receiver_parachain::{RuntimeEvent, System};
+            use pallet_acurast_receiver::Event::FulfillReceived;
+
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastReceiver(FulfillReceived(..))
+            )));
+        });
+    }
+
+    #[test]
+    fn fulfill_unknown_version() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeEvent, RuntimeOrigin, System,
+            };
+
+            // without a safe version, messages are not sent to destinations with an unknown version
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+
+            // the message is kept for a retry
+            assert_ok!(AcurastSender::send(
+                bob.clone(),
+                (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                [0u8; 10].to_vec(),
+                None,
+            ));
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastSender(crate::Event::XcmNotSent {
+                    error: xcm::v3::SendError::DestinationUnsupported,
+                    ..
+                })
+            )));
+            assert_eq!(
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmQueued {
+                    id: 0,
+                    next_retry: 3
+                })),
+                System::events().pop().map(|record| record.event)
+            );
+
+            let pending = AcurastSender::stored_pending_xcm(0).unwrap();
+            assert_eq!(bob, pending.caller);
+            assert_eq!(xcm::v3::SendError::DestinationUnsupported, pending.error);
+            assert_eq!(0, pending.attempts);
+            assert_eq!(1, AcurastSender::stored_pending_xcm_count());
+        });
+    }
+
+    #[test]
+    fn retry_pending_xcm() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeEvent, RuntimeOrigin, System,
+            };
+
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+            assert_ok!(AcurastSender::send(
+                bob,
+                (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                [0u8; 10].to_vec(),
+                None,
+            ));
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                Some(2),
+            ));
+
+            // not due yet
+            AcurastSender::on_idle(2, Weight::MAX);
+            assert!(AcurastSender::stored_pending_xcm(0).is_some());
+
+            System::set_block_number(3);
+            AcurastSender::on_idle(3, Weight::MAX);
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastSender(crate::Event::XcmResent { id: 0, .. })
+            )));
+            assert_eq!(None, AcurastSender::stored_pending_xcm(0));
+            assert_eq!(0, AcurastSender::stored_pending_xcm_count());
+        });
+
+        OtherParachain::execute_with(|| {
+            use crate::mock::runtime::receiver_parachain::{RuntimeEvent, System};
+            use pallet_acurast_receiver::Event::FulfillReceived;
+
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastReceiver(FulfillReceived(..))
+            )));
+        });
+    }
+
+    #[test]
+    fn retry_pending_xcm_by_due_block() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeEvent, RuntimeOrigin, System,
+            };
+
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+            for _ in 0..2 {
+                assert_ok!(AcurastSender::send(
+                    bob.clone(),
+                    (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                    [0u8; 10].to_vec(),
+                    None,
+                ));
+            }
+            // the first message is postponed to block 5 by a failed retry
+            assert_ok!(AcurastSender::retry_xcm(
+                RuntimeOrigin::signed(bob.clone()),
+                0
+            ));
+            assert_eq!(Some(()), AcurastSender::stored_pending_xcm_retry(5, 0));
+            assert_eq!(None, AcurastSender::stored_pending_xcm_retry(3, 0));
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                Some(2),
+            ));
+
+            // the message that is not due yet does not use up the weight of the due one
+            System::set_block_number(3);
+            AcurastSender::on_idle(3, AcurastSender::retry_weight());
+            assert!(matches!(
+                System::events().pop().map(|record| record.event),
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmResent {
+                    id: 1,
+                    ..
+                }))
+            ));
+            assert!(AcurastSender::stored_pending_xcm(0).is_some());
+            assert_eq!(Some(4), AcurastSender::stored_pending_xcm_retry_cursor());
+
+            System::set_block_number(5);
+            AcurastSender::on_idle(5, AcurastSender::retry_weight());
+            assert!(matches!(
+                System::events().pop().map(|record| record.event),
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmResent {
+                    id: 0,
+                    ..
+                }))
+            ));
+            assert_eq!(0, AcurastSender::stored_pending_xcm_count());
+
+            AcurastSender::on_idle(6, Weight::MAX);
+            assert_eq!(None, AcurastSender::stored_pending_xcm_retry_cursor());
+        });
+    }
+
+    #[test]
+    fn retry_pending_xcm_resumes_block() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeOrigin, System,
+            };
+
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+            for _ in 0..2 {
+                assert_ok!(AcurastSender::send(
+                    bob.clone(),
+                    (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                    [0u8; 10].to_vec(),
+                    None,
+                ));
+            }
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                Some(2),
+            ));
+
+            // only one of the due messages fits into the weight
+            System::set_block_number(3);
+            assert_eq!(
+                AcurastSender::retry_weight(),
+                AcurastSender::on_idle(3, AcurastSender::retry_weight())
+            );
+            assert_eq!(1, AcurastSender::stored_pending_xcm_count());
+            assert_eq!(Some(3), AcurastSender::stored_pending_xcm_retry_cursor());
+
+            System::set_block_number(4);
+            AcurastSender::on_idle(4, AcurastSender::retry_weight());
+            assert_eq!(0, AcurastSender::stored_pending_xcm_count());
+        });
+    }
+
+    #[test]
+    fn give_up_pending_xcm() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeEvent, RuntimeOrigin, System,
+            };
+
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+            assert_ok!(AcurastSender::send(
+                bob.clone(),
+                (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                [0u8; 10].to_vec(),
+                None,
+            ));
+
+            System::set_block_number(3);
+            AcurastSender::on_idle(3, Weight::MAX);
+            // the backoff doubles with every failed retry
+            assert_eq!(
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmRetryFailed {
+                    id: 0,
+                    error: xcm::v3::SendError::DestinationUnsupported,
+                    attempts: 1,
+                    next_retry: 7,
+                })),
+                System::events().pop().map(|record| record.event)
+            );
+
+            System::set_block_number(7);
+            AcurastSender::on_idle(7, Weight::MAX);
+            assert!(matches!(
+                System::events().pop().map(|record| record.event),
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmGivenUp {
+                    id: 0,
+                    caller,
+                    error: xcm::v3::SendError::DestinationUnsupported,
+                    ..
+                })) if caller == bob
+            ));
+            assert_eq!(None, AcurastSender::stored_pending_xcm(0));
+            assert_eq!(0, AcurastSender::stored_pending_xcm_count());
+        });
+    }
+
+    #[test]
+    fn pending_xcm_queue_full() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, RuntimeOrigin,
+            };
+
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+            for _ in 0..2 {
+                assert_ok!(AcurastSender::send(
+                    bob.clone(),
+                    (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                    [0u8; 10].to_vec(),
+                    None,
+                ));
+            }
+            assert_err!(
+                AcurastSender::send(
+                    bob,
+                    (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                    [0u8; 10].to_vec(),
+                    None,
+                ),
+                crate::Error::<crate::mock::runtime::sender_parachain::Runtime>::PendingXcmQueueFull
+            );
+        });
+    }
+
+    #[test]
+    fn retry_and_cancel_pending_xcm() {
+        Network::reset();
+
+        let bob = frame_support::sp_runtime::AccountId32::new([0u8; 32]);
+        let alice = frame_support::sp_runtime::AccountId32::new([1u8; 32]);
+
+        AcurastParachain::execute_with(|| {
+            use crate::mock::runtime::sender_parachain::{
+                AcurastSender, PolkadotXcm, Runtime, RuntimeEvent, RuntimeOrigin, System,
+            };
+
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                None,
+            ));
+            for _ in 0..2 {
+                assert_ok!(AcurastSender::send(
+                    bob.clone(),
+                    (1, Junction::Parachain(2000), Junction::PalletInstance(130)).into(),
+                    [0u8; 10].to_vec(),
+                    None,
+                ));
+            }
+
+            assert_err!(
+                AcurastSender::retry_xcm(RuntimeOrigin::signed(alice.clone()), 0),
+                crate::Error::<Runtime>::NotPendingXcmCaller
+            );
+            assert_err!(
+                AcurastSender::cancel_xcm(RuntimeOrigin::signed(alice), 1),
+                crate::Error::<Runtime>::NotPendingXcmCaller
+            );
+
+            // a forced retry does not wait for the backoff
+            assert_ok!(PolkadotXcm::force_default_xcm_version(
+                RuntimeOrigin::root(),
+                Some(2),
+            ));
+            assert_ok!(AcurastSender::retry_xcm(
+                RuntimeOrigin::signed(bob.clone()),
+                0
+            ));
+            assert!(matches!(
+                System::events().pop().map(|record| record.event),
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmResent {
+                    id: 0,
+                    ..
+                }))
+            ));
+
+            assert_ok!(AcurastSender::cancel_xcm(
+                RuntimeOrigin::signed(bob.clone()),
+                1
+            ));
+            assert_eq!(
+                Some(RuntimeEvent::AcurastSender(crate::Event::XcmCancelled {
+                    id: 1
+                })),
+                System::events().pop().map(|record| record.event)
+            );
+            assert_eq!(None, AcurastSender::stored_pending_xcm(1));
+            assert_eq!(0, AcurastSender::stored_pending_xcm_count());
+            assert_err!(
+                AcurastSender::cancel_xcm(RuntimeOrigin::signed(bob), 1),
+                crate::Error::<Runtime>::PendingXcmNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn execution_config_program() {
+        use xcm::v3::{prelude::*, Instruction};
+
+        let config = crate::XcmExecutionConfig {
+            fee_asset: MultiLocation::parent(),
+            fee_amount: 1_000,
+            weight_limit: Weight::from_ref_time(2_000_000_000),
+        };
+        let beneficiary: MultiLocation = (1, Junction::Parachain(2001)).into();
+        let program: Xcm<()> = config.program(vec![130, 0], beneficiary);
+
+        assert!(matches!(
+            program.0.as_slice(),
+            [
+                WithdrawAsset(_),
+                BuyExecution { .. },
+                Instruction::Transact {
+                    require_weight_at_most,
+                    ..
+                },
+                RefundSurplus,
+                DepositAsset { beneficiary: b, .. },
+            ] if require_weight_at_most == &Weight::from_ref_time(2_000_000_000) && b == &beneficiary
+        ));
+
+        // the program can be sent to destinations that only support XCM v2
+        assert!(xcm::v2::Xcm::<()>::try_from(program).is_ok());
+    }
 }
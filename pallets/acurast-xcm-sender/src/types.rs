@@ -0,0 +1,44 @@
+use frame_support::pallet_prelude::*;
+use sp_std::prelude::*;
+use xcm::v3::{
+    Instruction::{BuyExecution, DepositAsset, RefundSurplus, Transact, WithdrawAsset},
+    MultiAsset, MultiAssetFilter, MultiLocation, OriginKind, WeightLimit, WildMultiAsset, Xcm,
+};
+
+/// Configures how messages sent to a destination pay for their execution.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct XcmExecutionConfig {
+    /// The concrete fungible asset used to pay for the execution, as seen from the destination.
+    pub fee_asset: MultiLocation,
+    /// The amount of `fee_asset` withdrawn from this chain's sovereign account on the destination.
+    pub fee_amount: u128,
+    /// The maximum weight the `Transact` is allowed to use on the destination.
+    pub weight_limit: Weight,
+}
+
+impl XcmExecutionConfig {
+    /// Builds the program executing the encoded `call` on the destination, paid with the configured fee.
+    ///
+    /// The unused fee is deposited back to `beneficiary`, which should be the location of this chain as seen from the destination.
+    /// The program only uses instructions that can be converted to XCM v2.
+    pub fn program<Call>(&self, call: Vec<u8>, beneficiary: MultiLocation) -> Xcm<Call> {
+        let fees: MultiAsset = (self.fee_asset, self.fee_amount).into();
+        Xcm(vec![
+            WithdrawAsset(fees.clone().into()),
+            BuyExecution {
+                fees,
+                weight_limit: WeightLimit::Unlimited,
+            },
+            Transact {
+                origin_kind: OriginKind::Xcm,
+                require_weight_at_most: self.weight_limit,
+                call: call.into(),
+            },
+            RefundSurplus,
+            DepositAsset {
+                assets: MultiAssetFilter::Wild(WildMultiAsset::AllCounted(1)),
+                beneficiary,
+            },
+        ])
+    }
+}
@@ -2,19 +2,25 @@
 
 pub mod asn;
 pub mod error;
+pub mod revocation;
 
 use asn::*;
 use asn1::{oid, BitString, ObjectIdentifier, ParseError, SequenceOf};
+use codec::{Decode, Encode, MaxEncodedLen};
 use core::cell::RefCell;
 use ecdsa_vendored::hazmat::VerifyPrimitive;
 use error::ValidationError;
-use frame_support::{traits::ConstU32, BoundedVec};
+use frame_support::{traits::ConstU32, BoundedVec, RuntimeDebug};
 use num_bigint::BigUint;
 use p256::ecdsa::{signature::Verifier, VerifyingKey};
+pub use revocation::*;
+use scale_info::TypeInfo;
 
 use sha2::Digest;
 use sp_std::prelude::*;
 
+use crate::ListUpdate;
+
 pub const CHAIN_MAX_LENGTH: u32 = 5;
 pub const CERT_MAX_LENGTH: u32 = 3000;
 pub type CertificateInput = BoundedVec<u8, ConstU32<CERT_MAX_LENGTH>>;
@@ -167,13 +173,28 @@ fn validate<'a>(
     if cert.signature_algorithm.algorithm != cert.tbs_certificate.signature.algorithm {
         return Err(ValidationError::SignatureMismatch);
     }
-    match cert.signature_algorithm.algorithm {
+    validate_signature(
+        &cert.signature_algorithm.algorithm,
+        payload,
+        &cert.signature_value,
+        pbk,
+    )
+}
+
+/// Validates the `signature` of `payload` created with the signature `algorithm` and the private key of `pbk`.
+fn validate_signature(
+    algorithm: &ObjectIdentifier,
+    payload: &[u8],
+    signature: &BitString,
+    pbk: &PublicKey,
+) -> Result<(), ValidationError> {
+    match *algorithm {
         RSA_ALGORITHM => match pbk {
-            PublicKey::RSA(pbk) => validate_rsa(&payload, &cert.signature_value, &pbk),
+            PublicKey::RSA(pbk) => validate_rsa(&payload, signature, &pbk),
             _ => Err(ValidationError::UnsupportedPublicKeyAlgorithm),
         },
         ECDSA_ALGORITHM => match pbk {
-            PublicKey::ECDSA(pbk) => validate_ecdsa(&payload, &cert.signature_value, &pbk),
+            PublicKey::ECDSA(pbk) => validate_ecdsa(&payload, signature, &pbk),
             _ => Err(ValidationError::UnsupportedPublicKeyAlgorithm),
         },
         _ => Err(ValidationError::UnsupportedSignatureAlgorithm),
@@ -258,11 +279,50 @@ pub fn peek_attestation_version(data: &[u8]) -> Result<i64, ParseError> {
     result
 }
 
+/// A SHA-256 digest identifying a root certificate, see [TrustedRootId].
+pub type RootDigest = [u8; 32];
+
+/// Identifies a trusted root certificate.
+///
+/// A root can be trusted by its fingerprint, which matches exactly one certificate, or by its subject key, which matches
+/// every certificate issued for the same key, e.g. when a root is reissued with a new validity.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq)]
+pub enum TrustedRootId {
+    /// The SHA-256 digest of the DER encoded certificate.
+    Fingerprint(RootDigest),
+    /// The SHA-256 digest of the DER encoded subject public key info of the certificate.
+    SubjectKey(RootDigest),
+}
+
+/// An update adding or removing a trusted root.
+pub type TrustedRootListUpdate = ListUpdate<TrustedRootId>;
+
+/// Returns the SHA-256 fingerprint of a DER encoded certificate.
+pub fn certificate_fingerprint(cert: &[u8]) -> RootDigest {
+    sha2::Sha256::digest(cert).into()
+}
+
+/// Returns the ids a DER encoded certificate is trusted by as the root of a chain, its fingerprint and its subject key.
+pub fn trusted_root_ids(cert: &[u8]) -> Result<[TrustedRootId; 2], ValidationError> {
+    let parsed = parse_cert(cert)?;
+    let subject_key = asn1::write_single(&parsed.tbs_certificate.subject_public_key_info)
+        .map_err(|_| ValidationError::MissingPublicKey)?;
+    Ok([
+        TrustedRootId::Fingerprint(certificate_fingerprint(cert)),
+        TrustedRootId::SubjectKey(sha2::Sha256::digest(&subject_key).into()),
+    ])
+}
+
+/// Validates that the root of the chain is trusted, by checking its [TrustedRootId]s with `is_trusted`.
+///
+/// The signature of the root is not checked here but in [validate_certificate_chain], which makes sure that roots trusted
+/// by their subject key are signed by that key.
 pub fn validate_certificate_chain_root(
     chain: &CertificateChainInput,
+    is_trusted: impl Fn(&TrustedRootId) -> bool,
 ) -> Result<(), ValidationError> {
     let first = chain.first().ok_or(ValidationError::ChainTooShort)?;
-    if !TRUSTED_ROOT_CERTS.contains(&first.as_slice()) {
+    if !trusted_root_ids(first)?.iter().any(is_trusted) {
         return Err(ValidationError::UntrustedRoot);
     }
     Ok(())
@@ -270,7 +330,7 @@ pub fn validate_certificate_chain_root(
 
 /// Validates the chain by ensuring that
 ///
-/// - the chain starts with a self-signed certificate at index 0 that matches one of the known [TRUSTED_ROOT_CERTS]
+/// - the chain starts with a self-signed certificate at index 0, which has to be checked against the trusted roots with [validate_certificate_chain_root]
 /// - that the root's contained public key signs the next certificate in the chain
 /// - the next certificate's public key signs the next one and so on...
 pub fn validate_certificate_chain<'a>(
@@ -305,8 +365,10 @@ pub fn validate_certificate_chain<'a>(
     Ok((cert_ids, last_cert.tbs_certificate, last_cert_pbk))
 }
 
-/// The list of trusted root certificates, as decoded bytes arrays. [Source](https://developer.android.com/training/articles/security-key-attestation#root_certificate)
-const TRUSTED_ROOT_CERTS: &[&[u8]] = &[
+/// The list of root certificates trusted by default, as decoded bytes arrays. [Source](https://developer.android.com/training/articles/security-key-attestation#root_certificate)
+///
+/// Used to seed the trusted roots of the chain, which are managed in storage afterwards.
+pub const DEFAULT_TRUSTED_ROOT_CERTS: &[&[u8]] = &[
     // base64 equivalent: r"MIIFYDCCA0igAwIBAgIJAOj6GWMU0voYMA0GCSqGSIb3DQEBCwUAMBsxGTAXBgNVBAUTEGY5MjAwOWU4NTNiNmIwNDUwHhcNMTYwNTI2MTYyODUyWhcNMjYwNTI0MTYyODUyWjAbMRkwFwYDVQQFExBmOTIwMDllODUzYjZiMDQ1MIICIjANBgkqhkiG9w0BAQEFAAOCAg8AMIICCgKCAgEAr7bHgiuxpwHsK7Qui8xUFmOr75gvMsd/dTEDDJdSSxtf6An7xyqpRR90PL2abxM1dEqlXnf2tqw1Ne4Xwl5jlRfdnJLmN0pTy/4lj4/7tv0Sk3iiKkypnEUtR6WfMgH0QZfKHM1+di+y9TFRtv6y//0rb+T+W8a9nsNL/ggjnar86461qO0rOs2cXjp3kOG1FEJ5MVmFmBGtnrKpa73XpXyTqRxB/M0n1n/W9nGqC4FSYa04T6N5RIZGBN2z2MT5IKGbFlbC8UrW0DxW7AYImQQcHtGl/m00QLVWutHQoVJYnFPlXTcHYvASLu+RhhsbDmxMgJJ0mcDpvsC4PjvB+TxywElgS70vE0XmLD+OJtvsBslHZvPBKCOdT0MS+tgSOIfga+z1Z1g7+DVagf7quvmag8jfPioyKvxnK/EgsTUVi2ghzq8wm27ud/mIM7AY2qEORR8Go3TVB4HzWQgpZrt3i5MIlCaY504LzSRiigHCzAPlHws+W0rB5N+er5/2pJKnfBSDiCiFAVtCLOZ7gLiMm0jhO2B6tUXHI/+MRPjy02i59lINMRRev56GKtcd9qO/0kUJWdZTdA2XoS82ixPvZtXQpUpuL12ab+9EaDK8Z4RHJYYfCT3Q5vNAXaiWQ+8PTWm2QgBR/bkwSWc+NpUFgNPN9PvQi8WEg5UmAGMCAwEAAaOBpjCBozAdBgNVHQ4EFgQUNmHhAHyIBQlRi0RsR/8aTMnqTxIwHwYDVR0jBBgwFoAUNmHhAHyIBQlRi0RsR/8aTMnqTxIwDwYDVR0TAQH/BAUwAwEB/zAOBgNVHQ8BAf8EBAMCAYYwQAYDVR0fBDkwNzA1oDOgMYYvaHR0cHM6Ly9hbmRyb2lkLmdvb2dsZWFwaXMuY29tL2F0dGVzdGF0aW9uL2NybC8wDQYJKoZIhvcNAQELBQADggIBACDIw41L3KlXG0aMiS//cqrG+EShHUGo8HNsw30W1kJtjn6UBwRM6jnmiwfBPb8VA91chb2vssAtX2zbTvqBJ9+LBPGCdw/E53Rbf86qhxKaiAHOjpvAy5Y3m00mqC0w/Zwvju1twb4vhLaJ5NkUJYsUS7rmJKHHBnETLi8GFqiEsqTWpG/6ibYCv7rYDBJDcR9W62BW9jfIoBQcxUCUJouMPH25lLNcDc1ssqvC2v7iUgI9LeoM1sNovqPmQUiG9rHli1vXxzCyaMTjwftkJLkf6724DFhuKug2jITV0QkXvaJWF4nUaHOTNA4uJU9WDvZLI1j83A+/xnAJUucIv/zGJ1AMH2boHqF8CY16LpsYgBt6tKxxWH00XcyDCdW2KlBCeqbQPcsFmWyWugxdcekhYsAWyoSf818NUsZdBWBaR/OukXrNLfkQ79IyZohZbvabO/X+MVT3rriAoKc8oE2Uws6DF+60PV7/WIPjNvXySdqspImSN78mflxDqwLqRBYkA3I75qppLGG9rp7UCdRjxMl8ZDBld+7yvHVgt1cVzJx9xnyGCC23UaicMDSXYrB4I4WHXPGjxhZuCuPBLTdOLU8YRvMYdEvYebWHMpvwGCF6bAx3JBpIeOQ1wDB5y0USicV3YgYGmi+NZfhA4URSh77Yd6uuJOJENRaNVTzk"
     include_bytes!("./__root_certs__/00E8FA196314D2FA18.cer"),
     // base64 equivalent: r"MIIFHDCCAwSgAwIBAgIJANUP8luj8tazMA0GCSqGSIb3DQEBCwUAMBsxGTAXBgNVBAUTEGY5MjAwOWU4NTNiNmIwNDUwHhcNMTkxMTIyMjAzNzU4WhcNMzQxMTE4MjAzNzU4WjAbMRkwFwYDVQQFExBmOTIwMDllODUzYjZiMDQ1MIICIjANBgkqhkiG9w0BAQEFAAOCAg8AMIICCgKCAgEAr7bHgiuxpwHsK7Qui8xUFmOr75gvMsd/dTEDDJdSSxtf6An7xyqpRR90PL2abxM1dEqlXnf2tqw1Ne4Xwl5jlRfdnJLmN0pTy/4lj4/7tv0Sk3iiKkypnEUtR6WfMgH0QZfKHM1+di+y9TFRtv6y//0rb+T+W8a9nsNL/ggjnar86461qO0rOs2cXjp3kOG1FEJ5MVmFmBGtnrKpa73XpXyTqRxB/M0n1n/W9nGqC4FSYa04T6N5RIZGBN2z2MT5IKGbFlbC8UrW0DxW7AYImQQcHtGl/m00QLVWutHQoVJYnFPlXTcHYvASLu+RhhsbDmxMgJJ0mcDpvsC4PjvB+TxywElgS70vE0XmLD+OJtvsBslHZvPBKCOdT0MS+tgSOIfga+z1Z1g7+DVagf7quvmag8jfPioyKvxnK/EgsTUVi2ghzq8wm27ud/mIM7AY2qEORR8Go3TVB4HzWQgpZrt3i5MIlCaY504LzSRiigHCzAPlHws+W0rB5N+er5/2pJKnfBSDiCiFAVtCLOZ7gLiMm0jhO2B6tUXHI/+MRPjy02i59lINMRRev56GKtcd9qO/0kUJWdZTdA2XoS82ixPvZtXQpUpuL12ab+9EaDK8Z4RHJYYfCT3Q5vNAXaiWQ+8PTWm2QgBR/bkwSWc+NpUFgNPN9PvQi8WEg5UmAGMCAwEAAaNjMGEwHQYDVR0OBBYEFDZh4QB8iAUJUYtEbEf/GkzJ6k8SMB8GA1UdIwQYMBaAFDZh4QB8iAUJUYtEbEf/GkzJ6k8SMA8GA1UdEwEB/wQFMAMBAf8wDgYDVR0PAQH/BAQDAgIEMA0GCSqGSIb3DQEBCwUAA4ICAQBOMaBc8oumXb2voc7XCWnuXKhBBK3e2KMGz39t7lA3XXRe2ZLLAkLM5y3J7tURkf5a1SutfdOyXAmeE6SRo83Uh6WszodmMkxK5GM4JGrnt4pBisu5igXEydaW7qq2CdC6DOGjG+mEkN8/TA6p3cnoL/sPyz6evdjLlSeJ8rFBH6xWyIZCbrcpYEJzXaUOEaxxXxgYz5/cTiVKN2M1G2okQBUIYSY6bjEL4aUN5cfo7ogP3UvliEo3Eo0YgwuzR2v0KR6C1cZqZJSTnghIC/vAD32KdNQ+c3N+vl2OTsUVMC1GiWkngNx1OO1+kXW+YTnnTUOtOIswUP/Vqd5SYgAImMAfY8U9/iIgkQj6T2W6FsScy94IN9fFhE1UtzmLoBIuUFsVXJMTz+Jucth+IqoWFua9v1R93/k98p41pjtFX+H8DslVgfP097vju4KDlqN64xV1grw3ZLl4CiOe/A91oeLm2UHOq6wn3esB4r2EIQKb6jTVGu5sYCcdWpXr0AUVqcABPdgL+H7qJguBw09ojm6xNIrw2OocrDKsudk/okr/AwqEyPKw9WnMlQgLIKw1rODG2NvU9oR3GVGdMkUBZutL8VuFkERQGt6vQ2OCw0sV47VMkuYbacK/xyZFiRcrPJPb41zgbQj9XAEyLKCHex0SdDrx+tWUDqG8At2JHA=="
@@ -325,10 +387,17 @@ mod tests {
     };
 
     use super::{
-        asn::KeyDescription, validate_certificate_chain, validate_certificate_chain_root,
-        CertificateChainInput, CertificateInput,
+        asn::KeyDescription, certificate_fingerprint, trusted_root_ids, validate_certificate_chain,
+        validate_certificate_chain_root, CertificateChainInput, CertificateInput, TrustedRootId,
+        DEFAULT_TRUSTED_ROOT_CERTS,
     };
 
+    fn is_default_root(id: &TrustedRootId) -> bool {
+        DEFAULT_TRUSTED_ROOT_CERTS
+            .iter()
+            .any(|cert| id == &TrustedRootId::Fingerprint(certificate_fingerprint(cert)))
+    }
+
     pub fn decode_certificate_chain(chain: &Vec<&str>) -> CertificateChainInput {
         let decoded = chain
             .iter()
@@ -371,7 +440,7 @@ mod tests {
             SAMSUNG_KEY_CERT,
         ];
         let decoded_chain = decode_certificate_chain(&chain);
-        validate_certificate_chain_root(&decoded_chain)?;
+        validate_certificate_chain_root(&decoded_chain, is_default_root)?;
         let (_, cert, _) = validate_certificate_chain(&decoded_chain)?;
         let key_description = extract_attestation(cert.extensions)?;
         match &key_description {
@@ -393,7 +462,8 @@ mod tests {
             PIXEL_KEY_CERT,
         ];
         let decoded_chain = decode_certificate_chain(&chain);
-        validate_certificate_chain_root(&decoded_chain).expect("validating root failed");
+        validate_certificate_chain_root(&decoded_chain, is_default_root)
+            .expect("validating root failed");
         let (_, cert, _) =
             validate_certificate_chain(&decoded_chain).expect("validating chain failed");
         let key_description = extract_attestation(cert.extensions)?;
@@ -416,7 +486,8 @@ mod tests {
             PIXEL_KEY_CERT_INVALID,
         ];
         let decoded_chain = decode_certificate_chain(&chain);
-        validate_certificate_chain_root(&decoded_chain).expect("validating root failed");
+        validate_certificate_chain_root(&decoded_chain, is_default_root)
+            .expect("validating root failed");
         let res = validate_certificate_chain(&decoded_chain);
         match res {
             Err(e) => assert_eq!(e, ValidationError::InvalidSignature),
@@ -434,11 +505,34 @@ mod tests {
             PIXEL_KEY_CERT_INVALID,
         ];
         let decoded_chain = decode_certificate_chain(&chain);
-        let res = validate_certificate_chain_root(&decoded_chain);
+        let res = validate_certificate_chain_root(&decoded_chain, is_default_root);
         match res {
             Err(e) => assert_eq!(e, ValidationError::UntrustedRoot),
             _ => return Err(()),
         };
         Ok(())
     }
+
+    #[test]
+    fn test_validate_root_trusted_by_subject_key() -> Result<(), ()> {
+        let trusted_chain = decode_certificate_chain(&vec![PIXEL_ROOT_CERT]);
+        let [_, subject_key] = trusted_root_ids(&trusted_chain[0]).map_err(|_| ())?;
+
+        // the modified root shares the subject key of the trusted root, but is not signed by it
+        let chain = vec![
+            PIXEL_ROOT_CERT_UNTRUSTED,
+            PIXEL_INTERMEDIATE_2_CERT,
+            PIXEL_INTERMEDIATE_1_CERT,
+            PIXEL_KEY_CERT,
+        ];
+        let decoded_chain = decode_certificate_chain(&chain);
+        validate_certificate_chain_root(&decoded_chain, |id| id == &subject_key)
+            .expect("validating root failed");
+        let res = validate_certificate_chain(&decoded_chain);
+        match res {
+            Err(e) => assert_eq!(e, ValidationError::InvalidSignature),
+            _ => return Err(()),
+        };
+        Ok(())
+    }
 }
@@ -52,6 +52,18 @@ pub enum ValidationError {
     /// field in the sequence
     /// [Certificate](https://www.rfc-editor.org/rfc/rfc5280#section-4.1.1.2).
     SignatureMismatch,
+    /// The issuer of the certificate revocation list does not match the subject of the issuing certificate
+    RevocationListIssuerMismatch,
+    /// The certificate revocation list contains more than [crate::CRL_MAX_ENTRIES] revoked certificates
+    TooManyRevokedCertificates,
+    /// The attestation status list is not valid JSON of the expected format
+    InvalidStatusList,
+    /// The serial number is not a valid DER encoded integer of at most 20 bytes
+    InvalidSerialNumber,
+    /// The issuer of the certificate revocation list is not a CA with the `cRLSign` key usage
+    InvalidRevocationListSigner,
+    /// The certificate revocation list or one of its entries contains a critical extension that is not supported
+    UnsupportedCriticalExtension,
 }
 
 impl From<ParseError> for ValidationError {
@@ -0,0 +1,375 @@
+use asn1::{oid, BitString, ObjectIdentifier, SequenceOf};
+use frame_support::{traits::ConstU32, BoundedVec, RuntimeDebug};
+use lite_json::json::JsonValue;
+use sp_std::prelude::*;
+
+use super::{
+    asn::{
+        BasicConstraints, CertificateList, CertificateListRawPayload, Extension, TBSCertificate,
+    },
+    error::ValidationError,
+    validate_signature, PublicKey,
+};
+use crate::{CertId, IssuerName, SerialNumber};
+
+pub const CRL_MAX_LENGTH: u32 = 65_536;
+/// The maximum number of revoked certificates in a certificate revocation list.
+pub const CRL_MAX_ENTRIES: u32 = 1_000;
+pub type CertificateRevocationListInput = BoundedVec<u8, ConstU32<CRL_MAX_LENGTH>>;
+
+const BASIC_CONSTRAINTS_OID: ObjectIdentifier = oid!(2, 5, 29, 19);
+const KEY_USAGE_OID: ObjectIdentifier = oid!(2, 5, 29, 15);
+/// The bit of `cRLSign` in the [key usage](https://www.rfc-editor.org/rfc/rfc5280#section-4.2.1.3).
+const CRL_SIGN_KEY_USAGE: usize = 6;
+
+/// A validated [X.509 CRL](https://www.rfc-editor.org/rfc/rfc5280#section-5).
+#[derive(RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct ValidatedRevocationList {
+    /// The DER encoded name of the issuer of the CRL.
+    pub issuer: IssuerName,
+    /// The `thisUpdate` of the CRL, the time it was issued at, in milliseconds since the unix epoch.
+    pub this_update: u64,
+    /// The `nextUpdate` of the CRL, the time by which the next CRL will be issued, in milliseconds since the unix epoch.
+    /// The CRL should not be used after that time.
+    pub next_update: Option<u64>,
+    /// The [CertId]s of the revoked certificates.
+    pub revoked: Vec<CertId>,
+}
+
+/// Validates a DER encoded [X.509 CRL](https://www.rfc-editor.org/rfc/rfc5280#section-5) and returns it with the [CertId]s
+/// of the revoked certificates.
+///
+/// The CRL has to be issued by the subject of `issuer` and signed with its public key `issuer_pbk`. As required by
+/// [RFC 5280](https://www.rfc-editor.org/rfc/rfc5280#section-6.3.3), `issuer` has to be a CA with the `cRLSign` key usage.
+/// Its chain is not validated here, use [super::validate_certificate_chain] to obtain a validated issuer.
+///
+/// None of the CRL and CRL entry extensions are processed, so CRLs with a critical extension, e.g. an issuing distribution
+/// point or the certificate issuer of an indirect CRL, are rejected.
+///
+/// The times of the CRL are not checked against the current time, which is up to the caller.
+pub fn validate_certificate_revocation_list(
+    crl: &[u8],
+    issuer: &TBSCertificate,
+    issuer_pbk: &PublicKey,
+) -> Result<ValidatedRevocationList, ValidationError> {
+    validate_revocation_list_signer(issuer)?;
+
+    let list = asn1::parse_single::<CertificateList>(crl).or(Err(ValidationError::ParseError))?;
+    let payload = asn1::parse_single::<CertificateListRawPayload>(crl)
+        .or(Err(ValidationError::ParseError))?
+        .tbs_cert_list
+        .full_data();
+
+    if list.signature_algorithm.algorithm != list.tbs_cert_list.signature.algorithm {
+        return Err(ValidationError::SignatureMismatch);
+    }
+    let list_issuer =
+        asn1::write_single(&list.tbs_cert_list.issuer).or(Err(ValidationError::InvalidIssuer))?;
+    let issuer_subject =
+        asn1::write_single(&issuer.subject).or(Err(ValidationError::InvalidIssuer))?;
+    if list_issuer != issuer_subject {
+        return Err(ValidationError::RevocationListIssuerMismatch);
+    }
+    validate_signature(
+        &list.signature_algorithm.algorithm,
+        payload,
+        &list.signature_value,
+        issuer_pbk,
+    )?;
+    ensure_no_critical_extension(&list.tbs_cert_list.crl_extensions)?;
+
+    let issuer_name =
+        IssuerName::try_from(list_issuer.clone()).or(Err(ValidationError::InvalidIssuer))?;
+    let mut cert_ids = Vec::<CertId>::new();
+    for revoked in list
+        .tbs_cert_list
+        .revoked_certificates
+        .into_iter()
+        .flatten()
+    {
+        if cert_ids.len() >= CRL_MAX_ENTRIES as usize {
+            return Err(ValidationError::TooManyRevokedCertificates);
+        }
+        ensure_no_critical_extension(&revoked.crl_entry_extensions)?;
+        let serial_number = SerialNumber::try_from(revoked.user_certificate.as_bytes().to_vec())
+            .or(Err(ValidationError::InvalidSerialNumber))?;
+        cert_ids.push((issuer_name.clone(), serial_number));
+    }
+    Ok(ValidatedRevocationList {
+        issuer: issuer_name,
+        this_update: list.tbs_cert_list.this_update.timestamp_millis(),
+        next_update: list
+            .tbs_cert_list
+            .next_update
+            .map(|next_update| next_update.timestamp_millis()),
+        revoked: cert_ids,
+    })
+}
+
+/// Validates that `issuer` is a CA whose key usage includes `cRLSign`.
+fn validate_revocation_list_signer(issuer: &TBSCertificate) -> Result<(), ValidationError> {
+    let extension = |oid: &ObjectIdentifier| {
+        issuer
+            .extensions
+            .clone()
+            .into_iter()
+            .flatten()
+            .find(|extension| &extension.extn_id == oid)
+    };
+
+    let is_ca = match extension(&BASIC_CONSTRAINTS_OID) {
+        Some(extension) => asn1::parse_single::<BasicConstraints>(extension.extn_value)?.ca,
+        None => false,
+    };
+    let can_sign_crl = match extension(&KEY_USAGE_OID) {
+        Some(extension) => {
+            asn1::parse_single::<BitString>(extension.extn_value)?.has_bit_set(CRL_SIGN_KEY_USAGE)
+        }
+        None => false,
+    };
+    if !is_ca || !can_sign_crl {
+        return Err(ValidationError::InvalidRevocationListSigner);
+    }
+    Ok(())
+}
+
+fn ensure_no_critical_extension(
+    extensions: &Option<SequenceOf<Extension>>,
+) -> Result<(), ValidationError> {
+    if extensions
+        .clone()
+        .into_iter()
+        .flatten()
+        .any(|extension| extension.critical)
+    {
+        return Err(ValidationError::UnsupportedCriticalExtension);
+    }
+    Ok(())
+}
+
+/// The status of a certificate in the attestation status list.
+#[derive(RuntimeDebug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateStatus {
+    Revoked,
+    Suspended,
+}
+
+/// Parses the JSON of the [attestation status list](https://developer.android.com/training/articles/security-key-attestation#certificate_status)
+/// published by Google and returns the serial numbers of the listed certificates with their status.
+///
+/// The list keys its entries by the serial number in hex, which is converted to the content of its DER encoding, the format
+/// used for the serial numbers of the revocation list. The list does not contain the issuers of the certificates, which are
+/// required to revoke them, e.g. as found in the [CertId]s of stored attestations.
+///
+/// The list is not signed, so it cannot be verified on chain and is not imported by the pallet: it is meant for off-chain
+/// tooling preparing the revocations submitted by an account allowed to update the certificate revocation list.
+pub fn parse_attestation_status_list(
+    json: &[u8],
+) -> Result<Vec<(SerialNumber, CertificateStatus)>, ValidationError> {
+    let json = core::str::from_utf8(json).or(Err(ValidationError::InvalidStatusList))?;
+    let value = lite_json::parse_json(json).or(Err(ValidationError::InvalidStatusList))?;
+    let entries = match object_field(&value, "entries") {
+        Some(JsonValue::Object(entries)) => entries,
+        _ => return Err(ValidationError::InvalidStatusList),
+    };
+
+    entries
+        .iter()
+        .map(|(serial_number, entry)| {
+            let status = match object_field(entry, "status") {
+                Some(JsonValue::String(status)) if chars_eq(status, "REVOKED") => {
+                    CertificateStatus::Revoked
+                }
+                Some(JsonValue::String(status)) if chars_eq(status, "SUSPENDED") => {
+                    CertificateStatus::Suspended
+                }
+                _ => return Err(ValidationError::InvalidStatusList),
+            };
+            Ok((serial_number_from_hex(serial_number)?, status))
+        })
+        .collect()
+}
+
+fn object_field<'a>(value: &'a JsonValue, name: &str) -> Option<&'a JsonValue> {
+    match value {
+        JsonValue::Object(fields) => fields
+            .iter()
+            .find(|(key, _)| chars_eq(key, name))
+            .map(|(_, value)| value),
+        _ => None,
+    }
+}
+
+fn chars_eq(chars: &[char], value: &str) -> bool {
+    chars.iter().copied().eq(value.chars())
+}
+
+/// Converts a serial number in hex to the content of its DER encoding, the minimal big endian two's complement.
+fn serial_number_from_hex(hex: &[char]) -> Result<SerialNumber, ValidationError> {
+    let digits = hex
+        .iter()
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(ValidationError::InvalidSerialNumber)?;
+    if digits.is_empty() {
+        return Err(ValidationError::InvalidSerialNumber);
+    }
+
+    // an odd number of digits has an implicit leading zero
+    let odd = digits.len() % 2;
+    let mut bytes = digits[..odd].to_vec();
+    bytes.extend(digits[odd..].chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+
+    let first_significant = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len() - 1);
+    let mut bytes = bytes.split_off(first_significant);
+    // serial numbers are positive, a set high bit requires a leading zero
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    SerialNumber::try_from(bytes).or(Err(ValidationError::InvalidSerialNumber))
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+    use crate::{validate_certificate_chain, CertificateChainInput, DEFAULT_TRUSTED_ROOT_CERTS};
+
+    const ISSUER_CERT: [u8; 395] = hex!("308201873082012da00302010202020a01300a06082a8648ce3d040302301a311830160603550405130f6163757261737474657374726f6f74301e170d3236313031373032313234315a170d3436313031323032313234315a301a311830160603550405130f6163757261737474657374726f6f743059301306072a8648ce3d020106082a8648ce3d030107034200047ad0a4bfc57c77e336c33e7ca4da096648323af5f406381b432823882044febf9b78d185e2b0a0b3743eb3e5d96f02aca6e0aab6ba11035cdf0c16a0f6d19da8a3633061301d0603551d0e04160414127f49be7510289a525c0a35a440afe932292262301f0603551d23041830168014127f49be7510289a525c0a35a440afe932292262300f0603551d130101ff040530030101ff300e0603551d0f0101ff040403020106300a06082a8648ce3d0403020348003045022100b91e98bb7385d2b2215ea9b87142376dd3e85c259de8bad909e560b8409bc30e022017868d988cd6360de84ff617aa86fa3563f08d8fe3947458060cc3232052bcf9");
+    const CRL: [u8; 255] = hex!("3081fc3081a2020101300a06082a8648ce3d040302301a311830160603550405130f6163757261737474657374726f6f74170d3236313031373032313234375a170d3336313031343032313234375a3047301a020900c8966fcb2fbb0d7a170d3233303130313030303030305a3029020a15905857467176635834170d3233303130313030303030305a300c300a0603551d1504030a0101a00e300c300a0603551d140403020101300a06082a8648ce3d0403020349003046022100e98d690c43f61ab3f60f6ca86e8ca807d215b469c0bee22346e532c7a6383a8c022100f985ac8b136424e77f1e3a875deb725895733e897f6d254cd1463b772061b44a");
+
+    const CA_CERT: [u8; 332] = hex!("308201483081efa00302010202020a02300a06082a8648ce3d040302301b31193017060355040513106163757261737463726c746573746361301e170d3236313031373030303030305a170d3436313031323030303030305a301b31193017060355040513106163757261737463726c7465737463613059301306072a8648ce3d020106082a8648ce3d03010703420004205d5ece9bf10b3bd26e5b6396037d144494e6a3725c61d3331b255ab25da1333f4aaeab61f75766006f051c7b49d3403ec6df2c2f9b0af3c52296bd5dad5c79a3233021300f0603551d130101ff040530030101ff300e0603551d0f0101ff040403020106300a06082a8648ce3d04030203480030450221009ee86993fa606c3af3fb01ffc068542dd18d4b9317659faf8fe42523bc2f0d9502203c732eee374329e2343d25943ea0348a21ce817f1137d818f6da13cef1c7521c");
+    /// Issued by [CA_CERT] with a critical issuing distribution point.
+    const CRL_CRITICAL_EXTENSION: [u8; 229] = hex!("3081e2308189020101300a06082a8648ce3d040302301b31193017060355040513106163757261737463726c746573746361170d3236313031373030303030305a170d3336313031343030303030305a301c301a020900c8966fcb2fbb0d7a170d3233303130313030303030305aa01f301d300a0603551d140403020101300f0603551d1c0101ff040530038101ff300a06082a8648ce3d0403020348003045022100c668ff9c697280c7616421fc4538df5a4026f7d8ef77d2e9c4dcc8fa7cf932b102203652d157a642794a40399c6ce1f378cdc8aef20d68130f6a321e19bdb1912ae5");
+    /// Issued by [CA_CERT] with a critical certificate issuer in its entry.
+    const CRL_CRITICAL_ENTRY_EXTENSION: [u8; 260] = hex!("308201003081a7020101300a06082a8648ce3d040302301b31193017060355040513106163757261737463726c746573746361170d3236313031373030303030305a170d3336313031343030303030305a304b3049020900c8966fcb2fbb0d7a170d3233303130313030303030305a302d302b0603551d1d0101ff0421301fa41d301b31193017060355040513106163757261737463726c746573746361a00e300c300a0603551d140403020101300a06082a8648ce3d0403020348003045022100a83c31449d620b6895c8a00bb639b2886a0cc1783209ae4b4e0b20575988008e02204c572eec88df22270aadcc27a6a8ae37fcc43b7cec0b9bee4482e7a539fe298a");
+
+    fn validate_crl_issued_by(
+        issuer: &[u8],
+        crl: &[u8],
+    ) -> Result<ValidatedRevocationList, ValidationError> {
+        let chain = CertificateChainInput::truncate_from(vec![issuer.to_vec().try_into().unwrap()]);
+        let (_, issuer, issuer_pbk) = validate_certificate_chain(&chain)?;
+        validate_certificate_revocation_list(crl, &issuer, &issuer_pbk)
+    }
+
+    fn validate_crl(crl: &[u8]) -> Result<ValidatedRevocationList, ValidationError> {
+        validate_crl_issued_by(&ISSUER_CERT, crl)
+    }
+
+    #[test]
+    fn test_validate_certificate_revocation_list() {
+        let crl = validate_crl(&CRL).expect("validating crl failed");
+        let issuer_name = IssuerName::truncate_from(
+            hex!("301a311830160603550405130f6163757261737474657374726f6f74").to_vec(),
+        );
+        assert_eq!(
+            crl,
+            ValidatedRevocationList {
+                issuer: issuer_name.clone(),
+                // 2026-10-17T02:12:47Z
+                this_update: 1_792_203_167_000,
+                // 2036-10-14T02:12:47Z
+                next_update: Some(2_107_563_167_000),
+                revoked: vec![
+                    (
+                        issuer_name.clone(),
+                        SerialNumber::truncate_from(hex!("00c8966fcb2fbb0d7a").to_vec())
+                    ),
+                    (
+                        issuer_name,
+                        SerialNumber::truncate_from(hex!("15905857467176635834").to_vec())
+                    ),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_certificate_revocation_list_invalid_signature() {
+        let mut crl = CRL;
+        // last byte of the signature
+        crl[254] ^= 1;
+        assert_eq!(validate_crl(&crl), Err(ValidationError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_validate_certificate_revocation_list_signer() {
+        // the root only has the `keyCertSign` key usage
+        assert_eq!(
+            validate_crl_issued_by(DEFAULT_TRUSTED_ROOT_CERTS[1], &CRL),
+            Err(ValidationError::InvalidRevocationListSigner)
+        );
+    }
+
+    #[test]
+    fn test_validate_certificate_revocation_list_critical_extensions() {
+        assert_eq!(
+            validate_crl_issued_by(&CA_CERT, &CRL_CRITICAL_EXTENSION),
+            Err(ValidationError::UnsupportedCriticalExtension)
+        );
+        assert_eq!(
+            validate_crl_issued_by(&CA_CERT, &CRL_CRITICAL_ENTRY_EXTENSION),
+            Err(ValidationError::UnsupportedCriticalExtension)
+        );
+    }
+
+    #[test]
+    fn test_parse_attestation_status_list() {
+        let json = br#"{
+            "entries": {
+                "2c8cdddfd5e03bfc": {
+                    "status": "REVOKED",
+                    "expires": "2020-11-13",
+                    "reason": "KEY_COMPROMISE",
+                    "comment": "Key stored on unsecure system"
+                },
+                "c8966fcb2fbb0d7a": {
+                    "status": "SUSPENDED",
+                    "reason": "SOFTWARE_FLAW",
+                    "comment": "Bug in keystore causes this key malfunction b/555555"
+                },
+                "a01": {
+                    "status": "REVOKED"
+                }
+            }
+        }"#;
+        assert_eq!(
+            parse_attestation_status_list(json),
+            Ok(vec![
+                (
+                    SerialNumber::truncate_from(hex!("2c8cdddfd5e03bfc").to_vec()),
+                    CertificateStatus::Revoked
+                ),
+                (
+                    SerialNumber::truncate_from(hex!("00c8966fcb2fbb0d7a").to_vec()),
+                    CertificateStatus::Suspended
+                ),
+                (
+                    SerialNumber::truncate_from(hex!("0a01").to_vec()),
+                    CertificateStatus::Revoked
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_attestation_status_list() {
+        assert_eq!(
+            parse_attestation_status_list(br#"{"entries": {"xyz": {"status": "REVOKED"}}}"#),
+            Err(ValidationError::InvalidSerialNumber)
+        );
+        assert_eq!(
+            parse_attestation_status_list(br#"{"entries": {"0a01": {"status": "VALID"}}}"#),
+            Err(ValidationError::InvalidStatusList)
+        );
+        assert_eq!(
+            parse_attestation_status_list(br#"{"entries": []}"#),
+            Err(ValidationError::InvalidStatusList)
+        );
+    }
+}
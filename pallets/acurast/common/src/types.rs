@@ -29,6 +29,19 @@ pub type SerialNumber = BoundedVec<u8, ConstU32<SERIAL_NUMBER_MAX_LENGTH>>;
 /// A Job ID consists of an [AccountId] and a [Script].
 pub type JobId<AccountId> = (AccountId, Script);
 
+/// Identifies an execution of a job by one of its sources, allowing receivers of fulfillments to reject duplicates.
+///
+/// Each slot of a job executes the same schedule, so the source is required to tell the fulfillments of different slots apart.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq)]
+pub struct ExecutionId<AccountId> {
+    /// The job the execution belongs to.
+    pub job_id: JobId<AccountId>,
+    /// The source that executed the job.
+    pub source: AccountId,
+    /// The index of the execution in the job's schedule.
+    pub execution: u64,
+}
+
 /// The allowed sources update operation.
 #[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Copy)]
 pub enum ListUpdateOperation {
@@ -49,9 +62,6 @@ where
 /// Structure used to updated the allowed sources list of a [Registration].
 pub type AllowedSourcesUpdate<AccountId> = ListUpdate<AccountId>;
 
-/// Structure used to updated the certificate recovation list.
-pub type CertificateRevocationListUpdate = ListUpdate<SerialNumber>;
-
 /// Structure representing a job registration.
 #[derive(RuntimeDebug, Encode, Decode, TypeInfo, Clone, PartialEq)]
 pub struct JobRegistration<AccountId, Extra>
@@ -5,7 +5,7 @@ use crate::{
         asn::{self, KeyDescription},
         CertificateChainInput, CHAIN_MAX_LENGTH,
     },
-    SerialNumber,
+    ListUpdate, SerialNumber,
 };
 
 use frame_support::{pallet_prelude::*, storage::bounded_vec::BoundedVec};
@@ -32,6 +32,7 @@ pub type MgfDigest = BoundedVec<u8, ConstU32<MGF_DIGEST_MAX_LENGTH>>;
 pub type VerifiedBootKey = BoundedVec<u8, ConstU32<VERIFIED_BOOT_KEY_MAX_LENGTH>>;
 pub type VerifiedBootHash = BoundedVec<u8, ConstU32<VERIFIED_BOOT_HASH_MAX_LENGTH>>;
 pub type AttestationIdProperty = BoundedVec<u8, ConstU32<ATTESTATION_ID_MAX_LENGTH>>;
+/// Identifies a certificate by the DER encoded name of its issuer and its serial number.
 pub type CertId = (IssuerName, SerialNumber);
 pub type ValidatingCertIds = BoundedVec<CertId, ConstU32<CHAIN_MAX_LENGTH>>;
 pub type BoundedSetProperty = BoundedVec<CertId, ConstU32<BOUNDED_SET_PROPERTY>>;
@@ -39,6 +40,9 @@ pub type PackageName = BoundedVec<u8, ConstU32<PACKAGE_NAME_MAX_LENGTH>>;
 pub type SignatureDigestSet = BoundedVec<Digest, ConstU32<SIGNATURE_DIGEST_SET_MAX_LENGTH>>;
 pub type PackageInfoSet = BoundedVec<BoundedAttestationPackageInfo, ConstU32<16>>;
 
+/// Structure used to updated the certificate recovation list.
+pub type CertificateRevocationListUpdate = ListUpdate<CertId>;
+
 /// Structure representing a submitted attestation chain.
 #[derive(RuntimeDebug, Encode, Decode, TypeInfo, Clone, PartialEq)]
 pub struct AttestationChain {
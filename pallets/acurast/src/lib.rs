@@ -8,12 +8,15 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+mod migrations;
+mod policy;
 mod traits;
 pub mod utils;
 pub mod weights;
 
 pub use acurast_common::*;
 pub use pallet::*;
+pub use policy::*;
 pub use traits::*;
 
 pub type JobRegistrationFor<T> =
@@ -23,13 +26,17 @@ pub type JobRegistrationFor<T> =
 pub mod pallet {
     use acurast_common::*;
     use frame_support::{
-        dispatch::DispatchResultWithPostInfo, ensure, pallet_prelude::*, traits::UnixTime,
+        dispatch::DispatchResultWithPostInfo,
+        ensure, log,
+        pallet_prelude::*,
+        storage::{with_transaction, TransactionOutcome},
+        traits::UnixTime,
         Blake2_128Concat, PalletId,
     };
     use frame_system::pallet_prelude::*;
     use sp_std::prelude::*;
 
-    use crate::{traits::*, utils::*, JobRegistrationFor};
+    use crate::{policy::*, traits::*, utils::*, JobRegistrationFor};
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -54,11 +61,45 @@ pub mod pallet {
         type WeightInfo: WeightInfo;
     }
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub (super) trait Store)]
     #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        /// The DER encoded root certificates trusted by their fingerprint from genesis on.
+        pub trusted_root_certificates: Vec<Vec<u8>>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            Self {
+                trusted_root_certificates: DEFAULT_TRUSTED_ROOT_CERTS
+                    .iter()
+                    .map(|cert| cert.to_vec())
+                    .collect(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            for cert in &self.trusted_root_certificates {
+                <StoredTrustedRoot<T>>::insert(
+                    TrustedRootId::Fingerprint(certificate_fingerprint(cert)),
+                    (),
+                );
+            }
+        }
+    }
+
     /// The storage for [JobRegistration]s. They are stored by [AccountId] and [Script].
     #[pallet::storage]
     #[pallet::getter(fn stored_job_registration)]
@@ -77,12 +118,67 @@ pub mod pallet {
     pub type StoredAttestation<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, Attestation>;
 
-    /// Certificate revocation list storage.
+    /// Index of attested sources by the [CertId]s of their [Attestation] as a map [CertId] -> [AccountId] `(source)` -> `()`.
+    /// Reverse index of [StoredAttestation] used to look up the attestations affected by a revoked certificate.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_attested_sources)]
+    pub type StoredAttestedSources<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, CertId, Blake2_128Concat, T::AccountId, ()>;
+
+    /// Certificate revocation list storage, keyed by the issuer and serial number of the revoked certificates.
     #[pallet::storage]
     #[pallet::getter(fn stored_revoked_certificate)]
-    pub type StoredRevokedCertificate<T: Config> =
+    pub type StoredRevokedCertificate<T: Config> = StorageMap<_, Blake2_128Concat, CertId, ()>;
+
+    /// Serial numbers revoked before [StoredRevokedCertificate] was keyed by the issuer, kept by the migration since other
+    /// issuers than the ones found in the stored attestations might use them. They revoke the certificates with that serial
+    /// number of any issuer until root removes them with [Pallet::remove_revoked_serial_numbers].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_revoked_serial_number)]
+    pub type StoredRevokedSerialNumber<T: Config> =
         StorageMap<_, Blake2_128Concat, SerialNumber, ()>;
 
+    /// The `thisUpdate` of the last certificate revocation list imported for an issuer, as a map [IssuerName] -> milliseconds
+    /// since the unix epoch. Only newer CRLs of the issuer can be imported.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_revocation_list_update)]
+    pub type StoredRevocationListUpdate<T: Config> =
+        StorageMap<_, Blake2_128Concat, IssuerName, u64>;
+
+    /// The queue of revoked certificates whose attested sources still have to be revoked, as a map index -> [CertId].
+    /// Processed in `on_idle`, see [Pallet::revoke_attested_sources].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_revocation_queue)]
+    pub type StoredRevocationQueue<T: Config> = StorageMap<_, Twox64Concat, u64, CertId>;
+
+    /// The range `(head, tail)` of the indices used in [StoredRevocationQueue], `head` being the next certificate to process.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_revocation_queue_range)]
+    pub type StoredRevocationQueueRange<T: Config> = StorageValue<_, (u64, u64), ValueQuery>;
+
+    /// The raw [StoredAttestedSources] key of the last source processed for the certificate at the head of [StoredRevocationQueue],
+    /// if processing its sources ran out of weight.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_revocation_cursor)]
+    pub type StoredRevocationCursor<T: Config> = StorageValue<_, Vec<u8>>;
+
+    /// The sources whose stored attestation has been revoked, as a map [AccountId] -> [CertId] of the revoked certificate.
+    /// Prevents revoking an attestation again when another certificate of its chain gets revoked.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_revoked_attestation)]
+    pub type StoredRevokedAttestation<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, CertId>;
+
+    /// The [AttestationPolicy] enforced by [AttestationPolicyBarrier].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_attestation_policy)]
+    pub type StoredAttestationPolicy<T: Config> = StorageValue<_, AttestationPolicy>;
+
+    /// The roots attestation certificate chains have to start with, see [TrustedRootId].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_trusted_root)]
+    pub type StoredTrustedRoot<T: Config> = StorageMap<_, Blake2_128Concat, TrustedRootId, ()>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub (super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -100,6 +196,14 @@ pub mod pallet {
         AttestationStored(Attestation, T::AccountId),
         /// The certificate revocation list has been updated. [who, updates]
         CertificateRecovationListUpdated(T::AccountId, Vec<CertificateRevocationListUpdate>),
+        /// The attestation policy has been set or removed. [policy]
+        AttestationPolicyUpdated(Option<AttestationPolicy>),
+        /// The trusted roots have been updated. [updates]
+        TrustedRootsUpdated(Vec<TrustedRootListUpdate>),
+        /// The stored attestation of a source is no longer valid since a certificate of its chain got revoked. [who]
+        AttestationRevoked(T::AccountId),
+        /// Serial numbers revoked for any issuer have been removed from [StoredRevokedSerialNumber]. [serial_numbers]
+        RevokedSerialNumbersRemoved(Vec<SerialNumber>),
     }
 
     #[pallet::error]
@@ -150,10 +254,38 @@ pub mod pallet {
         AttestationPublicKeyDoesNotMatchSource,
         /// Calling a job hook produced an error.
         JobHookFailed,
+        /// The attestation security level is lower than required by the [AttestationPolicy].
+        AttestationSecurityLevelTooLow,
+        /// The verified boot state of the attested device is not verified, as required by the [AttestationPolicy].
+        AttestationBootStateNotVerified,
+        /// The bootloader of the attested device is not locked, as required by the [AttestationPolicy].
+        AttestationDeviceNotLocked,
+        /// The OS version of the attested device is lower than required by the [AttestationPolicy].
+        AttestationOsVersionTooLow,
+        /// The OS patch level of the attested device is lower than required by the [AttestationPolicy].
+        AttestationOsPatchLevelTooLow,
+        /// The attested key was created by a package not allowed by the [AttestationPolicy].
+        AttestationPackageNameNotAllowed,
+        /// The attested key was created by an application signed with a certificate not allowed by the [AttestationPolicy].
+        AttestationSignatureDigestNotAllowed,
+        /// The imported certificate revocation list is not valid or not signed by the issuer.
+        CertificateRevocationListValidationFailed,
+        /// The imported certificate revocation list is expired, its `nextUpdate` has passed.
+        CertificateRevocationListExpired,
+        /// The imported certificate revocation list is not newer than the last one imported for its issuer.
+        CertificateRevocationListOutdated,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::migrate::<T>()
+        }
+
+        fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::revoke_attested_sources(remaining_weight)
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -195,10 +327,12 @@ pub mod pallet {
         #[pallet::weight(< T as Config >::WeightInfo::deregister())]
         pub fn deregister(origin: OriginFor<T>, script: Script) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-            <StoredJobRegistration<T>>::remove(&who, &script);
 
+            // call hook first so that it has access to the registration before it is removed
             <T as Config>::JobHooks::deregister_hook(&who, &script)?;
 
+            <StoredJobRegistration<T>>::remove(&who, &script);
+
             Self::deposit_event(Event::JobRegistrationRemoved(script, who));
             Ok(().into())
         }
@@ -279,21 +413,31 @@ pub mod pallet {
 
             let attestation = validate_and_extract_attestation::<T>(&who, &attestation_chain)?;
 
-            if !T::KeyAttestationBarrier::accept_attestation_for_origin(&who, &attestation) {
-                return Err(Error::<T>::AttestationRejected.into());
-            }
+            T::KeyAttestationBarrier::check_attestation_for_origin(&who, &attestation)?;
 
             ensure_not_expired::<T>(&attestation)?;
             ensure_not_revoked::<T>(&attestation)?;
 
+            if let Some(old_attestation) = <StoredAttestation<T>>::get(&who) {
+                for cert_id in &old_attestation.cert_ids {
+                    <StoredAttestedSources<T>>::remove(cert_id, &who);
+                }
+            }
+            for cert_id in &attestation.cert_ids {
+                <StoredAttestedSources<T>>::insert(cert_id, &who, ());
+            }
+            <StoredRevokedAttestation<T>>::remove(&who);
             <StoredAttestation<T>>::insert(&who, attestation.clone());
             Self::deposit_event(Event::AttestationStored(attestation, who));
             Ok(().into())
         }
 
-        /// Updates the certificate revocation list by adding or removing a revoked certificate serial number. Attestations signed
+        /// Updates the certificate revocation list by adding or removing a revoked certificate by its [CertId]. Attestations signed
         /// by a revoked certificate will not be considered valid anymore. The `RevocationListUpdateBarrier` configured in [Config] can be used to
         /// customize who can execute this action.
+        ///
+        /// Revocation: The stored attestations that become invalid are revoked in `on_idle`, emitting [Event::AttestationRevoked] and calling
+        /// [JobHooks::attestation_revoked_hook] for each of them, see [Pallet::revoke_attested_sources].
         #[pallet::weight(<T as Config>::WeightInfo::update_certificate_revocation_list())]
         #[pallet::call_index(6)]
         pub fn update_certificate_revocation_list(
@@ -307,7 +451,7 @@ pub mod pallet {
             for update in &updates {
                 match &update.operation {
                     ListUpdateOperation::Add => {
-                        <StoredRevokedCertificate<T>>::insert(&update.item, ());
+                        Self::revoke_certificate(&update.item);
                     }
                     ListUpdateOperation::Remove => {
                         <StoredRevokedCertificate<T>>::remove(&update.item);
@@ -317,5 +461,222 @@ pub mod pallet {
             Self::deposit_event(Event::CertificateRecovationListUpdated(who, updates));
             Ok(().into())
         }
+
+        /// Sets or removes the [AttestationPolicy] enforced by [AttestationPolicyBarrier]. Can only be called by root.
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_attestation_policy())]
+        pub fn set_attestation_policy(
+            origin: OriginFor<T>,
+            policy: Option<AttestationPolicy>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            <StoredAttestationPolicy<T>>::set(policy.clone());
+            Self::deposit_event(Event::AttestationPolicyUpdated(policy));
+            Ok(().into())
+        }
+
+        /// Updates the roots trusted for attestation certificate chains by adding or removing a [TrustedRootId].
+        /// Attestations already stored are not affected. Can only be called by root.
+        #[pallet::call_index(8)]
+        #[pallet::weight(<T as Config>::WeightInfo::update_trusted_roots())]
+        pub fn update_trusted_roots(
+            origin: OriginFor<T>,
+            updates: Vec<TrustedRootListUpdate>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            for update in &updates {
+                match &update.operation {
+                    ListUpdateOperation::Add => {
+                        <StoredTrustedRoot<T>>::insert(&update.item, ());
+                    }
+                    ListUpdateOperation::Remove => {
+                        <StoredTrustedRoot<T>>::remove(&update.item);
+                    }
+                }
+            }
+            Self::deposit_event(Event::TrustedRootsUpdated(updates));
+            Ok(().into())
+        }
+
+        /// Imports a DER encoded X.509 certificate revocation list by adding its revoked certificates to the certificate
+        /// revocation list. The CRL has to be signed by the last certificate of `issuer_chain`, a CA with the `cRLSign` key
+        /// usage, and `issuer_chain` has to start with a trusted root. CRLs with critical extensions are rejected. Since the
+        /// CRL is verified, it can be imported by any account. Expired CRLs and CRLs not newer than the last one imported for
+        /// the same issuer are rejected.
+        ///
+        /// Attestations signed by a revoked certificate are revoked as with [Pallet::update_certificate_revocation_list].
+        #[pallet::call_index(9)]
+        #[pallet::weight(<T as Config>::WeightInfo::import_certificate_revocation_list(CRL_MAX_ENTRIES))]
+        pub fn import_certificate_revocation_list(
+            origin: OriginFor<T>,
+            issuer_chain: CertificateChainInput,
+            crl: CertificateRevocationListInput,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let crl = validate_certificate_revocation_list_issued_by::<T>(&issuer_chain, &crl)?;
+            <StoredRevocationListUpdate<T>>::insert(&crl.issuer, crl.this_update);
+            let updates = crl
+                .revoked
+                .into_iter()
+                .map(|item| CertificateRevocationListUpdate {
+                    operation: ListUpdateOperation::Add,
+                    item,
+                })
+                .collect::<Vec<_>>();
+            for update in &updates {
+                Self::revoke_certificate(&update.item);
+            }
+            let actual_weight =
+                <T as Config>::WeightInfo::import_certificate_revocation_list(updates.len() as u32);
+            Self::deposit_event(Event::CertificateRecovationListUpdated(who, updates));
+            Ok(Some(actual_weight).into())
+        }
+
+        /// Removes serial numbers revoked for any issuer from [StoredRevokedSerialNumber], which were kept by the migration to
+        /// keying revocations by [CertId]. Can only be called by root, once the certificates with these serial numbers have been
+        /// revoked by their [CertId] for every issuer that should remain revoked.
+        ///
+        /// Removing a certificate with [Pallet::update_certificate_revocation_list] does not affect these serial numbers.
+        #[pallet::call_index(10)]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_revoked_serial_numbers(serial_numbers.len() as u32))]
+        pub fn remove_revoked_serial_numbers(
+            origin: OriginFor<T>,
+            serial_numbers: Vec<SerialNumber>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            for serial_number in &serial_numbers {
+                <StoredRevokedSerialNumber<T>>::remove(serial_number);
+            }
+            Self::deposit_event(Event::RevokedSerialNumbersRemoved(serial_numbers));
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Adds the certificate to the revocation list and queues the revocation of its attested sources, see
+        /// [Pallet::revoke_attested_sources].
+        pub(crate) fn revoke_certificate(cert_id: &CertId) {
+            if <StoredRevokedCertificate<T>>::contains_key(cert_id) {
+                return;
+            }
+            <StoredRevokedCertificate<T>>::insert(cert_id, ());
+            <StoredRevocationQueueRange<T>>::mutate(|(_, tail)| {
+                <StoredRevocationQueue<T>>::insert(*tail, cert_id);
+                *tail = tail.saturating_add(1);
+            });
+        }
+
+        /// Revokes the stored attestations of the sources attested by the certificates in [StoredRevocationQueue], as long as
+        /// `max_weight` allows. Continues from [StoredRevocationCursor] when the sources of a certificate could not all be processed.
+        ///
+        /// Returns the consumed weight.
+        pub(crate) fn revoke_attested_sources(max_weight: Weight) -> Weight {
+            let weight_per_certificate = T::DbWeight::get().reads_writes(1, 1);
+            let weight_per_source = <T as Config>::WeightInfo::revoke_attestation();
+
+            // the range and cursor reads and writes
+            let mut consumed = T::DbWeight::get().reads_writes(2, 2);
+            if consumed.any_gt(max_weight) {
+                return Weight::zero();
+            }
+            let (mut head, tail) = <StoredRevocationQueueRange<T>>::get();
+            if head == tail {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut cursor = <StoredRevocationCursor<T>>::get();
+            while head < tail {
+                if consumed
+                    .saturating_add(weight_per_certificate)
+                    .any_gt(max_weight)
+                {
+                    break;
+                }
+                consumed = consumed.saturating_add(weight_per_certificate);
+                let cert_id = match <StoredRevocationQueue<T>>::get(head) {
+                    Some(cert_id) => cert_id,
+                    None => {
+                        head = head.saturating_add(1);
+                        continue;
+                    }
+                };
+
+                let mut sources = match cursor.take() {
+                    Some(raw_key) => {
+                        <StoredAttestedSources<T>>::iter_key_prefix_from(&cert_id, raw_key)
+                    }
+                    None => <StoredAttestedSources<T>>::iter_key_prefix(&cert_id),
+                };
+                let mut revoked = Vec::new();
+                let complete = loop {
+                    if consumed
+                        .saturating_add(weight_per_source)
+                        .any_gt(max_weight)
+                    {
+                        break false;
+                    }
+                    match sources.next() {
+                        Some(source) => {
+                            consumed = consumed.saturating_add(weight_per_source);
+                            revoked.push(source);
+                        }
+                        None => break true,
+                    }
+                };
+                if !complete {
+                    cursor = Some(sources.last_raw_key().to_vec());
+                }
+
+                for source in revoked {
+                    Self::revoke_attestation(&cert_id, &source);
+                }
+                if !complete {
+                    break;
+                }
+                <StoredRevocationQueue<T>>::remove(head);
+                head = head.saturating_add(1);
+            }
+
+            <StoredRevocationQueueRange<T>>::mutate(|(current_head, _)| *current_head = head);
+            match cursor {
+                Some(raw_key) => <StoredRevocationCursor<T>>::put(raw_key),
+                None => <StoredRevocationCursor<T>>::kill(),
+            }
+            consumed
+        }
+
+        /// Revokes the stored attestation of `source` since `cert_id` got revoked, emitting [Event::AttestationRevoked] and calling
+        /// [JobHooks::attestation_revoked_hook]. Attestations already revoked by another certificate of their chain that is still
+        /// revoked are skipped.
+        fn revoke_attestation(cert_id: &CertId, source: &T::AccountId) {
+            // the certificate might have been removed from the revocation list in the meantime
+            if !is_revoked::<T>(cert_id) {
+                return;
+            }
+            if let Some(revoked_by) = <StoredRevokedAttestation<T>>::get(source) {
+                if &revoked_by != cert_id && is_revoked::<T>(&revoked_by) {
+                    return;
+                }
+            }
+            <StoredRevokedAttestation<T>>::insert(source, cert_id);
+            Self::deposit_event(Event::AttestationRevoked(source.clone()));
+
+            // revert partial changes of a failed hook, as it would happen for a failed extrinsic
+            let result =
+                with_transaction(|| {
+                    match <T as Config>::JobHooks::attestation_revoked_hook(source) {
+                        Ok(()) => TransactionOutcome::Commit(Ok(())),
+                        Err(e) => TransactionOutcome::Rollback(Err(e)),
+                    }
+                });
+            if let Err(e) = result {
+                log::error!(
+                    target: "runtime::acurast",
+                    "failed to revoke the attestation of {:?}: {:?}",
+                    source,
+                    e
+                );
+            }
+        }
     }
 }
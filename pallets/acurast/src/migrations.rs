@@ -0,0 +1,116 @@
+use acurast_common::{certificate_fingerprint, TrustedRootId, DEFAULT_TRUSTED_ROOT_CERTS};
+use frame_support::{
+    traits::{Get, GetStorageVersion},
+    weights::Weight,
+};
+
+use crate::{Config, Pallet, StoredTrustedRoot};
+
+/// Seeds the trusted roots with [DEFAULT_TRUSTED_ROOT_CERTS], which were compiled into the runtime before version 1.
+pub mod v1 {
+    use super::*;
+
+    pub fn migrate<T: Config>() -> Weight {
+        for cert in DEFAULT_TRUSTED_ROOT_CERTS {
+            <StoredTrustedRoot<T>>::insert(
+                TrustedRootId::Fingerprint(certificate_fingerprint(cert)),
+                (),
+            );
+        }
+        T::DbWeight::get().writes(DEFAULT_TRUSTED_ROOT_CERTS.len() as u64)
+    }
+}
+
+/// Keys the revoked certificates by their [acurast_common::CertId] instead of their serial number only.
+///
+/// The issuers of the revoked serial numbers are taken from the stored attestations and the certificates found are revoked
+/// by their [acurast_common::CertId]. Since certificates of other issuers without a stored attestation can use the same
+/// serial number, all revoked serial numbers are kept in [crate::StoredRevokedSerialNumber], revoking the certificates with
+/// that serial number of any issuer, until root removes them with [crate::Pallet::remove_revoked_serial_numbers] once every
+/// issuer of the serial number has been revoked by its [acurast_common::CertId].
+///
+/// Every certificate of the stored attestations is looked up once, so the migration is linear in the number of revoked
+/// serial numbers and stored attestations.
+pub mod v2 {
+    use acurast_common::SerialNumber;
+    use frame_support::{log, storage_alias, Blake2_128Concat};
+
+    use super::*;
+    use crate::{StoredAttestation, StoredRevokedSerialNumber};
+
+    /// The revoked certificates as stored before version 2.
+    #[storage_alias]
+    pub(crate) type StoredRevokedCertificate<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, SerialNumber, ()>;
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut weight = Weight::zero();
+        let mut revoked: u64 = 0;
+        for (serial_number, _) in StoredRevokedCertificate::<T>::drain() {
+            <StoredRevokedSerialNumber<T>>::insert(serial_number, ());
+            revoked += 1;
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+        }
+
+        let mut rekeyed: u64 = 0;
+        for (_, attestation) in <StoredAttestation<T>>::iter() {
+            weight = weight
+                .saturating_add(T::DbWeight::get().reads(1 + attestation.cert_ids.len() as u64));
+            for cert_id in attestation.cert_ids {
+                if <StoredRevokedSerialNumber<T>>::contains_key(&cert_id.1) {
+                    <crate::StoredRevokedCertificate<T>>::insert(cert_id, ());
+                    rekeyed += 1;
+                    weight = weight.saturating_add(T::DbWeight::get().writes(1));
+                }
+            }
+        }
+
+        if revoked > 0 {
+            log::warn!(
+                target: "runtime::acurast",
+                "kept {} revoked serial numbers for any issuer, {} certificates of stored attestations revoked by their issuer",
+                revoked,
+                rekeyed
+            );
+        }
+        weight
+    }
+}
+
+/// Indexes the stored attestations by their [acurast_common::CertId]s in [crate::StoredAttestedSources].
+pub mod v3 {
+    use super::*;
+    use crate::{StoredAttestation, StoredAttestedSources};
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut weight = Weight::zero();
+        for (source, attestation) in <StoredAttestation<T>>::iter() {
+            for cert_id in &attestation.cert_ids {
+                <StoredAttestedSources<T>>::insert(cert_id, &source, ());
+            }
+            weight = weight.saturating_add(
+                T::DbWeight::get().reads_writes(1, attestation.cert_ids.len() as u64),
+            );
+        }
+        weight
+    }
+}
+
+/// Runs the migrations required to bring the storage of the pallet to its in-code version.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+
+    if on_chain_version < 1 {
+        weight = weight.saturating_add(v1::migrate::<T>());
+    }
+    if on_chain_version < 2 {
+        weight = weight.saturating_add(v2::migrate::<T>());
+    }
+    if on_chain_version < 3 {
+        weight = weight.saturating_add(v3::migrate::<T>());
+    }
+
+    Pallet::<T>::current_storage_version().put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
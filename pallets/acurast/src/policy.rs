@@ -0,0 +1,183 @@
+use frame_support::pallet_prelude::*;
+
+use crate::{
+    Attestation, AttestationSecurityLevel, BoundedAttestationApplicationId,
+    BoundedAuthorizationList, Config, Error, KeyAttestationBarrier, PackageName,
+    SignatureDigestSet, StoredAttestationPolicy, VerifiedBootState,
+};
+
+pub(crate) const ALLOWED_PACKAGE_NAMES_MAX_LENGTH: u32 = 16;
+
+pub type AllowedPackageNames = BoundedVec<PackageName, ConstU32<ALLOWED_PACKAGE_NAMES_MAX_LENGTH>>;
+
+/// The rules key attestations have to satisfy to be accepted by [AttestationPolicyBarrier].
+///
+/// Rules that are [None] or `false` are not checked. The root of trust, OS version and patch level are taken from the
+/// TEE enforced authorization list, the attestation application id from either list.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, Default)]
+pub struct AttestationPolicy {
+    /// The minimum security level of the attestation, [AttestationSecurityLevel::StrongBox] being the highest.
+    pub min_security_level: Option<AttestationSecurityLevel>,
+    /// Requires the verified boot state to be [VerifiedBootState::Verified].
+    pub require_verified_boot: bool,
+    /// Requires the bootloader of the device to be locked.
+    pub require_device_locked: bool,
+    /// The minimum OS version, e.g. `130000` for Android 13.
+    pub min_os_version: Option<u32>,
+    /// The minimum OS patch level in the format `YYYYMM`.
+    pub min_os_patch_level: Option<u32>,
+    /// The package names of the applications allowed to create the attested key.
+    pub allowed_package_names: Option<AllowedPackageNames>,
+    /// The digests of the signing certificates of the applications allowed to create the attested key.
+    pub allowed_signature_digests: Option<SignatureDigestSet>,
+}
+
+/// The rule of an [AttestationPolicy] an attestation does not satisfy.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq)]
+pub enum AttestationPolicyViolation {
+    SecurityLevelTooLow,
+    BootStateNotVerified,
+    DeviceNotLocked,
+    OsVersionTooLow,
+    OsPatchLevelTooLow,
+    PackageNameNotAllowed,
+    SignatureDigestNotAllowed,
+}
+
+impl AttestationPolicy {
+    /// Checks the `attestation` against the policy, returning the first rule it does not satisfy.
+    pub fn check(&self, attestation: &Attestation) -> Result<(), AttestationPolicyViolation> {
+        let key_description = &attestation.key_description;
+        let tee_enforced = &key_description.tee_enforced;
+
+        if let Some(min_security_level) = &self.min_security_level {
+            ensure!(
+                security_level_rank(&key_description.attestation_security_level)
+                    >= security_level_rank(min_security_level),
+                AttestationPolicyViolation::SecurityLevelTooLow
+            );
+        }
+        if self.require_verified_boot {
+            ensure!(
+                tee_enforced
+                    .root_of_trust
+                    .as_ref()
+                    .map(|root| &root.verified_boot_state)
+                    == Some(&VerifiedBootState::Verified),
+                AttestationPolicyViolation::BootStateNotVerified
+            );
+        }
+        if self.require_device_locked {
+            ensure!(
+                tee_enforced
+                    .root_of_trust
+                    .as_ref()
+                    .map_or(false, |root| root.device_locked),
+                AttestationPolicyViolation::DeviceNotLocked
+            );
+        }
+        if let Some(min_os_version) = self.min_os_version {
+            ensure!(
+                tee_enforced
+                    .os_version
+                    .map_or(false, |version| version >= min_os_version),
+                AttestationPolicyViolation::OsVersionTooLow
+            );
+        }
+        if let Some(min_os_patch_level) = self.min_os_patch_level {
+            ensure!(
+                tee_enforced
+                    .os_patch_level
+                    .map_or(false, |patch_level| patch_level >= min_os_patch_level),
+                AttestationPolicyViolation::OsPatchLevelTooLow
+            );
+        }
+
+        let application_id = attestation_application_id(attestation);
+        if let Some(allowed_package_names) = &self.allowed_package_names {
+            ensure!(
+                application_id.map_or(false, |id| !id.package_infos.is_empty()
+                    && id
+                        .package_infos
+                        .iter()
+                        .all(|info| allowed_package_names.contains(&info.package_name))),
+                AttestationPolicyViolation::PackageNameNotAllowed
+            );
+        }
+        if let Some(allowed_signature_digests) = &self.allowed_signature_digests {
+            ensure!(
+                application_id.map_or(false, |id| !id.signature_digests.is_empty()
+                    && id
+                        .signature_digests
+                        .iter()
+                        .all(|digest| allowed_signature_digests.contains(digest))),
+                AttestationPolicyViolation::SignatureDigestNotAllowed
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn security_level_rank(level: &AttestationSecurityLevel) -> u8 {
+    match level {
+        AttestationSecurityLevel::Unknown => 0,
+        AttestationSecurityLevel::Software => 1,
+        AttestationSecurityLevel::TrustedEnvironemnt => 2,
+        AttestationSecurityLevel::StrongBox => 3,
+    }
+}
+
+/// Returns the attestation application id, which is usually part of the software enforced authorization list.
+fn attestation_application_id(
+    attestation: &Attestation,
+) -> Option<&BoundedAttestationApplicationId> {
+    let lists: [&BoundedAuthorizationList; 2] = [
+        &attestation.key_description.software_enforced,
+        &attestation.key_description.tee_enforced,
+    ];
+    lists
+        .into_iter()
+        .find_map(|list| list.attestation_application_id.as_ref())
+}
+
+/// A [KeyAttestationBarrier] accepting the attestations that satisfy the [AttestationPolicy] set with
+/// [crate::Pallet::set_attestation_policy]. All attestations are accepted as long as no policy is set.
+pub struct AttestationPolicyBarrier;
+
+impl<T: Config> KeyAttestationBarrier<T> for AttestationPolicyBarrier {
+    fn accept_attestation_for_origin(origin: &T::AccountId, attestation: &Attestation) -> bool {
+        <Self as KeyAttestationBarrier<T>>::check_attestation_for_origin(origin, attestation)
+            .is_ok()
+    }
+
+    fn check_attestation_for_origin(
+        _origin: &T::AccountId,
+        attestation: &Attestation,
+    ) -> Result<(), Error<T>> {
+        match <StoredAttestationPolicy<T>>::get() {
+            Some(policy) => policy.check(attestation).map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Config> From<AttestationPolicyViolation> for Error<T> {
+    fn from(violation: AttestationPolicyViolation) -> Self {
+        match violation {
+            AttestationPolicyViolation::SecurityLevelTooLow => Self::AttestationSecurityLevelTooLow,
+            AttestationPolicyViolation::BootStateNotVerified => {
+                Self::AttestationBootStateNotVerified
+            }
+            AttestationPolicyViolation::DeviceNotLocked => Self::AttestationDeviceNotLocked,
+            AttestationPolicyViolation::OsVersionTooLow => Self::AttestationOsVersionTooLow,
+            AttestationPolicyViolation::OsPatchLevelTooLow => Self::AttestationOsPatchLevelTooLow,
+            AttestationPolicyViolation::PackageNameNotAllowed => {
+                Self::AttestationPackageNameNotAllowed
+            }
+            AttestationPolicyViolation::SignatureDigestNotAllowed => {
+                Self::AttestationSignatureDigestNotAllowed
+            }
+        }
+    }
+}
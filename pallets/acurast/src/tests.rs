@@ -1,10 +1,22 @@
 #![cfg(test)]
 
 use crate::{
-    mock::*, utils::validate_and_extract_attestation, AllowedSourcesUpdate,
-    CertificateRevocationListUpdate, Error, ListUpdateOperation, SerialNumber,
+    certificate_fingerprint, migrations,
+    mock::*,
+    trusted_root_ids,
+    utils::{ensure_source_verified, validate_and_extract_attestation},
+    AllowedSourcesUpdate, AttestationPolicy, AttestationSecurityLevel, CertificateChainInput,
+    CertificateRevocationListInput, CertificateRevocationListUpdate, Error, IssuerName,
+    ListUpdateOperation, SerialNumber, StoredAttestedSources, StoredRevokedSerialNumber,
+    TrustedRootId, TrustedRootListUpdate, WeightInfo,
 };
-use frame_support::{assert_err, assert_ok};
+use frame_support::{
+    assert_err, assert_ok,
+    sp_runtime::DispatchError,
+    traits::{GetStorageVersion, Hooks, StorageVersion},
+    weights::Weight,
+};
+use hex_literal::hex;
 
 #[test]
 fn test_job_registration() {
@@ -364,34 +376,178 @@ fn test_submit_attestation_failure_3() {
     });
 }
 
+#[test]
+fn test_submit_attestation_with_policy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let policy = AttestationPolicy {
+            min_security_level: Some(AttestationSecurityLevel::StrongBox),
+            require_verified_boot: true,
+            require_device_locked: true,
+            min_os_version: Some(120000),
+            min_os_patch_level: Some(202206),
+            ..Default::default()
+        };
+        assert_err!(
+            Acurast::set_attestation_policy(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                Some(policy.clone())
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Acurast::set_attestation_policy(
+            RuntimeOrigin::root(),
+            Some(policy.clone())
+        ));
+        assert_eq!(Some(policy.clone()), Acurast::stored_attestation_policy());
+
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+
+        let attestation =
+            validate_and_extract_attestation::<Test>(&processor_account_id(), &attestation_chain())
+                .unwrap();
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::Acurast(crate::Event::AttestationPolicyUpdated(Some(policy))),
+                RuntimeEvent::Acurast(crate::Event::AttestationStored(
+                    attestation,
+                    processor_account_id()
+                )),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_submit_attestation_rejected_by_policy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+
+        // the attested device runs Android 12
+        assert_ok!(Acurast::set_attestation_policy(
+            RuntimeOrigin::root(),
+            Some(AttestationPolicy {
+                min_os_version: Some(130000),
+                ..Default::default()
+            })
+        ));
+        assert_err!(
+            Acurast::submit_attestation(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                attestation_chain()
+            ),
+            Error::<Test>::AttestationOsVersionTooLow
+        );
+
+        assert_ok!(Acurast::set_attestation_policy(
+            RuntimeOrigin::root(),
+            Some(AttestationPolicy {
+                allowed_package_names: Some(
+                    vec![b"com.example.app".to_vec().try_into().unwrap()]
+                        .try_into()
+                        .unwrap()
+                ),
+                ..Default::default()
+            })
+        ));
+        assert_err!(
+            Acurast::submit_attestation(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                attestation_chain()
+            ),
+            Error::<Test>::AttestationPackageNameNotAllowed
+        );
+
+        // without a policy, all attestations are accepted
+        assert_ok!(Acurast::set_attestation_policy(RuntimeOrigin::root(), None));
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+    });
+}
+
+#[test]
+fn test_update_trusted_roots() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        let fingerprint = TrustedRootId::Fingerprint(certificate_fingerprint(&ROOT_CERT));
+        let [_, subject_key] = trusted_root_ids(&ROOT_CERT).unwrap();
+        assert!(Acurast::stored_trusted_root(fingerprint).is_some());
+
+        let updates_1 = vec![TrustedRootListUpdate {
+            operation: ListUpdateOperation::Remove,
+            item: fingerprint,
+        }];
+        assert_err!(
+            Acurast::update_trusted_roots(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                updates_1.clone()
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Acurast::update_trusted_roots(
+            RuntimeOrigin::root(),
+            updates_1.clone()
+        ));
+        assert_err!(
+            Acurast::submit_attestation(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                attestation_chain()
+            ),
+            Error::<Test>::RootCertificateValidationFailed
+        );
+
+        // trusting the subject key of the root trusts the root again
+        let updates_2 = vec![TrustedRootListUpdate {
+            operation: ListUpdateOperation::Add,
+            item: subject_key,
+        }];
+        assert_ok!(Acurast::update_trusted_roots(
+            RuntimeOrigin::root(),
+            updates_2.clone()
+        ));
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+
+        assert_eq!(
+            events()[..2],
+            [
+                RuntimeEvent::Acurast(crate::Event::TrustedRootsUpdated(updates_1)),
+                RuntimeEvent::Acurast(crate::Event::TrustedRootsUpdated(updates_2)),
+            ]
+        );
+    });
+}
+
 #[test]
 fn test_update_revocation_list() {
     ExtBuilder::default().build().execute_with(|| {
         let updates_1 = vec![CertificateRevocationListUpdate {
             operation: ListUpdateOperation::Add,
-            item: cert_serial_number(),
+            item: cert_id(),
         }];
         assert_ok!(Acurast::update_certificate_revocation_list(
             RuntimeOrigin::signed(alice_account_id()).into(),
             updates_1.clone(),
         ));
-        assert_eq!(
-            Some(()),
-            Acurast::stored_revoked_certificate::<SerialNumber>(cert_serial_number())
-        );
+        assert_eq!(Some(()), Acurast::stored_revoked_certificate(cert_id()));
 
         let updates_2 = vec![CertificateRevocationListUpdate {
             operation: ListUpdateOperation::Remove,
-            item: cert_serial_number(),
+            item: cert_id(),
         }];
         assert_ok!(Acurast::update_certificate_revocation_list(
             RuntimeOrigin::signed(alice_account_id()).into(),
             updates_2.clone(),
         ));
-        assert_eq!(
-            None,
-            Acurast::stored_revoked_certificate::<SerialNumber>(cert_serial_number())
-        );
+        assert_eq!(None, Acurast::stored_revoked_certificate(cert_id()));
 
         assert_err!(
             Acurast::update_certificate_revocation_list(
@@ -400,10 +556,7 @@ fn test_update_revocation_list() {
             ),
             Error::<Test>::CertificateRevocationListUpdateNotAllowed
         );
-        assert_eq!(
-            None,
-            Acurast::stored_revoked_certificate::<SerialNumber>(cert_serial_number())
-        );
+        assert_eq!(None, Acurast::stored_revoked_certificate(cert_id()));
 
         assert_eq!(
             events(),
@@ -426,7 +579,7 @@ fn test_update_revocation_list_submit_attestation() {
     ExtBuilder::default().build().execute_with(|| {
         let updates = vec![CertificateRevocationListUpdate {
             operation: ListUpdateOperation::Add,
-            item: cert_serial_number(),
+            item: cert_id(),
         }];
         assert_ok!(Acurast::update_certificate_revocation_list(
             RuntimeOrigin::signed(alice_account_id()).into(),
@@ -457,7 +610,7 @@ fn test_update_revocation_list_assign_job() {
     ExtBuilder::default().build().execute_with(|| {
         let updates = vec![CertificateRevocationListUpdate {
             operation: ListUpdateOperation::Add,
-            item: cert_serial_number(),
+            item: cert_id(),
         }];
         let chain = attestation_chain();
         let registration = job_registration(None, true);
@@ -474,6 +627,7 @@ fn test_update_revocation_list_assign_job() {
             RuntimeOrigin::signed(alice_account_id()).into(),
             updates.clone(),
         ));
+        Acurast::on_idle(1, Weight::MAX);
 
         let attestation =
             validate_and_extract_attestation::<Test>(&processor_account_id(), &chain).unwrap();
@@ -493,7 +647,361 @@ fn test_update_revocation_list_assign_job() {
                     alice_account_id(),
                     updates
                 )),
+                RuntimeEvent::Acurast(crate::Event::AttestationRevoked(processor_account_id())),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_update_revocation_list_revoke_attestation() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+        let cert_ids = Acurast::stored_attestation(processor_account_id())
+            .unwrap()
+            .cert_ids;
+        for cert_id in &cert_ids {
+            assert_eq!(
+                Acurast::stored_attested_sources(cert_id, processor_account_id()),
+                Some(())
+            );
+        }
+
+        let updates = vec![CertificateRevocationListUpdate {
+            operation: ListUpdateOperation::Add,
+            item: cert_id(),
+        }];
+        assert_ok!(Acurast::update_certificate_revocation_list(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            updates.clone(),
+        ));
+        Acurast::on_idle(1, Weight::MAX);
+        assert_err!(
+            ensure_source_verified::<Test>(&processor_account_id()),
+            Error::<Test>::RevokedCertificate
+        );
+
+        // the attestation is already revoked, revoking another certificate of its chain does not revoke it again
+        let other_cert_id = cert_ids.into_iter().find(|id| id != &cert_id()).unwrap();
+        let other_updates = vec![CertificateRevocationListUpdate {
+            operation: ListUpdateOperation::Add,
+            item: other_cert_id,
+        }];
+        assert_ok!(Acurast::update_certificate_revocation_list(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            other_updates.clone(),
+        ));
+        Acurast::on_idle(2, Weight::MAX);
+
+        assert_eq!(
+            events()[1..],
+            [
+                RuntimeEvent::Acurast(crate::Event::CertificateRecovationListUpdated(
+                    alice_account_id(),
+                    updates
+                )),
+                RuntimeEvent::Acurast(crate::Event::AttestationRevoked(processor_account_id())),
+                RuntimeEvent::Acurast(crate::Event::CertificateRecovationListUpdated(
+                    alice_account_id(),
+                    other_updates
+                )),
             ]
         );
     });
 }
+
+#[test]
+fn test_revoke_attested_sources_bounded_by_weight() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+        assert_ok!(Acurast::update_certificate_revocation_list(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            vec![CertificateRevocationListUpdate {
+                operation: ListUpdateOperation::Add,
+                item: cert_id(),
+            }],
+        ));
+        // the certificate is revoked right away, its attested sources are revoked in on_idle
+        assert_eq!(Acurast::stored_revoked_certificate(cert_id()), Some(()));
+        assert_eq!(Acurast::stored_revocation_queue(0), Some(cert_id()));
+        assert_eq!(Acurast::stored_revocation_queue_range(), (0, 1));
+
+        let weight_per_source = <Test as crate::Config>::WeightInfo::revoke_attestation();
+        Acurast::on_idle(1, weight_per_source - Weight::from_ref_time(1));
+        assert_eq!(Acurast::stored_revocation_queue_range(), (0, 1));
+        assert!(Acurast::stored_revocation_cursor().is_some());
+        assert_eq!(
+            Acurast::stored_revoked_attestation(processor_account_id()),
+            None
+        );
+
+        Acurast::on_idle(2, weight_per_source);
+        assert_eq!(Acurast::stored_revocation_queue(0), None);
+        assert_eq!(Acurast::stored_revocation_queue_range(), (1, 1));
+        assert_eq!(Acurast::stored_revocation_cursor(), None);
+        assert_eq!(
+            Acurast::stored_revoked_attestation(processor_account_id()),
+            Some(cert_id())
+        );
+        assert_eq!(
+            events().last(),
+            Some(&RuntimeEvent::Acurast(crate::Event::AttestationRevoked(
+                processor_account_id()
+            )))
+        );
+    });
+}
+
+#[test]
+fn test_import_certificate_revocation_list() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        let issuer_chain: CertificateChainInput =
+            vec![CRL_ISSUER_CERT.to_vec().try_into().unwrap()]
+                .try_into()
+                .unwrap();
+        let crl: CertificateRevocationListInput = CRL.to_vec().try_into().unwrap();
+
+        assert_err!(
+            Acurast::import_certificate_revocation_list(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                issuer_chain.clone(),
+                crl.clone()
+            ),
+            Error::<Test>::RootCertificateValidationFailed
+        );
+
+        assert_ok!(Acurast::update_trusted_roots(
+            RuntimeOrigin::root(),
+            vec![TrustedRootListUpdate {
+                operation: ListUpdateOperation::Add,
+                item: TrustedRootId::Fingerprint(certificate_fingerprint(&CRL_ISSUER_CERT)),
+            }]
+        ));
+
+        let mut tampered_crl = CRL;
+        tampered_crl[254] ^= 1;
+        assert_err!(
+            Acurast::import_certificate_revocation_list(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                issuer_chain.clone(),
+                tampered_crl.to_vec().try_into().unwrap()
+            ),
+            Error::<Test>::CertificateRevocationListValidationFailed
+        );
+
+        // any account can import a valid CRL
+        assert_ok!(Acurast::import_certificate_revocation_list(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            issuer_chain.clone(),
+            crl.clone()
+        ));
+
+        let issuer_name = IssuerName::truncate_from(
+            hex!("301a311830160603550405130f6163757261737474657374726f6f74").to_vec(),
+        );
+        let updates: Vec<CertificateRevocationListUpdate> = vec![
+            hex!("00c8966fcb2fbb0d7a").to_vec(),
+            hex!("15905857467176635834").to_vec(),
+        ]
+        .into_iter()
+        .map(|serial_number| CertificateRevocationListUpdate {
+            operation: ListUpdateOperation::Add,
+            item: (
+                issuer_name.clone(),
+                SerialNumber::truncate_from(serial_number),
+            ),
+        })
+        .collect();
+        for update in &updates {
+            assert_eq!(Acurast::stored_revoked_certificate(&update.item), Some(()));
+        }
+        assert_eq!(
+            events().last(),
+            Some(&RuntimeEvent::Acurast(
+                crate::Event::CertificateRecovationListUpdated(alice_account_id(), updates)
+            ))
+        );
+
+        assert_eq!(
+            Acurast::stored_revocation_list_update(&issuer_name),
+            Some(1_792_203_167_000)
+        );
+        // the same CRL cannot be imported again, only newer ones of the issuer
+        assert_err!(
+            Acurast::import_certificate_revocation_list(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                issuer_chain,
+                crl
+            ),
+            Error::<Test>::CertificateRevocationListOutdated
+        );
+
+        // the CRL revokes the serial number of a certificate in the attestation chain, but for a different issuer
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+    });
+}
+
+#[test]
+fn test_import_expired_certificate_revocation_list() {
+    ExtBuilder::default().build().execute_with(|| {
+        // after the CRL's nextUpdate 2036-10-14T02:12:47Z
+        let _ = Timestamp::set(RuntimeOrigin::none(), 2_107_563_167_000);
+        assert_ok!(Acurast::update_trusted_roots(
+            RuntimeOrigin::root(),
+            vec![TrustedRootListUpdate {
+                operation: ListUpdateOperation::Add,
+                item: TrustedRootId::Fingerprint(certificate_fingerprint(&CRL_ISSUER_CERT)),
+            }]
+        ));
+
+        assert_err!(
+            Acurast::import_certificate_revocation_list(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                vec![CRL_ISSUER_CERT.to_vec().try_into().unwrap()]
+                    .try_into()
+                    .unwrap(),
+                CRL.to_vec().try_into().unwrap()
+            ),
+            Error::<Test>::CertificateRevocationListExpired
+        );
+    });
+}
+
+#[test]
+fn test_submit_attestation_revoked_serial_number() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        // a serial number kept by the migration to version 2 revokes certificates of any issuer
+        StoredRevokedSerialNumber::<Test>::insert(cert_serial_number(), ());
+        assert_err!(
+            Acurast::submit_attestation(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                attestation_chain()
+            ),
+            Error::<Test>::RevokedCertificate
+        );
+    });
+}
+
+#[test]
+fn test_remove_revoked_serial_numbers() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        StoredRevokedSerialNumber::<Test>::insert(cert_serial_number(), ());
+
+        // removing the certificate of an issuer keeps the serial number revoked for any issuer
+        assert_ok!(Acurast::update_certificate_revocation_list(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            vec![CertificateRevocationListUpdate {
+                operation: ListUpdateOperation::Remove,
+                item: cert_id(),
+            }],
+        ));
+        assert_eq!(
+            Acurast::stored_revoked_serial_number(cert_serial_number()),
+            Some(())
+        );
+        assert_err!(
+            Acurast::submit_attestation(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                attestation_chain()
+            ),
+            Error::<Test>::RevokedCertificate
+        );
+
+        // only root can remove the serial number
+        assert_err!(
+            Acurast::remove_revoked_serial_numbers(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                vec![cert_serial_number()],
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Acurast::remove_revoked_serial_numbers(
+            RuntimeOrigin::root(),
+            vec![cert_serial_number()],
+        ));
+        assert_eq!(
+            Acurast::stored_revoked_serial_number(cert_serial_number()),
+            None
+        );
+        assert_eq!(
+            events().last(),
+            Some(&RuntimeEvent::Acurast(
+                crate::Event::RevokedSerialNumbersRemoved(vec![cert_serial_number()])
+            ))
+        );
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+    });
+}
+
+#[test]
+fn test_migrate_revoked_serial_numbers() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+
+        StorageVersion::new(1).put::<Acurast>();
+        let unknown_serial_number = SerialNumber::truncate_from(hex!("0a01").to_vec());
+        migrations::v2::StoredRevokedCertificate::<Test>::insert(cert_serial_number(), ());
+        migrations::v2::StoredRevokedCertificate::<Test>::insert(&unknown_serial_number, ());
+
+        migrations::migrate::<Test>();
+
+        assert_eq!(Acurast::stored_revoked_certificate(cert_id()), Some(()));
+        // the serial numbers are kept for any issuer, since other issuers might use them as well
+        assert_eq!(
+            Acurast::stored_revoked_serial_number(cert_serial_number()),
+            Some(())
+        );
+        assert_eq!(
+            Acurast::stored_revoked_serial_number(&unknown_serial_number),
+            Some(())
+        );
+        assert_eq!(
+            migrations::v2::StoredRevokedCertificate::<Test>::iter_keys().count(),
+            0
+        );
+        assert_eq!(Acurast::on_chain_storage_version(), StorageVersion::new(3));
+    });
+}
+
+#[test]
+fn test_migrate_attested_sources() {
+    ExtBuilder::default().build().execute_with(|| {
+        let _ = Timestamp::set(RuntimeOrigin::none(), 1657363915001);
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain()
+        ));
+
+        StorageVersion::new(2).put::<Acurast>();
+        let _ = StoredAttestedSources::<Test>::clear(u32::MAX, None);
+
+        migrations::migrate::<Test>();
+
+        assert_eq!(
+            Acurast::stored_attested_sources(cert_id(), processor_account_id()),
+            Some(())
+        );
+        assert_eq!(Acurast::on_chain_storage_version(), StorageVersion::new(3));
+    });
+}
@@ -27,6 +27,18 @@ impl<T: Config> RevocationListUpdateBarrier<T> for () {
 /// Allows to customize the kind of key attestations that are accepted.
 pub trait KeyAttestationBarrier<T: Config> {
     fn accept_attestation_for_origin(origin: &T::AccountId, attestation: &Attestation) -> bool;
+
+    /// Checks the attestation like [KeyAttestationBarrier::accept_attestation_for_origin], returning the reason of a rejection.
+    fn check_attestation_for_origin(
+        origin: &T::AccountId,
+        attestation: &Attestation,
+    ) -> Result<(), Error<T>> {
+        if Self::accept_attestation_for_origin(origin, attestation) {
+            Ok(())
+        } else {
+            Err(Error::<T>::AttestationRejected)
+        }
+    }
 }
 
 impl<T: Config> KeyAttestationBarrier<T> for () {
@@ -41,6 +53,11 @@ pub trait WeightInfo {
     fn update_allowed_sources() -> Weight;
     fn submit_attestation() -> Weight;
     fn update_certificate_revocation_list() -> Weight;
+    fn set_attestation_policy() -> Weight;
+    fn update_trusted_roots() -> Weight;
+    fn import_certificate_revocation_list(x: u32) -> Weight;
+    fn revoke_attestation() -> Weight;
+    fn remove_revoked_serial_numbers(x: u32) -> Weight;
 }
 
 /// Allows to hook additional logic for various job related extrinsics.
@@ -58,6 +75,10 @@ pub trait JobHooks<T: Config> {
         script: &Script,
         updates: &Vec<AllowedSourcesUpdate<<T as frame_system::Config>::AccountId>>,
     ) -> Result<(), DispatchError>;
+    /// Called for each source whose stored attestation got invalid due to a revoked certificate.
+    fn attestation_revoked_hook(
+        source: &<T as frame_system::Config>::AccountId,
+    ) -> Result<(), DispatchError>;
 }
 
 impl<T: Config> JobHooks<T> for () {
@@ -80,6 +101,11 @@ impl<T: Config> JobHooks<T> for () {
     ) -> Result<(), DispatchError> {
         Ok(())
     }
+    fn attestation_revoked_hook(
+        _source: &<T as frame_system::Config>::AccountId,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
 }
 
 impl<T: Config> From<()> for Error<T> {
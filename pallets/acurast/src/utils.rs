@@ -1,6 +1,7 @@
 use acurast_common::{
-    extract_attestation, validate_certificate_chain, validate_certificate_chain_root, ECDSACurve,
-    PublicKey,
+    extract_attestation, validate_certificate_chain, validate_certificate_chain_root,
+    validate_certificate_revocation_list, CertificateChainInput, CertificateId, ECDSACurve,
+    PublicKey, ValidatedRevocationList,
 };
 use codec::Encode;
 use frame_support::{ensure, traits::UnixTime};
@@ -8,7 +9,8 @@ use sp_std::prelude::*;
 
 use crate::{
     Attestation, AttestationChain, AttestationValidity, CertId, Config, Error, IssuerName,
-    SerialNumber, StoredAttestation, StoredRevokedCertificate, ValidatingCertIds,
+    SerialNumber, StoredAttestation, StoredRevocationListUpdate, StoredRevokedCertificate,
+    StoredRevokedSerialNumber, StoredTrustedRoot, ValidatingCertIds,
 };
 
 /// Validates and returns an [Attestation] from the provided chain.
@@ -16,8 +18,10 @@ pub fn validate_and_extract_attestation<T: Config>(
     source: &T::AccountId,
     attestation_chain: &AttestationChain,
 ) -> Result<Attestation, Error<T>> {
-    validate_certificate_chain_root(&attestation_chain.certificate_chain)
-        .map_err(|_| Error::<T>::RootCertificateValidationFailed)?;
+    validate_certificate_chain_root(&attestation_chain.certificate_chain, |id| {
+        <StoredTrustedRoot<T>>::contains_key(id)
+    })
+    .map_err(|_| Error::<T>::RootCertificateValidationFailed)?;
 
     let (cert_ids, cert, public_key) =
         validate_certificate_chain(&attestation_chain.certificate_chain)
@@ -35,14 +39,7 @@ pub fn validate_and_extract_attestation<T: Config>(
 
     let cert_ids_bounded = cert_ids
         .into_iter()
-        .map(|cert_id| {
-            let (iss, sn) = cert_id;
-            let iss_bounded = IssuerName::try_from(iss)
-                .map_err(|_| Error::<T>::CannotGetAttestationIssuerName)?;
-            let sn_bounded = SerialNumber::try_from(sn)
-                .map_err(|_| Error::<T>::CannotGetAttestationSerialNumber)?;
-            Ok((iss_bounded, sn_bounded))
-        })
+        .map(bounded_cert_id::<T>)
         .collect::<Result<Vec<CertId>, Error<T>>>()?;
     let cert_ids_bounded_vec = ValidatingCertIds::try_from(cert_ids_bounded)
         .map_err(|_| Error::<T>::CannotGetCertificateId)?;
@@ -56,6 +53,16 @@ pub fn validate_and_extract_attestation<T: Config>(
     })
 }
 
+/// Converts a [CertificateId] to its bounded [CertId].
+fn bounded_cert_id<T: Config>(cert_id: CertificateId) -> Result<CertId, Error<T>> {
+    let (iss, sn) = cert_id;
+    let iss_bounded =
+        IssuerName::try_from(iss).map_err(|_| Error::<T>::CannotGetAttestationIssuerName)?;
+    let sn_bounded =
+        SerialNumber::try_from(sn).map_err(|_| Error::<T>::CannotGetAttestationSerialNumber)?;
+    Ok((iss_bounded, sn_bounded))
+}
+
 /// Ensures that the provided account id has a valid (not expired and not revoked) key attestation.
 pub fn ensure_source_verified<T: Config>(source: &T::AccountId) -> Result<(), Error<T>> {
     let attestation =
@@ -97,13 +104,63 @@ pub(crate) fn ensure_not_expired<T: Config>(attestation: &Attestation) -> Result
 pub(crate) fn ensure_not_revoked<T: Config>(attestation: &Attestation) -> Result<(), Error<T>> {
     let ids = &attestation.cert_ids;
     for id in ids {
-        if <StoredRevokedCertificate<T>>::get(&id.1).is_some() {
+        if is_revoked::<T>(id) {
             return Err(Error::<T>::RevokedCertificate);
         }
     }
     Ok(())
 }
 
+/// Returns whether the certificate is revoked, including the serial numbers revoked without their issuer.
+pub(crate) fn is_revoked<T: Config>(cert_id: &CertId) -> bool {
+    <StoredRevokedCertificate<T>>::contains_key(cert_id)
+        || <StoredRevokedSerialNumber<T>>::contains_key(&cert_id.1)
+}
+
+/// Validates the `crl` issued by the last certificate of `issuer_chain` and returns it with the [CertId]s of the revoked certificates.
+///
+/// The issuer chain has to start with a trusted root and none of its certificates may be revoked. Its last certificate has
+/// to be a CA allowed to sign CRLs, see [validate_certificate_revocation_list]. The CRL must not be expired, i.e. its
+/// `nextUpdate` must not have passed, and it has to be newer than the last CRL imported for its issuer, see [StoredRevocationListUpdate].
+pub fn validate_certificate_revocation_list_issued_by<T: Config>(
+    issuer_chain: &CertificateChainInput,
+    crl: &[u8],
+) -> Result<ValidatedRevocationList, Error<T>> {
+    validate_certificate_chain_root(issuer_chain, |id| <StoredTrustedRoot<T>>::contains_key(id))
+        .map_err(|_| Error::<T>::RootCertificateValidationFailed)?;
+
+    let (cert_ids, issuer, issuer_pbk) = validate_certificate_chain(issuer_chain)
+        .map_err(|_| Error::<T>::CertificateChainValidationFailed)?;
+    for cert_id in cert_ids {
+        ensure!(
+            !is_revoked::<T>(&bounded_cert_id::<T>(cert_id)?),
+            Error::<T>::RevokedCertificate
+        );
+    }
+
+    let crl = validate_certificate_revocation_list(crl, &issuer, &issuer_pbk)
+        .map_err(|_| Error::<T>::CertificateRevocationListValidationFailed)?;
+
+    let now: u64 = T::UnixTime::now()
+        .as_millis()
+        .try_into()
+        .map_err(|_| Error::<T>::FailedTimestampConversion)?;
+    if let Some(next_update) = crl.next_update {
+        ensure!(
+            now < next_update,
+            Error::<T>::CertificateRevocationListExpired
+        );
+    }
+    // an older CRL could be replayed to pass as the current one
+    if let Some(last_update) = <StoredRevocationListUpdate<T>>::get(&crl.issuer) {
+        ensure!(
+            crl.this_update > last_update,
+            Error::<T>::CertificateRevocationListOutdated
+        );
+    }
+    Ok(crl)
+}
+
 /// Ensures the provided public key correponds to the provided account id.
 fn ensure_valid_public_key_for_source<T: Config>(
     source: &T::AccountId,
@@ -49,17 +49,69 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: Acurast StoredTrustedRoot (r:2 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: Acurast StoredRevokedCertificate (r:4 w:0)
-	// Storage: Acurast StoredAttestation (r:0 w:1)
+	// Storage: Acurast StoredRevokedSerialNumber (r:4 w:0)
+	// Storage: Acurast StoredAttestation (r:1 w:1)
+	// Storage: Acurast StoredAttestedSources (r:0 w:4)
+	// Storage: Acurast StoredRevokedAttestation (r:0 w:1)
 	fn submit_attestation() -> Weight {
-		Weight::from_ref_time(10_046_000_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(5 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		Weight::from_ref_time(10_058_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
-	// Storage: Acurast StoredRevokedCertificate (r:0 w:1)
+	// Storage: Acurast StoredRevokedCertificate (r:1 w:1)
+	// Storage: Acurast StoredRevocationQueueRange (r:1 w:1)
+	// Storage: Acurast StoredRevocationQueue (r:0 w:1)
 	fn update_certificate_revocation_list() -> Weight {
+		Weight::from_ref_time(15_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Acurast StoredAttestationPolicy (r:0 w:1)
+	fn set_attestation_policy() -> Weight {
+		Weight::from_ref_time(12_000_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Acurast StoredTrustedRoot (r:0 w:1)
+	fn update_trusted_roots() -> Weight {
 		Weight::from_ref_time(12_000_000 as u64)
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: Acurast StoredTrustedRoot (r:2 w:0)
+	// Storage: Acurast StoredRevokedCertificate (r:5 w:1)
+	// Storage: Acurast StoredRevokedSerialNumber (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Acurast StoredRevocationListUpdate (r:1 w:1)
+	// Storage: Acurast StoredRevocationQueueRange (r:1 w:1)
+	// Storage: Acurast StoredRevocationQueue (r:0 w:1)
+	/// The range of component `x` is `[0, 1000]`.
+	fn import_certificate_revocation_list(x: u32, ) -> Weight {
+		Weight::from_ref_time(10_046_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(15_000_000 as u64).saturating_mul(x as u64))
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(x as u64)))
+	}
+	// Storage: Acurast StoredRevocationQueueRange (r:1 w:1)
+	// Storage: Acurast StoredRevocationCursor (r:1 w:1)
+	// Storage: Acurast StoredRevocationQueue (r:1 w:1)
+	// Storage: Acurast StoredAttestedSources (r:2 w:0)
+	// Storage: Acurast StoredRevokedCertificate (r:1 w:0)
+	// Storage: Acurast StoredRevokedSerialNumber (r:1 w:0)
+	// Storage: Acurast StoredRevokedAttestation (r:1 w:1)
+	fn revoke_attestation() -> Weight {
+		Weight::from_ref_time(31_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: Acurast StoredRevokedSerialNumber (r:0 w:1)
+	/// The range of component `x` is `[1, 100]`.
+	fn remove_revoked_serial_numbers(x: u32, ) -> Weight {
+		Weight::from_ref_time(11_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(1_600_000 as u64).saturating_mul(x as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(x as u64)))
+	}
 }
@@ -9,6 +9,7 @@
 //
 // #[cfg(feature = "runtime-benchmarks")]
 // mod benchmarking;
+mod migrations;
 pub mod weights;
 
 pub mod traits;
@@ -28,13 +29,19 @@ pub mod pallet {
     use crate::traits::AssetValidator;
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
-    use xcm::latest::MultiLocation;
-    use xcm::prelude::{Abstract, AssetId, Concrete, GeneralIndex, PalletInstance, Parachain, X3};
+    use xcm::v3::prelude::{
+        Abstract, AssetId, Concrete, GeneralIndex, PalletInstance, Parachain, X3,
+    };
+    use xcm::v3::MultiLocation;
     use xcm_executor::traits::Convert;
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
     #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
     #[pallet::config]
@@ -100,6 +107,13 @@ pub mod pallet {
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config<I>, I: 'static = ()> {}
 
+    #[pallet::hooks]
+    impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::migrate::<T, I>()
+        }
+    }
+
     #[pallet::error]
     pub enum Error<T, I = ()> {
         /// The job registration's reward type is not supported.
@@ -239,7 +253,7 @@ pub mod pallet {
         for Pallet<T, I>
     {
         fn convert_ref(id: impl Borrow<MultiLocation>) -> Result<T::AssetId, ()> {
-            Ok(Self::reverse_asset_index(Concrete(id.borrow().clone())).ok_or(())?)
+            Ok(Self::reverse_asset_index(Concrete(*id.borrow())).ok_or(())?)
         }
         fn reverse_ref(id: impl Borrow<T::AssetId>) -> Result<MultiLocation, ()> {
             match Self::asset_index(id.borrow()).ok_or(())? {
@@ -0,0 +1,73 @@
+use frame_support::{
+    traits::{Get, GetStorageVersion},
+    weights::Weight,
+};
+
+use crate::{Config, Pallet};
+
+/// Translates the indexed [xcm::v3::AssetId]s from XCM v2, the latest XCM version before version 1.
+///
+/// Assets whose ids cannot be expressed in XCM v3 are removed from the index and have to be indexed again.
+pub mod v1 {
+    use frame_support::{log, pallet_prelude::*, storage_alias};
+    use sp_std::prelude::*;
+    use xcm::{v2::AssetId as OldAssetId, v3::AssetId};
+
+    use super::*;
+    use crate::{AssetIndex, ReverseAssetIndex};
+
+    /// The asset index with values as stored before version 1.
+    #[storage_alias]
+    pub(crate) type OldAssetIndex<T: Config<I>, I: 'static> =
+        StorageMap<Pallet<T, I>, Blake2_128, <T as pallet_assets::Config<I>>::AssetId, OldAssetId>;
+
+    /// The reverse asset index with keys as stored before version 1.
+    #[storage_alias]
+    pub(crate) type OldReverseAssetIndex<T: Config<I>, I: 'static> =
+        StorageMap<Pallet<T, I>, Blake2_128, OldAssetId, <T as pallet_assets::Config<I>>::AssetId>;
+
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        // the keys of both indices are not reversible, so the reverse index is rebuilt from the values of the index
+        let old_asset_ids = OldAssetIndex::<T, I>::iter_values().collect::<Vec<_>>();
+        let count = old_asset_ids.len() as u64;
+
+        let mut failed: u32 = 0;
+        <AssetIndex<T, I>>::translate_values(|old: OldAssetId| {
+            let asset_id = AssetId::try_from(old).ok();
+            if asset_id.is_none() {
+                failed += 1;
+            }
+            asset_id
+        });
+        for old in old_asset_ids {
+            let internal_asset_id = OldReverseAssetIndex::<T, I>::take(&old);
+            if let (Some(internal_asset_id), Ok(asset_id)) =
+                (internal_asset_id, AssetId::try_from(old))
+            {
+                <ReverseAssetIndex<T, I>>::insert(&asset_id, &internal_asset_id);
+            }
+        }
+
+        if failed > 0 {
+            log::warn!(
+                target: "runtime::acurast_assets",
+                "removed {} assets with ids not expressible in XCM v3 from the index",
+                failed
+            );
+        }
+        T::DbWeight::get().reads_writes(count.saturating_mul(3), count.saturating_mul(3))
+    }
+}
+
+/// Runs the migrations required to bring the storage of the pallet to its in-code version.
+pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+    let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+
+    if on_chain_version < 1 {
+        weight = weight.saturating_add(v1::migrate::<T, I>());
+    }
+
+    Pallet::<T, I>::current_storage_version().put::<Pallet<T, I>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
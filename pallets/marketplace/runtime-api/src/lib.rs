@@ -0,0 +1,45 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use acurast_common::JobRegistration;
+use codec::Codec;
+use frame_support::pallet_prelude::{Member, Parameter};
+use pallet_acurast_marketplace::Match;
+use sp_runtime::{
+    traits::{MaybeDisplay, MaybeSerializeDeserialize},
+    DispatchError,
+};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    /// API to query the Acurast marketplace pallet, e.g. for consumer UIs and off-chain matchers.
+    pub trait MarketplaceRuntimeApi<AccountId, Extra, AssetAmount, Reward>
+    where
+        AccountId: Parameter + Member + MaybeSerializeDeserialize + MaybeDisplay + Ord,
+        Extra: Parameter + Member,
+        AssetAmount: Codec,
+        Reward: Codec,
+    {
+        /// Returns the advertised sources accepting the job's reward asset with sufficient memory and remaining storage capacity.
+        ///
+        /// The job does not have to be registered. Candidates might still fail other checks in matching, e.g. on their schedule.
+        fn candidate_sources(
+            registration: JobRegistration<AccountId, Extra>,
+        ) -> Result<Vec<AccountId>, DispatchError>;
+
+        /// Returns the fee per execution the source charges for the job, according to the source's pricing for the job's reward asset.
+        fn fee_per_execution(
+            registration: JobRegistration<AccountId, Extra>,
+            source: AccountId,
+        ) -> Result<AssetAmount, DispatchError>;
+
+        /// Returns the total reward to be locked on registration of the job, covering all slots and executions.
+        fn total_reward_amount(
+            registration: JobRegistration<AccountId, Extra>,
+        ) -> Result<AssetAmount, DispatchError>;
+
+        /// Validates a match for a registered job like `propose_matching` does, without changing storage.
+        ///
+        /// Returns the remaining reward (not spent on assigned fees) of the job or the error that matching would fail with.
+        fn dry_run_match(m: Match<AccountId>) -> Result<Reward, DispatchError>;
+    }
+}
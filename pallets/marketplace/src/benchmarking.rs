@@ -2,7 +2,8 @@ use frame_benchmarking::{account, benchmarks, whitelist_account};
 use frame_support::{
     assert_ok,
     sp_runtime::traits::{AccountIdConversion, Get, StaticLookup},
-    traits::Currency,
+    traits::{Currency, ReservableCurrency},
+    weights::Weight,
 };
 use frame_system::RawOrigin;
 use sp_core::*;
@@ -11,7 +12,7 @@ use sp_runtime::BoundedVec;
 use sp_std::prelude::*;
 
 pub use pallet::Config;
-use pallet_acurast::{Event as AcurastEvent, JobRegistrationFor, Script};
+use pallet_acurast::{Event as AcurastEvent, JobRegistrationFor, Script, StoredJobRegistration};
 use pallet_acurast::{Pallet as Acurast, Schedule};
 
 pub use crate::stub::*;
@@ -48,6 +49,7 @@ where
         fee_per_millisecond: fee_per_millisecond.into(),
         fee_per_storage_byte: 5.into(),
         base_fee_per_execution: 0.into(),
+        stake_per_execution: 0.into(),
         scheduling_window: SchedulingWindow::Delta(2_628_000_000), // 1 month
     });
     assert!(r.is_ok(), "Expected Ok(_). Got {:#?}", r);
@@ -65,14 +67,32 @@ pub fn job_registration_with_reward<T: Config>(
     duration: u64,
     reward_value: u128,
 ) -> JobRegistrationFor<T>
+where
+    <T as Config>::RegistrationExtra: From<JobRequirementsFor<T>>,
+    RewardFor<T>: From<MockAsset>,
+{
+    job_registration_with_slots::<T>(script, duration, reward_value, 1, None)
+}
+
+pub fn job_registration_with_slots<T: Config>(
+    script: Script,
+    duration: u64,
+    reward_value: u128,
+    slots: u8,
+    max_output_size: Option<u32>,
+) -> JobRegistrationFor<T>
 where
     <T as Config>::RegistrationExtra: From<JobRequirementsFor<T>>,
     RewardFor<T>: From<MockAsset>,
 {
     let r = JobRequirements {
-        slots: 1,
+        slots,
+        min_slots: None,
         reward: asset(reward_value).into(),
+        slot_rewards: None,
         instant_match: None,
+        min_reputation: None,
+        max_output_size,
     };
     let r: <T as Config>::RegistrationExtra = r.into();
     let r: <T as pallet_acurast::Config>::RegistrationExtra = r.into();
@@ -181,15 +201,94 @@ where
     (caller, job)
 }
 
+/// Registers an open job with a single execution and `slots` slots and lets as many distinct sources advertise matching resources.
+fn open_job_helper<T: Config>(
+    slots: u8,
+    max_output_size: Option<u32>,
+) -> (T::AccountId, JobRegistrationFor<T>, Vec<T::AccountId>)
+where
+    T: pallet_assets::Config,
+    <T as Config>::AssetId: From<u32>,
+    <T as Config>::AssetAmount: From<u128>,
+    <T as pallet_assets::Config>::AssetId: From<u32>,
+    <T as pallet_assets::Config>::Balance: From<u128>,
+    <T as Config>::RegistrationExtra: From<JobRequirementsFor<T>>,
+    RewardFor<T>: From<MockAsset>,
+{
+    let caller: T::AccountId = token_22_funded_account::<T>();
+    whitelist_account!(caller);
+
+    let mut job = job_registration_with_slots::<T>(script(), 2, 10_000, slots, max_output_size);
+    job.schedule.end_time = job.schedule.start_time + job.schedule.interval;
+    job.storage = 0;
+    assert_ok!(Acurast::<T>::register(
+        RawOrigin::Signed(caller.clone()).into(),
+        job.clone()
+    ));
+
+    let sources: Vec<T::AccountId> = (0..slots as u32)
+        .map(|i| account("source", i, SEED))
+        .collect();
+    for source in &sources {
+        assert_ok!(AcurastMarketplace::<T>::advertise(
+            RawOrigin::Signed(source.clone()).into(),
+            advertisement::<T>(1000, 5),
+        ));
+    }
+
+    (caller, job, sources)
+}
+
+/// Registers a job with a single execution and `slots` slots, matches each slot with a distinct source and lets the sources acknowledge.
+fn assigned_job_helper<T: Config>(
+    slots: u8,
+    max_output_size: Option<u32>,
+) -> (T::AccountId, JobRegistrationFor<T>, Vec<T::AccountId>)
+where
+    T: pallet_assets::Config,
+    <T as Config>::AssetId: From<u32>,
+    <T as Config>::AssetAmount: From<u128>,
+    <T as pallet_assets::Config>::AssetId: From<u32>,
+    <T as pallet_assets::Config>::Balance: From<u128>,
+    <T as Config>::RegistrationExtra: From<JobRequirementsFor<T>>,
+    RewardFor<T>: From<MockAsset>,
+{
+    let (caller, job, sources) = open_job_helper::<T>(slots, max_output_size);
+
+    let job_id = (caller.clone(), job.script.clone());
+    assert_ok!(AcurastMarketplace::<T>::propose_matching(
+        RawOrigin::Signed(caller.clone()).into(),
+        vec![Match {
+            job_id: job_id.clone(),
+            sources: sources
+                .iter()
+                .map(|source| PlannedExecution {
+                    source: source.clone(),
+                    start_delay: 0,
+                })
+                .collect(),
+        }],
+    ));
+    for source in &sources {
+        assert_ok!(AcurastMarketplace::<T>::acknowledge_match(
+            RawOrigin::Signed(source.clone()).into(),
+            job_id.clone(),
+        ));
+    }
+
+    (caller, job, sources)
+}
+
 benchmarks! {
     where_clause {  where
-        T: pallet_assets::Config + pallet_acurast::Config,
+        T: pallet_assets::Config + pallet_acurast::Config + pallet_timestamp::Config,
         <T as Config>::RegistrationExtra: From<JobRequirementsFor<T>>,
         RewardFor<T>: From<MockAsset>,
         <T as Config>::AssetId: From<u32>,
         <T as Config>::AssetAmount: From<u128>,
         <T as pallet_assets::Config>::AssetId: From<u32>,
         <T as pallet_assets::Config>::Balance: From<u128>,
+        <T as pallet_timestamp::Config>::Moment: From<u64>,
     }
 
     advertise {
@@ -225,7 +324,10 @@ benchmarks! {
     }
 
     deregister {
-        let (caller, job) = register_helper::<T>(true);
+        let x in 1 .. MAX_SLOTS;
+        let (caller, job, _) = assigned_job_helper::<T>(x as u8, None);
+        // at the start of the job, the acknowledged sources are paid for the execution in progress and the rest of the budget is refunded
+        assert_ok!(pallet_timestamp::Pallet::<T>::set(T::RuntimeOrigin::none(), job.schedule.start_time.into()));
     }: {
          pallet_acurast::Pallet::<T>::deregister(RawOrigin::Signed(caller.clone()).into(), job.script.clone())?
     }
@@ -235,5 +337,84 @@ benchmarks! {
         ).into());
     }
 
+    finalize_job {
+        let x in 1 .. MAX_SLOTS;
+        let (caller, job, _) = assigned_job_helper::<T>(x as u8, None);
+        let job_id = (caller.clone(), job.script.clone());
+        let (_, end) = job.schedule.range(job.schedule.max_start_delay).unwrap();
+        assert_ok!(pallet_timestamp::Pallet::<T>::set(T::RuntimeOrigin::none(), end.into()));
+    }: _(RawOrigin::Signed(caller.clone()), job_id.clone())
+    verify {
+        assert_last_event::<T>(Event::JobFinalized(job_id).into());
+    }
+
+    auto_match_job {
+        let c in 1 .. T::MaxAutoMatchCandidates::get().min(MAX_SLOTS);
+        // all scanned sources are candidates and get matched
+        let (caller, job, sources) = open_job_helper::<T>(c as u8, None);
+        let job_id = (caller, job.script.clone());
+        // each candidate has as many matches as the matcher considers, all checked against the job's schedule
+        let mut other_job = job.clone();
+        other_job.schedule.start_time = job.schedule.start_time - 2 * job.schedule.interval;
+        other_job.schedule.end_time = job.schedule.start_time - job.schedule.interval;
+        for i in 0..AUTO_MATCH_MAX_SOURCE_MATCHES {
+            let consumer: T::AccountId = account("consumer", i, SEED);
+            <StoredJobRegistration<T>>::insert(&consumer, &other_job.script, other_job.clone());
+            for source in &sources {
+                <StoredMatches<T>>::insert(
+                    source,
+                    (&consumer, &other_job.script),
+                    Assignment {
+                        slot: 0,
+                        start_delay: 0,
+                        fee_per_execution: asset(0).into(),
+                        stake_per_execution: asset(0).into(),
+                        acknowledged: true,
+                        sla: SLA { total: 1, met: 0 },
+                    },
+                );
+            }
+        }
+    }: {
+        AcurastMarketplace::<T>::auto_match(Weight::MAX);
+    }
+    verify {
+        assert_eq!(Some(JobStatus::Matched), <StoredJobStatus<T>>::get(&job_id.0, &job_id.1));
+    }
+
+    report_with_output {
+        let b in 0 .. EXECUTION_OUTPUT_MAX_LENGTH;
+        let (caller, job, sources) = assigned_job_helper::<T>(1, Some(EXECUTION_OUTPUT_MAX_LENGTH));
+        let job_id = (caller, job.script.clone());
+        let source = sources[0].clone();
+        let output: ExecutionOutput = vec![0u8; b as usize].try_into().unwrap();
+        assert_ok!(pallet_timestamp::Pallet::<T>::set(T::RuntimeOrigin::none(), job.schedule.start_time.into()));
+    }: _(RawOrigin::Signed(source.clone()), job_id.clone(), false, ExecutionResult::Success(operation_hash()), output)
+    verify {
+        assert_last_event::<T>(Event::ExecutionOutputStored(job_id, source, 0).into());
+    }
+
+    remove_execution_outputs {
+        let n in 1 .. 1_000;
+        let (caller, job, sources) = assigned_job_helper::<T>(1, Some(EXECUTION_OUTPUT_MAX_LENGTH));
+        let job_id = (caller.clone(), job.script.clone());
+        let deposit = T::OutputDepositBase::get();
+        for execution in 0..n as u64 {
+            assert_ok!(T::Currency::reserve(&caller, deposit));
+            <StoredExecutionOutput<T>>::insert(
+                &job_id,
+                (&sources[0], execution),
+                ExecutionOutputRecord {
+                    output: Default::default(),
+                    deposit,
+                    expiry: 1u32.into(),
+                },
+            );
+        }
+    }: _(RawOrigin::Signed(caller), job_id.clone(), n)
+    verify {
+        assert_last_event::<T>(Event::ExecutionOutputsRemoved(job_id, n).into());
+    }
+
     impl_benchmark_test_suite!(AcurastMarketplace, mock::ExtBuilder::default().build(), mock::Test);
 }
@@ -14,6 +14,7 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
+mod migrations;
 pub mod payments;
 pub mod types;
 mod utils;
@@ -23,13 +24,18 @@ pub mod weights_with_hooks;
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
-        dispatch::DispatchResultWithPostInfo, ensure, pallet_prelude::*,
-        sp_runtime::traits::StaticLookup, traits::UnixTime, Blake2_128, PalletId,
+        dispatch::DispatchResultWithPostInfo,
+        ensure, log,
+        pallet_prelude::*,
+        sp_runtime::traits::StaticLookup,
+        storage::{with_transaction, TransactionOutcome},
+        traits::{ReservableCurrency, UnixTime},
+        Blake2_128, PalletId,
     };
     use frame_system::pallet_prelude::*;
     use itertools::Itertools;
-    use sp_runtime::traits::{CheckedAdd, CheckedMul, CheckedSub};
-    use sp_runtime::SaturatedConversion;
+    use sp_runtime::traits::{CheckedAdd, CheckedMul, CheckedSub, One, Saturating};
+    use sp_runtime::{Permill, SaturatedConversion};
     use sp_std::iter::once;
     use sp_std::prelude::*;
 
@@ -78,12 +84,47 @@ pub mod pallet {
         /// Logic for locking and paying tokens for job execution
         type RewardManager: RewardManager<Self>;
         type AssetValidator: AssetValidator<Self::AssetId>;
+        /// The minimum number of executions in the [Reputation] of a source for it to be accepted by jobs requiring a `min_reputation`.
+        #[pallet::constant]
+        type MinReputationHistory: Get<u64>;
+        /// The maximum number of advertisement index entries the on-chain matcher scans for candidates for each open job. Set to `0` to disable on-chain matching.
+        #[pallet::constant]
+        type MaxAutoMatchCandidates: Get<u32>;
+        /// The number of blocks a terminal [JobStatus] is retained before it gets removed from storage.
+        #[pallet::constant]
+        type JobStatusRetention: Get<BlockNumberFor<Self>>;
+        /// The number of blocks after matching in which all matched sources have to acknowledge.
+        ///
+        /// Sources that did not acknowledge in time are removed from the job. If none of them acknowledged, the job is open for matching again,
+        /// or cancelled if it can no longer be matched because its start time passed.
+        #[pallet::constant]
+        type AcknowledgementTimeout: Get<BlockNumberFor<Self>>;
+        /// If the matcher reward is reclaimed from the matcher when none of the matched sources acknowledged in time.
+        ///
+        /// The full matcher reward is restored to the job's budget; the fees deducted when it was paid are charged to the matcher.
+        #[pallet::constant]
+        type ClawBackMatcherReward: Get<bool>;
+        /// The currency in which the deposits for stored execution outputs are reserved.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// The deposit reserved from the consumer for each stored execution output.
+        #[pallet::constant]
+        type OutputDepositBase: Get<BalanceOf<Self>>;
+        /// The deposit reserved from the consumer for each byte of a stored execution output.
+        #[pallet::constant]
+        type OutputDepositPerByte: Get<BalanceOf<Self>>;
+        /// The number of blocks an execution output is retained before it gets removed from storage and its deposit is returned.
+        #[pallet::constant]
+        type OutputRetention: Get<BlockNumberFor<Self>>;
         type WeightInfo: WeightInfo;
     }
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub (super) trait Store)]
     #[pallet::without_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// The storage for jobs' status as a map [`AccountId`] `(consumer)` -> [`Script`] -> [`JobStatus`].
@@ -92,12 +133,77 @@ pub mod pallet {
     pub type StoredJobStatus<T: Config> =
         StorageDoubleMap<_, Blake2_128, T::AccountId, Blake2_128, Script, JobStatus>;
 
+    /// Jobs with a terminal [`JobStatus`] as a map `block` -> [`JobId`] -> `()`, where `block` is the block in which the status gets removed from [`StoredJobStatus`].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_job_status_expiry)]
+    pub type StoredJobStatusExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        JobId<T::AccountId>,
+        (),
+    >;
+
+    /// The [`SLA`] accumulated over the ended assignments of a job so far, as a map [`AccountId`] `(consumer)` -> [`Script`] -> [`SLA`].
+    ///
+    /// Used to determine the terminal [`JobStatus`] of the job.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_job_sla)]
+    pub type StoredJobSLA<T: Config> =
+        StorageDoubleMap<_, Blake2_128, T::AccountId, Blake2_128, Script, SLA, ValueQuery>;
+
+    /// The storage for matches not yet acknowledged by all matched sources as a map [`AccountId`] `(consumer)` -> [`Script`] -> [`PendingMatch`].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_pending_match)]
+    pub type StoredPendingMatch<T: Config> =
+        StorageDoubleMap<_, Blake2_128, T::AccountId, Blake2_128, Script, PendingMatchFor<T>>;
+
+    /// Acknowledgement deadlines of pending matches as a map `block` -> [`JobId`] -> `()`.
+    ///
+    /// Entries of jobs whose [`StoredPendingMatch`] got removed or replaced in the meantime are ignored when the deadline elapses.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_acknowledgement_deadline)]
+    pub type StoredAcknowledgementDeadline<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        JobId<T::AccountId>,
+        (),
+    >;
+
+    /// Execution outputs stored on reporting as a map [`JobId`] -> ([`AccountId`] `(source)`, `execution index`) -> [`ExecutionOutputRecord`].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_execution_output)]
+    pub type StoredExecutionOutput<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        JobId<T::AccountId>,
+        Blake2_128Concat,
+        (T::AccountId, u64),
+        ExecutionOutputRecordFor<T>,
+    >;
+
+    /// Stored execution outputs as a map `block` -> ([`JobId`], [`AccountId`] `(source)`, `execution index`) -> `()`, where `block` is the block in which
+    /// the output gets removed from [`StoredExecutionOutput`].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_execution_output_expiry)]
+    pub type StoredExecutionOutputExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        (JobId<T::AccountId>, T::AccountId, u64),
+        (),
+    >;
+
     /// The storage for basic advertisements' restrictions (without pricing). They are stored as a map [`AccountId`] `(source)` -> [`AdvertisementRestriction`] since only one
     /// advertisement per client is allowed.
     #[pallet::storage]
     #[pallet::getter(fn stored_advertisement)]
     pub type StoredAdvertisementRestriction<T: Config> =
-        StorageMap<_, Blake2_128, T::AccountId, AdvertisementRestriction<T::AccountId>>;
+        StorageMap<_, Blake2_128Concat, T::AccountId, AdvertisementRestriction<T::AccountId>>;
 
     /// The storage for advertisements' pricing variants. They are stored as a map [`AccountId`] `(source)` -> [`AssetId`] -> [`PricingVariant`] since only one
     /// advertisement per client, and at most one pricing for each distinct `AssetID` is allowed.
@@ -111,6 +217,25 @@ pub mod pallet {
     #[pallet::getter(fn stored_storage_capacity)]
     pub type StoredStorageCapacity<T: Config> = StorageMap<_, Blake2_128, T::AccountId, i64>;
 
+    /// Index of advertised sources by accepted reward asset as a map [`AssetId`] -> [`AccountId`] `(source)` -> `()`.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_sources_by_asset)]
+    pub type StoredSourcesByAsset<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AssetId, Blake2_128Concat, T::AccountId, ()>;
+
+    /// Index of advertised sources by the [`resource_bucket`] of their `max_memory` as a map `bucket` -> [`AccountId`] `(source)` -> `()`.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_sources_by_memory_bucket)]
+    pub type StoredSourcesByMemoryBucket<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u8, Blake2_128Concat, T::AccountId, ()>;
+
+    /// Index of advertised sources by the [`resource_bucket`] of their remaining storage capacity (see [`StoredStorageCapacity`])
+    /// as a map `bucket` -> [`AccountId`] `(source)` -> `()`. Sources with negative remaining capacity are in bucket `0`.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_sources_by_storage_bucket)]
+    pub type StoredSourcesByStorageBucket<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u8, Blake2_128Concat, T::AccountId, ()>;
+
     /// Job matches as a map [`AccountId`] `(source)` -> [`JobId`] -> `SlotId`
     #[pallet::storage]
     #[pallet::getter(fn stored_matches)]
@@ -123,6 +248,38 @@ pub mod pallet {
         AssignmentFor<T>,
     >;
 
+    /// Matched sources as a map [`JobId`] -> [`AccountId`] `(source)` -> `()`. Reverse index of [`StoredMatches`] used to look up all sources matched with a job.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_matched_sources)]
+    pub type StoredMatchedSources<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        JobId<T::AccountId>,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+    >;
+
+    /// The storage for the remaining locked reward of each job as a map [`AccountId`] `(consumer)` -> [`Script`] -> [`Reward`].
+    ///
+    /// The budget starts as the total reward locked on registration and gets reduced by every payment to sources and the matcher.
+    /// What is left when the job is finalized gets refunded to the consumer.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_job_budget)]
+    pub type StoredJobBudget<T: Config> =
+        StorageDoubleMap<_, Blake2_128, T::AccountId, Blake2_128, Script, RewardFor<T>>;
+
+    /// The storage for the SLA history of each source as a map [`AccountId`] `(source)` -> [`Reputation`].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_reputation)]
+    pub type StoredReputation<T: Config> =
+        StorageMap<_, Blake2_128, T::AccountId, Reputation, ValueQuery>;
+
+    /// The job visited last by the on-chain matcher. The next run continues with the job following it in [`StoredJobRegistration`].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_auto_match_cursor)]
+    pub type StoredAutoMatchCursor<T: Config> = StorageValue<_, JobId<T::AccountId>>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub (super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -140,6 +297,30 @@ pub mod pallet {
         ExecutionSuccess(JobId<T::AccountId>, ExecutionOperationHash),
         /// An execution is reported to have failed.
         ExecutionFailure(JobId<T::AccountId>, ExecutionFailureMessage),
+        /// A job was finalized after its schedule ended. [JobId]
+        JobFinalized(JobId<T::AccountId>),
+        /// The stake of a source was slashed for executions missed and transferred to the consumer. [JobId, SourceId, slashed stake]
+        StakeSlashed(JobId<T::AccountId>, T::AccountId, RewardFor<T>),
+        /// A report arrived after the execution it was intended for ended. It is not rewarded. [JobId, SourceId]
+        ReportedLate(JobId<T::AccountId>, T::AccountId),
+        /// The reputation of a source was updated. [SourceId, Reputation]
+        ReputationUpdated(T::AccountId, Reputation),
+        /// The status of a job changed.
+        JobStatusChanged {
+            job_id: JobId<T::AccountId>,
+            from: JobStatus,
+            to: JobStatus,
+        },
+        /// Sources that did not acknowledge a match in time were removed from the job. [JobId, SourceIds]
+        MatchAcknowledgementTimedOut(JobId<T::AccountId>, Vec<T::AccountId>),
+        /// The matcher reward was reclaimed after none of the matched sources acknowledged in time. [JobId, MatcherId, matcher reward]
+        MatcherRewardClawedBack(JobId<T::AccountId>, T::AccountId, RewardFor<T>),
+        /// The output of an execution was stored. [JobId, SourceId, execution index]
+        ExecutionOutputStored(JobId<T::AccountId>, T::AccountId, u64),
+        /// Stored execution outputs of a job were removed by the consumer. [JobId, number of outputs removed]
+        ExecutionOutputsRemoved(JobId<T::AccountId>, u32),
+        /// A source was removed from a match it did not acknowledge yet since its attestation got revoked. [JobId, SourceId]
+        MatchRevoked(JobId<T::AccountId>, T::AccountId),
     }
 
     #[pallet::error]
@@ -204,7 +385,7 @@ pub mod pallet {
         DuplicateSourceInMatch,
         /// Match is invalid due to an unverfied source while `allow_only_verified_sources` is true.
         UnverifiedSourceInMatch,
-        /// Multiple different reward assets are currently not supported in a single matching.
+        /// Deprecated: matchings may contain jobs with different reward assets since the matcher reward is paid per asset.
         MultipleRewardAssetsInMatch,
         /// Match is invalid due to a source's maximum memory exceeded.
         SchedulingWindowExceededInMatch,
@@ -228,10 +409,108 @@ pub mod pallet {
         MoreReportsThanExpected,
         /// Report received outside of schedule.
         ReportOutsideSchedule,
+        /// Job budget not found. SEVERE error
+        JobBudgetNotFound,
+        /// Payment exceeds the remaining budget of a job. SEVERE error
+        InsufficientJobBudget,
+        /// Finalize cannot be called for a job before the end of its schedule.
+        CannotFinalizeJobBeforeScheduleEnd,
+        /// The job's status cannot change to the requested status.
+        InvalidJobStatusTransition,
+        /// The job does not accept execution outputs to be stored.
+        ExecutionOutputNotAccepted,
+        /// The execution output exceeds the job's `max_output_size`.
+        ExecutionOutputTooLarge,
+        /// The consumer cannot afford the deposit for storing the execution output.
+        InsufficientBalanceForOutputDeposit,
+        /// Only the consumer of a job can remove its execution outputs.
+        OnlyConsumerCanRemoveOutputs,
+        /// Match is invalid due to a source's reputation score below the required minimum, or a source without attestation or enough history.
+        InsufficientReputationInMatch,
+        /// The job registration's must specify `min_slots` between 1 and `slots`.
+        JobRegistrationInvalidMinSlots,
+        /// The job registration's `slot_rewards` must contain a reward in the asset of `reward` for each slot.
+        JobRegistrationInvalidSlotRewards,
+        /// An execution output was already stored for the reported execution.
+        ExecutionOutputAlreadyStored,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::migrate::<T>()
+        }
+
+        fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+            let mut removed: u64 = 0;
+            for (job_id, _) in <StoredJobStatusExpiry<T>>::drain_prefix(block_number) {
+                // the job might have been registered again in the meantime
+                if <StoredJobStatus<T>>::get(&job_id.0, &job_id.1)
+                    .map_or(false, |status| status.is_terminal())
+                {
+                    <StoredJobStatus<T>>::remove(&job_id.0, &job_id.1);
+                }
+                removed += 1;
+            }
+            let mut weight = T::DbWeight::get()
+                .reads_writes(removed.saturating_mul(2), removed.saturating_mul(2));
+
+            for ((job_id, source, execution), _) in
+                <StoredExecutionOutputExpiry<T>>::drain_prefix(block_number)
+            {
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 3));
+                // the output might have been removed by the consumer in the meantime
+                if <StoredExecutionOutput<T>>::get(&job_id, (&source, execution))
+                    .map_or(false, |record| record.expiry == block_number)
+                {
+                    Self::remove_execution_output(&job_id, &source, execution);
+                }
+            }
+
+            for (job_id, _) in <StoredAcknowledgementDeadline<T>>::drain_prefix(block_number) {
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 1));
+                // the job might have been acknowledged, cleared or matched again in the meantime
+                if <StoredPendingMatch<T>>::get(&job_id.0, &job_id.1)
+                    .map_or(true, |pending| pending.deadline != block_number)
+                {
+                    continue;
+                }
+                // revert partial changes of a failed timeout, as it would happen for a failed extrinsic
+                let dropped = match with_transaction(|| match Self::time_out_match(&job_id) {
+                    Ok(dropped) => TransactionOutcome::Commit(Ok(dropped)),
+                    Err(e) => TransactionOutcome::Rollback(Err(e)),
+                }) {
+                    Ok(dropped) => dropped,
+                    Err(e) => {
+                        log::error!(
+                            target: "runtime::acurast_marketplace",
+                            "failed to time out match of job {:?}: {:?}",
+                            job_id,
+                            e
+                        );
+                        // retry in the next block instead of leaving the job matched forever
+                        let deadline = block_number.saturating_add(One::one());
+                        <StoredPendingMatch<T>>::mutate(&job_id.0, &job_id.1, |pending| {
+                            if let Some(pending) = pending {
+                                pending.deadline = deadline;
+                            }
+                        });
+                        <StoredAcknowledgementDeadline<T>>::insert(deadline, &job_id, ());
+                        0
+                    }
+                };
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(
+                    8u64.saturating_add(dropped.saturating_mul(3)),
+                    8u64.saturating_add(dropped.saturating_mul(5)),
+                ));
+            }
+            weight
+        }
+
+        fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::auto_match(remaining_weight)
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -252,18 +531,20 @@ pub mod pallet {
             // update capacity to save on operations when checking available capacity
             if let Some(old) = <StoredAdvertisementRestriction<T>>::get(&who) {
                 // allow capacity to become negative (in which case source remains assigned but does not receive new jobs assigned)
-                <StoredStorageCapacity<T>>::mutate(&who, |c| {
-                    // new remaining capacity = new total capacity - (old total capacity - old remaining capacity) = old remaining capacity + new total capacity - old total capacity
-                    *c = Some(
-                        c.unwrap_or(0)
-                            .checked_add(advertisement.storage_capacity as i64)
-                            .unwrap_or(i64::MAX)
-                            .checked_sub(old.storage_capacity as i64)
-                            .unwrap_or(0),
-                    )
-                });
+                // new remaining capacity = new total capacity - (old total capacity - old remaining capacity) = old remaining capacity + new total capacity - old total capacity
+                let capacity = <StoredStorageCapacity<T>>::get(&who)
+                    .unwrap_or(0)
+                    .checked_add(advertisement.storage_capacity as i64)
+                    .unwrap_or(i64::MAX)
+                    .checked_sub(old.storage_capacity as i64)
+                    .unwrap_or(0);
+                Self::set_storage_capacity(&who, Some(capacity));
+                <StoredSourcesByMemoryBucket<T>>::remove(
+                    resource_bucket(old.max_memory.into()),
+                    &who,
+                );
             } else {
-                <StoredStorageCapacity<T>>::insert(&who, advertisement.storage_capacity as i64);
+                Self::set_storage_capacity(&who, Some(advertisement.storage_capacity as i64));
             }
 
             <StoredAdvertisementRestriction<T>>::insert(
@@ -275,10 +556,16 @@ pub mod pallet {
                     allowed_consumers: advertisement.allowed_consumers.clone(),
                 },
             );
+            <StoredSourcesByMemoryBucket<T>>::insert(
+                resource_bucket(advertisement.max_memory.into()),
+                &who,
+                (),
+            );
             // update separate pricing index
             for pricing in &advertisement.pricing {
                 T::AssetValidator::validate(&pricing.reward_asset).map_err(|e| e.into())?;
                 <StoredAdvertisementPricing<T>>::insert(&who, &pricing.reward_asset, pricing);
+                <StoredSourcesByAsset<T>>::insert(&pricing.reward_asset, &who, ());
             }
 
             Self::deposit_event(Event::AdvertisementStored(advertisement, who));
@@ -291,7 +578,7 @@ pub mod pallet {
         pub fn delete_advertisement(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
-            <StoredAdvertisementRestriction<T>>::get(&who)
+            let ad = <StoredAdvertisementRestriction<T>>::get(&who)
                 .ok_or(Error::<T>::AdvertisementNotFound)?;
 
             // prohibit updates as long as jobs assigned
@@ -300,8 +587,12 @@ pub mod pallet {
                 Error::<T>::CannotDeleteAdvertisementWhileMatched
             );
 
+            for pricing in <StoredAdvertisementPricing<T>>::iter_prefix_values(&who) {
+                <StoredSourcesByAsset<T>>::remove(&pricing.reward_asset, &who);
+            }
             let _ = <StoredAdvertisementPricing<T>>::clear_prefix(&who, MAX_PRICING_VARIANTS, None);
-            <StoredStorageCapacity<T>>::remove(&who);
+            Self::set_storage_capacity(&who, None);
+            <StoredSourcesByMemoryBucket<T>>::remove(resource_bucket(ad.max_memory.into()), &who);
             <StoredAdvertisementRestriction<T>>::remove(&who);
 
             Self::deposit_event(Event::AdvertisementRemoved(who));
@@ -309,6 +600,10 @@ pub mod pallet {
         }
 
         /// Proposes processors to match with a job. The match fails if it conflicts with the processor's schedule.
+        ///
+        /// A match can leave slots of a job unassigned down to the job's `min_slots`, the reward of the unassigned slots is refunded to the consumer.
+        ///
+        /// Matches of jobs rewarded in different assets can be proposed together, the matcher gets its reward paid in each of the assets.
         #[pallet::call_index(2)]
         #[pallet::weight(< T as Config >::WeightInfo::propose_matching())]
         pub fn propose_matching(
@@ -317,19 +612,61 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
-            let remaining_reward = Self::process_matching(&matches)?;
+            // accumulate remaining rewards per asset since the matcher gets paid separately in each asset
+            let mut remaining_rewards_by_asset: Vec<(
+                <T as Config>::AssetId,
+                Vec<(JobId<T::AccountId>, RewardFor<T>)>,
+            )> = Vec::new();
+            let mut unassigned_rewards: Vec<(JobId<T::AccountId>, RewardFor<T>)> = Vec::new();
+            for (job_id, remaining_reward, unassigned_reward) in Self::process_matching(&matches)? {
+                unassigned_rewards.push((job_id.clone(), unassigned_reward));
+                let asset: <T as Config>::AssetId = remaining_reward
+                    .try_get_asset_id()
+                    .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                    .into();
+                match remaining_rewards_by_asset
+                    .iter_mut()
+                    .find(|(other, _)| other == &asset)
+                {
+                    Some((_, rewards)) => rewards.push((job_id, remaining_reward)),
+                    None => {
+                        remaining_rewards_by_asset.push((asset, vec![(job_id, remaining_reward)]))
+                    }
+                }
+            }
+
+            for (_, rewards) in remaining_rewards_by_asset {
+                let (job_ids, remaining_rewards): (Vec<_>, Vec<_>) = rewards.into_iter().unzip();
 
-            // pay part of accumulated remaining reward (unspent to consumer) to matcher
-            // pay only after all other steps succeeded without errors because paying reward is not revertable
-            T::RewardManager::pay_matcher_reward(
-                remaining_reward,
-                T::Lookup::unlookup(who.clone()),
-            )?;
+                // pay part of accumulated remaining reward (unspent to consumer) to matcher
+                // pay only after all other steps succeeded without errors because paying reward is not revertable
+                let matcher_rewards = T::RewardManager::pay_matcher_reward(
+                    remaining_rewards,
+                    T::Lookup::unlookup(who.clone()),
+                )?;
+
+                // the rest of the remaining reward stays in the budget to be refunded to the consumer
+                for (job_id, matcher_reward) in job_ids.iter().zip(matcher_rewards) {
+                    Self::consume_budget(job_id, &matcher_reward)?;
+                    // remember the matcher reward in case it gets clawed back after the acknowledgement timeout
+                    <StoredPendingMatch<T>>::mutate(&job_id.0, &job_id.1, |pending| {
+                        if let Some(pending) = pending {
+                            pending.matcher = Some((who.clone(), matcher_reward));
+                        }
+                    });
+                }
+            }
+
+            for (job_id, unassigned_reward) in unassigned_rewards {
+                Self::refund_unassigned_slots(&job_id, unassigned_reward)?;
+            }
 
             Ok(().into())
         }
 
         /// Acknowledges a matched job. It fails if the origin is not the account that was matched for the job.
+        ///
+        /// The stake required by the pricing agreed on in matching gets locked for all executions of the job.
         #[pallet::call_index(3)]
         #[pallet::weight(< T as Config >::WeightInfo::acknowledge_match())]
         pub fn acknowledge_match(
@@ -353,20 +690,32 @@ pub mod pallet {
             )?;
 
             if changed {
-                <StoredJobStatus<T>>::try_mutate(
-                    &job_id.0,
-                    &job_id.1,
-                    |s| -> Result<(), Error<T>> {
-                        let status = s.ok_or(Error::<T>::JobStatusNotFound)?;
-                        *s = Some(match status {
-                            JobStatus::Open => Err(Error::<T>::CannotAcknowledgeWhenNotMatched)?,
-                            JobStatus::Matched => JobStatus::Assigned(1),
-                            JobStatus::Assigned(count) => JobStatus::Assigned(count + 1),
-                        });
+                let status = <StoredJobStatus<T>>::get(&job_id.0, &job_id.1)
+                    .ok_or(Error::<T>::JobStatusNotFound)?;
+                let acknowledged = match status {
+                    JobStatus::Matched => 1,
+                    JobStatus::Assigned(count) => count.saturating_add(1),
+                    _ => Err(Error::<T>::CannotAcknowledgeWhenNotMatched)?,
+                };
+                Self::set_job_status(&job_id, JobStatus::Assigned(acknowledged))?;
+
+                if <StoredMatchedSources<T>>::iter_key_prefix(&job_id).all(|source| {
+                    <StoredMatches<T>>::get(&source, &job_id)
+                        .map_or(true, |assignment| assignment.acknowledged)
+                }) {
+                    // all assigned slots acknowledged, no more timeout for this match
+                    <StoredPendingMatch<T>>::remove(&job_id.0, &job_id.1);
+                }
 
-                        Ok(())
-                    },
-                )?;
+                // lock only after all other steps succeeded without errors because locking stake is not revertable
+                let stake = Self::total_stake(&assignment)?;
+                let stake_amount: T::AssetAmount = stake
+                    .try_get_amount()
+                    .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                    .into();
+                if stake_amount > 0u8.into() {
+                    T::RewardManager::lock_stake(stake, T::Lookup::unlookup(who.clone()))?;
+                }
 
                 Self::deposit_event(Event::JobRegistrationAssigned(
                     job_id,
@@ -381,6 +730,8 @@ pub mod pallet {
         /// Reward is payed out to source if timing of this call is within expected interval. More precisely,
         /// the report is accepted if `[now, now + tolerance]` overlaps with an execution of the schedule agreed on.
         /// `tolerance` is a pallet config value.
+        ///
+        /// A report arriving after the execution it was intended for ended is not rewarded but recorded as late in the source's [Reputation].
         #[pallet::call_index(4)]
         #[pallet::weight(< T as Config >::WeightInfo::report())]
         pub fn report(
@@ -390,82 +741,122 @@ pub mod pallet {
             execution_result: ExecutionResult,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
+            Self::do_report(who, job_id, last, execution_result)?;
+            Ok(().into())
+        }
 
-            // find assignment
-            let assignment = <StoredMatches<T>>::try_mutate(
-                &who,
-                &job_id,
-                |a| -> Result<AssignmentFor<T>, Error<T>> {
-                    // NOTE: the None case is the "good case", used when there is *no entry yet and thus no duplicate assignment so far*.
-                    if let Some(assignment) = a.as_mut() {
-                        // CHECK that job is assigned
-                        ensure!(
-                            assignment.acknowledged,
-                            Error::<T>::CannotReportWhenNotAcknowledged
-                        );
-
-                        // CHECK that we don't accept more reports than expected
-                        ensure!(
-                            assignment.sla.met < assignment.sla.total,
-                            Error::<T>::MoreReportsThanExpected
-                        );
-
-                        assignment.sla.met += 1;
-                        return Ok(assignment.to_owned());
-                    } else {
-                        return Err(Error::<T>::ReportFromUnassignedSource);
-                    }
-                },
-            )?;
+        /// Finalizes a job whose schedule ended without all expected reports being received.
+        ///
+        /// Remaining assignments are removed and the unpaid part of the reward is refunded to the consumer.
+        /// Can be called by anyone once the latest possible execution of the job's schedule is over.
+        #[pallet::call_index(5)]
+        #[pallet::weight(< T as Config >::WeightInfo::finalize_job(MAX_SLOTS))]
+        pub fn finalize_job(
+            origin: OriginFor<T>,
+            job_id: JobId<T::AccountId>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
 
+            <StoredJobStatus<T>>::get(&job_id.0, &job_id.1).ok_or(Error::<T>::JobStatusNotFound)?;
             let registration = <StoredJobRegistration<T>>::get(&job_id.0, &job_id.1)
                 .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
 
-            let now = Self::now()?;
-            let now_max = now
-                .checked_add(T::ReportTolerance::get())
+            // no report is accepted after the last execution ended, even with the maximum start delay applied
+            let (_, end) = registration
+                .schedule
+                .range(registration.schedule.max_start_delay)
                 .ok_or(Error::<T>::CalculationOverflow)?;
-
             ensure!(
-                registration
-                    .schedule
-                    .overlaps(assignment.start_delay, now, now_max)
-                    .ok_or(Error::<T>::CalculationOverflow)?,
-                Error::<T>::ReportOutsideSchedule
+                Self::now()? >= end,
+                Error::<T>::CannotFinalizeJobBeforeScheduleEnd
             );
 
-            if last {
-                // TODO update reputation since we don't expect further reports for this job
-                // (only for attested devices! because non-attested devices)
+            Self::clear_job(&job_id, &registration)?;
 
-                // removed completed job from all storage points (completed SLA gets still deposited in event below)
-                <StoredMatches<T>>::remove(&who, &job_id);
-                <StoredJobStatus<T>>::remove(&job_id.0, &job_id.1);
+            // refund only after all other steps succeeded without errors because refunding reward is not revertable
+            Self::refund(&job_id)?;
 
-                // increase capacity
-                <StoredStorageCapacity<T>>::mutate(&who, |c| {
-                    *c = c.unwrap_or(0).checked_add(registration.storage.into())
-                });
+            Self::deposit_event(Event::JobFinalized(job_id));
+            Ok(().into())
+        }
 
-                <StoredJobRegistration<T>>::remove(&job_id.0, &job_id.1);
-            }
+        /// Reports like [`Pallet::report`] and stores the `output` of the execution on-chain, reserving a deposit from the consumer.
+        ///
+        /// The job has to accept outputs up to the size of `output` with its `max_output_size`. Outputs of late reports are not stored and only one output is stored per execution.
+        /// The output is retained for [`Config::OutputRetention`] blocks or until the consumer removes it with [`Pallet::remove_execution_outputs`].
+        #[pallet::call_index(6)]
+        #[pallet::weight(< T as Config >::WeightInfo::report_with_output(output.len() as u32))]
+        pub fn report_with_output(
+            origin: OriginFor<T>, // source
+            job_id: JobId<T::AccountId>,
+            last: bool,
+            execution_result: ExecutionResult,
+            output: ExecutionOutput,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let registration = <StoredJobRegistration<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
+            let e: <T as Config>::RegistrationExtra = registration.extra.into();
+            let requirements: JobRequirementsFor<T> = e.into();
+            let max_output_size = requirements
+                .max_output_size
+                .ok_or(Error::<T>::ExecutionOutputNotAccepted)?;
+            ensure!(
+                output.len() as u32 <= max_output_size,
+                Error::<T>::ExecutionOutputTooLarge
+            );
 
-            // pay only after all other steps succeeded without errors because paying reward is not revertable
-            T::RewardManager::pay_reward(
-                assignment.fee_per_execution.clone(),
-                T::Lookup::unlookup(who.clone()),
-            )?;
+            if let Some(execution) =
+                Self::do_report(who.clone(), job_id.clone(), last, execution_result)?
+            {
+                // a repeated report within the same execution's window must not replace the output stored for it
+                ensure!(
+                    !<StoredExecutionOutput<T>>::contains_key(&job_id, (&who, execution)),
+                    Error::<T>::ExecutionOutputAlreadyStored
+                );
+                let deposit = T::OutputDepositPerByte::get()
+                    .saturating_mul((output.len() as u32).into())
+                    .saturating_add(T::OutputDepositBase::get());
+                T::Currency::reserve(&job_id.0, deposit)
+                    .map_err(|_| Error::<T>::InsufficientBalanceForOutputDeposit)?;
+                let expiry = <frame_system::Pallet<T>>::block_number()
+                    .saturating_add(T::OutputRetention::get());
+                <StoredExecutionOutput<T>>::insert(
+                    &job_id,
+                    (&who, execution),
+                    ExecutionOutputRecord {
+                        output,
+                        deposit,
+                        expiry,
+                    },
+                );
+                <StoredExecutionOutputExpiry<T>>::insert(expiry, (&job_id, &who, execution), ());
+                Self::deposit_event(Event::ExecutionOutputStored(job_id, who, execution));
+            }
+            Ok(().into())
+        }
 
-            match execution_result {
-                ExecutionResult::Success(operation_hash) => {
-                    Self::deposit_event(Event::ExecutionSuccess(job_id.clone(), operation_hash))
-                }
-                ExecutionResult::Failure(message) => {
-                    Self::deposit_event(Event::ExecutionFailure(job_id.clone(), message))
-                }
+        /// Removes up to `limit` stored execution outputs of a job and returns their deposits. Can only be called by the job's consumer.
+        #[pallet::call_index(7)]
+        #[pallet::weight(< T as Config >::WeightInfo::remove_execution_outputs(*limit))]
+        pub fn remove_execution_outputs(
+            origin: OriginFor<T>,
+            job_id: JobId<T::AccountId>,
+            limit: u32,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(who == job_id.0, Error::<T>::OnlyConsumerCanRemoveOutputs);
+
+            let outputs: Vec<(T::AccountId, u64)> =
+                <StoredExecutionOutput<T>>::iter_key_prefix(&job_id)
+                    .take(limit as usize)
+                    .collect();
+            let removed = outputs.len() as u32;
+            for (source, execution) in outputs {
+                Self::remove_execution_output(&job_id, &source, execution);
             }
 
-            Self::deposit_event(Event::Reported(job_id, who, assignment.clone()));
+            Self::deposit_event(Event::ExecutionOutputsRemoved(job_id, removed));
             Ok(().into())
         }
     }
@@ -512,6 +903,11 @@ pub mod pallet {
                 Error::<T>::JobRegistrationEndBeforeStart
             );
             ensure!(requirements.slots > 0, Error::<T>::JobRegistrationZeroSlots);
+            ensure!(
+                requirements.required_slots() > 0
+                    && requirements.required_slots() <= requirements.slots,
+                Error::<T>::JobRegistrationInvalidMinSlots
+            );
             let reward_amount: T::AssetAmount = requirements
                 .reward
                 .try_get_amount()
@@ -521,56 +917,115 @@ pub mod pallet {
                 reward_amount > 0u8.into(),
                 Error::<T>::JobRegistrationZeroReward
             );
-
-            if let Some(job_status) = <StoredJobStatus<T>>::get(&who, &registration.script) {
+            if let Some(slot_rewards) = &requirements.slot_rewards {
                 ensure!(
-                    job_status == JobStatus::Open,
-                    Error::<T>::JobRegistrationUnmodifiable
+                    slot_rewards.len() == requirements.slots as usize,
+                    Error::<T>::JobRegistrationInvalidSlotRewards
                 );
+                let reward_asset: <T as Config>::AssetId = requirements
+                    .reward
+                    .try_get_asset_id()
+                    .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                    .into();
+                for slot_reward in slot_rewards {
+                    let slot_reward_asset: <T as Config>::AssetId = slot_reward
+                        .try_get_asset_id()
+                        .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                        .into();
+                    ensure!(
+                        slot_reward_asset == reward_asset,
+                        Error::<T>::JobRegistrationInvalidSlotRewards
+                    );
+                    let slot_reward_amount: T::AssetAmount = slot_reward
+                        .try_get_amount()
+                        .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                        .into();
+                    ensure!(
+                        slot_reward_amount > 0u8.into(),
+                        Error::<T>::JobRegistrationZeroReward
+                    );
+                }
+            }
+
+            if let Some(job_status) = <StoredJobStatus<T>>::get(&who, &registration.script) {
+                if job_status.is_terminal() {
+                    // a job can be registered again once it ended
+                    Self::set_job_status(
+                        &(who.clone(), registration.script.clone()),
+                        JobStatus::Open,
+                    )?;
+                } else {
+                    ensure!(
+                        job_status == JobStatus::Open,
+                        Error::<T>::JobRegistrationUnmodifiable
+                    );
+                }
             } else {
                 <StoredJobStatus<T>>::insert(&who, &registration.script, JobStatus::default());
             }
 
+            // a previous registration of the same job gets overwritten, so its reward is refunded
+            Self::refund(&(who.clone(), registration.script.clone()))?;
+
+            // reward is understood per slot and execution
+            let mut reward_amount = Self::total_reward_amount(registration)?;
             match requirements.instant_match {
                 Some(sources) => {
-                    Self::process_matching(once(&Match {
+                    for (_, _, unassigned_reward) in Self::process_matching(once(&Match {
                         job_id: (who.clone(), registration.script.clone()),
                         sources,
-                    }))?;
+                    }))? {
+                        // the reward of slots left unassigned is not locked in the first place
+                        let unassigned_amount: T::AssetAmount = unassigned_reward
+                            .try_get_amount()
+                            .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                            .into();
+                        reward_amount = reward_amount
+                            .checked_sub(&unassigned_amount)
+                            .ok_or(Error::<T>::CalculationOverflow)?;
+                    }
                 }
                 None => {}
             }
 
-            // reward is understood per slot and execution
             let mut reward = requirements.reward.clone();
             reward
-                .with_amount(Self::total_reward_amount(registration)?.into())
+                .with_amount(reward_amount.into())
                 .map_err(|_| Error::<T>::RewardConversionFailed)?;
 
             // lock only after all other steps succeeded without errors because locking reward is not revertable
             T::RewardManager::lock_reward(reward.clone(), T::Lookup::unlookup(who.clone()))?;
+            <StoredJobBudget<T>>::insert(&who, &registration.script, reward);
 
             Ok(().into())
         }
 
         /// Deregisters a job for the given script.
+        ///
+        /// The unpaid budget is refunded to the consumer. Only acknowledged sources in the middle of an execution they did not
+        /// report yet get paid for that execution.
         fn deregister_hook(who: &T::AccountId, script: &Script) -> Result<(), DispatchError> {
             let job_status =
                 <StoredJobStatus<T>>::get(&who, &script).ok_or(Error::<T>::JobStatusNotFound)?;
-            // lazily evaluated check if job is overdue
-            let overdue = || -> Result<bool, DispatchError> {
-                let registration = <StoredJobRegistration<T>>::get(&who, &script)
-                    .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
-
-                Ok(Self::now()? >= registration.schedule.start_time)
-            };
+            let registration = <StoredJobRegistration<T>>::get(&who, &script)
+                .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
             ensure!(
                 // allow to deregister overdue jobs
-                job_status == JobStatus::Open || overdue()?,
+                job_status == JobStatus::Open || Self::now()? >= registration.schedule.start_time,
                 Error::<T>::JobRegistrationUnmodifiable
             );
 
-            <StoredJobStatus<T>>::remove(&who, &script);
+            let job_id = (who.clone(), script.clone());
+            let reserved = Self::reserved_payments(&job_id, &registration)?;
+            Self::clear_job(&job_id, &registration)?;
+
+            // pay and refund only after all other steps succeeded without errors because paying reward is not revertable
+            for (source, payment) in reserved {
+                Self::consume_budget(&job_id, &payment)?;
+                T::RewardManager::pay_reward(payment, T::Lookup::unlookup(source))?;
+            }
+            Self::refund(&job_id)?;
+
             Ok(().into())
         }
 
@@ -590,154 +1045,195 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Removes the source from the matches it did not acknowledge yet, so that the jobs can be matched again.
+        ///
+        /// Assignments the source already acknowledged are kept.
+        fn attestation_revoked_hook(source: &T::AccountId) -> Result<(), DispatchError> {
+            let unacknowledged: Vec<(JobId<T::AccountId>, AssignmentFor<T>)> =
+                <StoredMatches<T>>::iter_prefix(source)
+                    .filter(|(_, assignment)| !assignment.acknowledged)
+                    .collect();
+            for (job_id, assignment) in unacknowledged {
+                Self::revoke_unacknowledged_match(&job_id, source, &assignment)?;
+            }
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
-        /// Checks if a Processor - Job match is possible and returns the job reward.
-        fn process_matching<'a>(
-            matching: impl IntoIterator<Item = &'a Match<T::AccountId>>,
-        ) -> Result<RewardFor<T>, DispatchError> {
-            // Currently we require all matches to be rewarded with the same asset
-            let mut remaining_reward: Option<(RewardFor<T>, T::AssetAmount)> = None;
+        /// Processes a report of a source, see [`Pallet::report`].
+        ///
+        /// Returns the index of the execution reported, or [None] if the report arrived late.
+        fn do_report(
+            who: T::AccountId,
+            job_id: JobId<T::AccountId>,
+            last: bool,
+            execution_result: ExecutionResult,
+        ) -> Result<Option<u64>, DispatchError> {
+            // find assignment
+            let mut assignment = <StoredMatches<T>>::get(&who, &job_id)
+                .ok_or(Error::<T>::ReportFromUnassignedSource)?;
 
-            for m in matching {
-                let registration = <StoredJobRegistration<T>>::get(&m.job_id.0, &m.job_id.1)
-                    .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
-                let e: <T as Config>::RegistrationExtra = registration.extra.clone().into();
-                let requirements: JobRequirementsFor<T> = e.into();
+            // CHECK that job is assigned
+            ensure!(
+                assignment.acknowledged,
+                Error::<T>::CannotReportWhenNotAcknowledged
+            );
 
-                let now = Self::now()?;
-                ensure!(
-                    now < registration.schedule.start_time,
-                    Error::<T>::OverdueMatch
-                );
-                let l: u8 = m.sources.len().try_into().unwrap_or(0);
-                ensure!(
-                    // NOTE: we are checking for duplicates while inserting/mutating StoredMatches below
-                    l == requirements.slots,
-                    Error::<T>::IncorrectSourceCountInMatch
-                );
+            // CHECK that we don't accept more reports than expected
+            ensure!(
+                assignment.sla.met < assignment.sla.total,
+                Error::<T>::MoreReportsThanExpected
+            );
 
-                // parse reward into asset_id and amount
-                let reward_asset: <T as Config>::AssetId = requirements
-                    .reward
-                    .try_get_asset_id()
-                    .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
-                    .into();
-                T::AssetValidator::validate(&reward_asset).map_err(|e| e.into())?;
+            let registration = <StoredJobRegistration<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
 
-                let reward_amount: <T as Config>::AssetAmount = requirements
-                    .reward
-                    .try_get_amount()
-                    .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
-                    .into();
+            let now = Self::now()?;
+            let now_max = now
+                .checked_add(T::ReportTolerance::get())
+                .ok_or(Error::<T>::CalculationOverflow)?;
 
-                // keep track of total fee in assignments to check later if it exceeds reward
-                let mut total_fee: <T as Config>::AssetAmount = 0u8.into();
+            if !registration
+                .schedule
+                .overlaps(assignment.start_delay, now, now_max)
+                .ok_or(Error::<T>::CalculationOverflow)?
+            {
+                // reports before the first execution are rejected, later ones missed the execution they were intended for
+                let (start, _) = registration
+                    .schedule
+                    .range(assignment.start_delay)
+                    .ok_or(Error::<T>::CalculationOverflow)?;
+                ensure!(now >= start, Error::<T>::ReportOutsideSchedule);
 
-                // `slot` is used for detecting duplicate source proposed for distinct slots
-                // TODO: add global (configurable) maximum of jobs assigned. This would limit the weight of `propose_matching` to a constant, since it depends on the number of active matches.
-                for (slot, planned_execution) in m.sources.iter().enumerate() {
-                    // CHECK attestation
-                    ensure!(
-                        !registration.allow_only_verified_sources
-                            || ensure_source_verified::<T>(&planned_execution.source).is_ok(),
-                        Error::<T>::UnverifiedSourceInMatch
-                    );
+                Self::update_reputation(&who, |r| {
+                    r.late_reports = r.late_reports.saturating_add(1)
+                });
+                Self::deposit_event(Event::ReportedLate(job_id, who));
+                return Ok(None);
+            }
 
-                    let ad = <StoredAdvertisementRestriction<T>>::get(&planned_execution.source)
-                        .ok_or(Error::<T>::AdvertisementNotFound)?;
+            let execution = execution_index(&registration.schedule, assignment.start_delay, now)
+                .ok_or(Error::<T>::CalculationOverflow)?;
+            assignment.sla.met += 1;
+            Self::consume_budget(&job_id, &assignment.fee_per_execution)?;
+
+            let sla = assignment.sla;
+            Self::update_reputation(&who, |r| {
+                r.met = r.met.saturating_add(1);
+                if last {
+                    // executions not reported until the source's last report are missed
+                    r.missed = r.missed.saturating_add(sla.total.saturating_sub(sla.met));
+                    r.jobs_completed = r.jobs_completed.saturating_add(1);
+                }
+            });
 
-                    let pricing = <StoredAdvertisementPricing<T>>::get(
-                        &planned_execution.source,
-                        &reward_asset,
-                    )
-                    .ok_or(Error::<T>::AdvertisementPricingNotFound)?;
+            let mut finalized = false;
+            if !last {
+                <StoredMatches<T>>::insert(&who, &job_id, &assignment);
+            } else {
+                Self::add_to_job_sla(&job_id, &sla);
 
-                    // CHECK the scheduling_window allow to schedule this job
-                    match pricing.scheduling_window {
-                        SchedulingWindow::End(end) => {
-                            ensure!(
-                                end >= registration
-                                    .schedule
-                                    .end_time
-                                    .checked_add(planned_execution.start_delay)
-                                    .ok_or(Error::<T>::CalculationOverflow)?,
-                                Error::<T>::SchedulingWindowExceededInMatch
-                            );
-                        }
-                        SchedulingWindow::Delta(delta) => {
-                            ensure!(
-                                now.checked_add(delta)
-                                    .ok_or(Error::<T>::CalculationOverflow)?
-                                    >= registration
-                                        .schedule
-                                        .end_time
-                                        .checked_add(planned_execution.start_delay)
-                                        .ok_or(Error::<T>::CalculationOverflow)?,
-                                Error::<T>::SchedulingWindowExceededInMatch
-                            );
-                        }
-                    }
+                // removed completed assignment from all storage points (completed SLA gets still deposited in event below)
+                <StoredMatches<T>>::remove(&who, &job_id);
+                <StoredMatchedSources<T>>::remove(&job_id, &who);
 
-                    // CHECK memory sufficient
-                    ensure!(
-                        ad.max_memory >= registration.memory,
-                        Error::<T>::MaxMemoryExceededInMatch
-                    );
+                // increase capacity
+                Self::set_storage_capacity(
+                    &who,
+                    <StoredStorageCapacity<T>>::get(&who)
+                        .unwrap_or(0)
+                        .checked_add(registration.storage.into()),
+                );
 
-                    // CHECK network request quota sufficient
-                    ensure!(
-                        // duration (s) * network_request_quota >= network_requests (per second)
-                        // <=>
-                        // duration (ms) / 1000 * network_request_quota >= network_requests (per second)
-                        // <=>
-                        // duration (ms) * network_request_quota >= network_requests (per second) * 1000
-                        registration
-                            .schedule
-                            .duration
-                            .checked_mul(ad.network_request_quota.into())
-                            .unwrap_or(0u64)
-                            >= registration
-                                .network_requests
-                                .saturated_into::<u64>()
-                                .checked_mul(1000u64)
-                                .unwrap_or(u64::MAX),
-                        Error::<T>::NetworkRequestQuotaExceededInMatch
-                    );
+                // the job is completed with the final report of the last assigned source
+                if !Self::has_matched_sources(&job_id) {
+                    Self::clear_job(&job_id, &registration)?;
+                    finalized = true;
+                }
+            }
 
-                    // CHECK remaining storage capacity sufficient
-                    let capacity = <StoredStorageCapacity<T>>::get(&planned_execution.source)
-                        .ok_or(Error::<T>::CapacityNotFound)?;
-                    ensure!(capacity > 0, Error::<T>::InsufficientStorageCapacityInMatch);
+            // pay only after all other steps succeeded without errors because paying reward is not revertable
+            T::RewardManager::pay_reward(
+                assignment.fee_per_execution.clone(),
+                T::Lookup::unlookup(who.clone()),
+            )?;
 
-                    // CHECK source is whitelisted
-                    ensure!(
-                        is_source_whitelisted::<T>(&planned_execution.source, &registration),
-                        Error::<T>::SourceNotAllowedInMatch
-                    );
+            if last {
+                Self::settle_stake(&job_id, &who, &assignment, true)?;
+            }
+            if finalized {
+                Self::refund(&job_id)?;
+            }
 
-                    // CHECK consumer is whitelisted
-                    ensure!(
-                        is_consumer_whitelisted::<T>(&m.job_id.0, &ad.allowed_consumers),
-                        Error::<T>::ConsumerNotAllowedInMatch
-                    );
+            match execution_result {
+                ExecutionResult::Success(operation_hash) => {
+                    Self::deposit_event(Event::ExecutionSuccess(job_id.clone(), operation_hash))
+                }
+                ExecutionResult::Failure(message) => {
+                    Self::deposit_event(Event::ExecutionFailure(job_id.clone(), message))
+                }
+            }
 
-                    // CHECK schedule
-                    Self::fits_schedule(
-                        &planned_execution.source,
-                        &registration.schedule,
-                        planned_execution.start_delay,
-                    )?;
+            Self::deposit_event(Event::Reported(job_id, who, assignment.clone()));
+            Ok(Some(execution))
+        }
 
-                    // calculate fee
-                    let fee_per_execution = Self::fee_per_execution(&registration, &pricing)?;
+        /// Checks if a Processor - Job match is possible and returns for each matched job the remaining reward (not spent on assigned fees)
+        /// and the reward of the slots left unassigned.
+        fn process_matching<'a>(
+            matching: impl IntoIterator<Item = &'a Match<T::AccountId>>,
+        ) -> Result<Vec<(JobId<T::AccountId>, RewardFor<T>, RewardFor<T>)>, DispatchError> {
+            let mut remaining_rewards: Vec<(JobId<T::AccountId>, RewardFor<T>, RewardFor<T>)> =
+                Vec::new();
 
-                    // CHECK price not exceeding reward
-                    ensure!(
-                        fee_per_execution <= reward_amount,
-                        Error::<T>::InsufficientRewardInMatch
-                    );
+            for m in matching {
+                let registration = <StoredJobRegistration<T>>::get(&m.job_id.0, &m.job_id.1)
+                    .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
+                let e: <T as Config>::RegistrationExtra = registration.extra.clone().into();
+                let requirements: JobRequirementsFor<T> = e.into();
+
+                let now = Self::now()?;
+                ensure!(
+                    now < registration.schedule.start_time,
+                    Error::<T>::OverdueMatch
+                );
+                let l: u8 = m.sources.len().try_into().unwrap_or(0);
+                ensure!(
+                    // NOTE: we are checking for duplicates while inserting/mutating StoredMatches below
+                    l >= requirements.required_slots() && l <= requirements.slots,
+                    Error::<T>::IncorrectSourceCountInMatch
+                );
+
+                // parse reward into asset_id and amount
+                let reward_asset: <T as Config>::AssetId = requirements
+                    .reward
+                    .try_get_asset_id()
+                    .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                    .into();
+                T::AssetValidator::validate(&reward_asset).map_err(|e| e.into())?;
+
+                // keep track of total fee in assignments to check later if it exceeds reward
+                let mut total_fee: <T as Config>::AssetAmount = 0u8.into();
+
+                // `slot` is used for detecting duplicate source proposed for distinct slots
+                // TODO: add global (configurable) maximum of jobs assigned. This would limit the weight of `propose_matching` to a constant, since it depends on the number of active matches.
+                for (slot, planned_execution) in m.sources.iter().enumerate() {
+                    let reward_amount: <T as Config>::AssetAmount = requirements
+                        .slot_reward(slot as u8)
+                        .try_get_amount()
+                        .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                        .into();
+                    let (pricing, capacity, fee_per_execution) = Self::check_planned_execution(
+                        &m.job_id,
+                        &registration,
+                        &requirements,
+                        &reward_asset,
+                        &reward_amount,
+                        now,
+                        planned_execution,
+                    )?;
 
                     let execution_count = registration.schedule.execution_count();
 
@@ -751,6 +1247,10 @@ pub mod pallet {
                     let mut fee = requirements.reward.clone();
                     fee.with_amount(fee_per_execution.into())
                         .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                    let mut stake = requirements.reward.clone();
+                    stake
+                        .with_amount(pricing.stake_per_execution.clone().into())
+                        .map_err(|_| Error::<T>::RewardConversionFailed)?;
 
                     // ASSIGN if not yet assigned (equals to CHECK that no duplicate source in a single mutate operation)
                     <StoredMatches<T>>::try_mutate(
@@ -765,6 +1265,7 @@ pub mod pallet {
                                         slot: slot as u8,
                                         start_delay: planned_execution.start_delay,
                                         fee_per_execution: fee,
+                                        stake_per_execution: stake,
                                         acknowledged: false,
                                         sla: SLA {
                                             total: execution_count,
@@ -777,47 +1278,425 @@ pub mod pallet {
                             Ok(())
                         },
                     )?;
-                    <StoredStorageCapacity<T>>::set(
+                    <StoredMatchedSources<T>>::insert(&m.job_id, &planned_execution.source, ());
+                    Self::set_storage_capacity(
                         &planned_execution.source,
                         capacity.checked_sub(registration.storage.into()),
                     );
                 }
 
-                // CHECK total fee is not exceeding reward
-                let total_reward_amount = Self::total_reward_amount(&registration)?;
-                let diff = total_reward_amount
+                // CHECK total fee is not exceeding reward of the assigned slots
+                let assigned_reward_amount =
+                    Self::slots_reward_amount(&registration, &requirements, 0..l)?;
+                let diff = assigned_reward_amount
                     .checked_sub(&total_fee)
                     .ok_or(Error::<T>::InsufficientRewardInMatch)?;
-                // We better check for diff positive <=> total_fee <= total_reward_amount
+                // We better check for diff positive <=> total_fee <= assigned_reward_amount
                 // because we cannot assume that asset amount is an unsigned integer for all future
                 ensure!(diff >= 0u32.into(), Error::<T>::InsufficientRewardInMatch);
 
-                if let Some(a) = remaining_reward.as_mut() {
+                let mut remaining_reward = requirements.reward.clone();
+                remaining_reward
+                    .with_amount(diff.into())
+                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                let mut unassigned_reward = requirements.reward.clone();
+                unassigned_reward
+                    .with_amount(
+                        Self::slots_reward_amount(
+                            &registration,
+                            &requirements,
+                            l..requirements.slots,
+                        )?
+                        .into(),
+                    )
+                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                remaining_rewards.push((m.job_id.clone(), remaining_reward, unassigned_reward));
+
+                Self::set_job_status(&m.job_id, JobStatus::Matched)?;
+                let deadline = <frame_system::Pallet<T>>::block_number()
+                    .saturating_add(T::AcknowledgementTimeout::get());
+                <StoredPendingMatch<T>>::insert(
+                    &m.job_id.0,
+                    &m.job_id.1,
+                    PendingMatch {
+                        deadline,
+                        matcher: None,
+                    },
+                );
+                <StoredAcknowledgementDeadline<T>>::insert(deadline, &m.job_id, ());
+                Self::deposit_event(Event::JobRegistrationMatched(m.clone()));
+            }
+
+            ensure!(!remaining_rewards.is_empty(), Error::<T>::EmptyMatching);
+            Ok(remaining_rewards)
+        }
+
+        /// Checks if a source can be assigned to a slot of a job with the planned execution.
+        ///
+        /// Returns the source's pricing for the job's reward asset, the source's remaining storage capacity and the fee per execution.
+        fn check_planned_execution(
+            job_id: &JobId<T::AccountId>,
+            registration: &JobRegistrationFor<T>,
+            requirements: &JobRequirementsFor<T>,
+            reward_asset: &<T as Config>::AssetId,
+            reward_amount: &<T as Config>::AssetAmount,
+            now: u64,
+            planned_execution: &PlannedExecution<T::AccountId>,
+        ) -> Result<(PricingVariantFor<T>, i64, <T as Config>::AssetAmount), DispatchError>
+        {
+            // CHECK attestation
+            ensure!(
+                !registration.allow_only_verified_sources
+                    || ensure_source_verified::<T>(&planned_execution.source).is_ok(),
+                Error::<T>::UnverifiedSourceInMatch
+            );
+
+            let ad = <StoredAdvertisementRestriction<T>>::get(&planned_execution.source)
+                .ok_or(Error::<T>::AdvertisementNotFound)?;
+
+            let pricing =
+                <StoredAdvertisementPricing<T>>::get(&planned_execution.source, reward_asset)
+                    .ok_or(Error::<T>::AdvertisementPricingNotFound)?;
+
+            // CHECK the scheduling_window allow to schedule this job
+            match pricing.scheduling_window {
+                SchedulingWindow::End(end) => {
                     ensure!(
-                        a.0 == requirements.reward,
-                        Error::<T>::MultipleRewardAssetsInMatch
+                        end >= registration
+                            .schedule
+                            .end_time
+                            .checked_add(planned_execution.start_delay)
+                            .ok_or(Error::<T>::CalculationOverflow)?,
+                        Error::<T>::SchedulingWindowExceededInMatch
+                    );
+                }
+                SchedulingWindow::Delta(delta) => {
+                    ensure!(
+                        now.checked_add(delta)
+                            .ok_or(Error::<T>::CalculationOverflow)?
+                            >= registration
+                                .schedule
+                                .end_time
+                                .checked_add(planned_execution.start_delay)
+                                .ok_or(Error::<T>::CalculationOverflow)?,
+                        Error::<T>::SchedulingWindowExceededInMatch
                     );
+                }
+            }
 
-                    a.1 =
-                        a.1.checked_add(&diff)
-                            .ok_or(Error::<T>::CalculationOverflow)?;
-                } else {
-                    remaining_reward = Some((requirements.reward, diff));
+            // CHECK memory sufficient
+            ensure!(
+                ad.max_memory >= registration.memory,
+                Error::<T>::MaxMemoryExceededInMatch
+            );
+
+            // CHECK network request quota sufficient
+            ensure!(
+                // duration (s) * network_request_quota >= network_requests (per second)
+                // <=>
+                // duration (ms) / 1000 * network_request_quota >= network_requests (per second)
+                // <=>
+                // duration (ms) * network_request_quota >= network_requests (per second) * 1000
+                registration
+                    .schedule
+                    .duration
+                    .checked_mul(ad.network_request_quota.into())
+                    .unwrap_or(0u64)
+                    >= registration
+                        .network_requests
+                        .saturated_into::<u64>()
+                        .checked_mul(1000u64)
+                        .unwrap_or(u64::MAX),
+                Error::<T>::NetworkRequestQuotaExceededInMatch
+            );
+
+            // CHECK remaining storage capacity sufficient
+            let capacity = <StoredStorageCapacity<T>>::get(&planned_execution.source)
+                .ok_or(Error::<T>::CapacityNotFound)?;
+            ensure!(capacity > 0, Error::<T>::InsufficientStorageCapacityInMatch);
+
+            // CHECK source is whitelisted
+            ensure!(
+                is_source_whitelisted::<T>(&planned_execution.source, &registration),
+                Error::<T>::SourceNotAllowedInMatch
+            );
+
+            // CHECK consumer is whitelisted
+            ensure!(
+                is_consumer_whitelisted::<T>(&job_id.0, &ad.allowed_consumers),
+                Error::<T>::ConsumerNotAllowedInMatch
+            );
+
+            // CHECK reputation sufficient, it is only tracked for attested sources
+            if let Some(min_reputation) = requirements.min_reputation {
+                let reputation = <StoredReputation<T>>::get(&planned_execution.source);
+                ensure!(
+                    ensure_source_verified::<T>(&planned_execution.source).is_ok()
+                        && reputation.due() >= T::MinReputationHistory::get()
+                        && reputation.score() >= min_reputation,
+                    Error::<T>::InsufficientReputationInMatch
+                );
+            }
+
+            // CHECK schedule
+            Self::fits_schedule(
+                &planned_execution.source,
+                &registration.schedule,
+                planned_execution.start_delay,
+            )?;
+
+            // calculate fee
+            let fee_per_execution = Self::fee_per_execution(&registration, &pricing)?;
+
+            // CHECK price not exceeding reward
+            ensure!(
+                &fee_per_execution <= reward_amount,
+                Error::<T>::InsufficientRewardInMatch
+            );
+
+            Ok((pricing, capacity, fee_per_execution))
+        }
+
+        /// Matches open jobs with advertised sources on-chain, consuming at most `max_weight`. Returns the weight consumed.
+        ///
+        /// Jobs are visited in the order of [`StoredJobRegistration`], continuing after the job visited last in the previous run.
+        /// Since there is no matcher to reward, the remaining reward of a job matched stays in its budget to be refunded to the consumer.
+        pub fn auto_match(max_weight: Weight) -> Weight {
+            let max_candidates = T::MaxAutoMatchCandidates::get();
+            // reading and writing the cursor
+            let mut consumed = T::DbWeight::get().reads_writes(1, 1);
+            if max_candidates == 0 || consumed.any_gt(max_weight) {
+                return Weight::zero();
+            }
+            let weight_per_job = <T as Config>::WeightInfo::auto_match_job(max_candidates);
+
+            let mut cursor = <StoredAutoMatchCursor<T>>::get();
+            let mut jobs = match cursor.as_ref() {
+                Some(job_id) => <StoredJobRegistration<T>>::iter_from(
+                    <StoredJobRegistration<T>>::hashed_key_for(&job_id.0, &job_id.1),
+                ),
+                None => <StoredJobRegistration<T>>::iter(),
+            };
+            while !consumed.saturating_add(weight_per_job).any_gt(max_weight) {
+                let (consumer, script, registration) = match jobs.next() {
+                    Some(job) => job,
+                    None => {
+                        // start over with the first job in the next run
+                        cursor = None;
+                        break;
+                    }
+                };
+                consumed = consumed.saturating_add(weight_per_job);
+                let job_id = (consumer, script);
+                cursor = Some(job_id.clone());
+
+                if <StoredJobStatus<T>>::get(&job_id.0, &job_id.1) != Some(JobStatus::Open) {
+                    continue;
                 }
+                if let Some(m) = Self::find_match(&job_id, &registration) {
+                    // revert partial changes of a failed matching, as it would happen for a failed extrinsic
+                    let _ = with_transaction(|| {
+                        match Self::process_matching(once(&m)).and_then(|matched| {
+                            matched
+                                .into_iter()
+                                .try_for_each(|(job_id, _, unassigned_reward)| {
+                                    Self::refund_unassigned_slots(&job_id, unassigned_reward)
+                                })
+                        }) {
+                            Ok(_) => TransactionOutcome::Commit(Ok(())),
+                            Err(e) => TransactionOutcome::Rollback(Err::<(), DispatchError>(e)),
+                        }
+                    });
+                }
+            }
 
-                <StoredJobStatus<T>>::insert(&m.job_id.0, &registration.script, JobStatus::Matched);
-                Self::deposit_event(Event::JobRegistrationMatched(m.clone()));
+            match cursor {
+                Some(job_id) => <StoredAutoMatchCursor<T>>::put(job_id),
+                None => <StoredAutoMatchCursor<T>>::kill(),
             }
-            // If we arrive here with remaining_reward None, then matching was empty
-            if let Some(reward) = remaining_reward.as_mut() {
-                reward
-                    .0
-                    .with_amount(reward.1.clone().into())
-                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
-                return Ok(reward.0.to_owned());
+            consumed
+        }
+
+        /// Searches the candidate sources for a match of as many slots of a job as possible, but at least its `min_slots`, trying start delays spread over `[0, max_start_delay]`.
+        ///
+        /// At most [`Config::MaxAutoMatchCandidates`] entries of the advertisement indices are scanned for candidates, see [`Self::candidate_sources`].
+        /// Candidates with more than [`AUTO_MATCH_MAX_SOURCE_MATCHES`] matches are skipped.
+        fn find_match(
+            job_id: &JobId<T::AccountId>,
+            registration: &JobRegistrationFor<T>,
+        ) -> Option<Match<T::AccountId>> {
+            let e: <T as Config>::RegistrationExtra = registration.extra.clone().into();
+            let requirements: JobRequirementsFor<T> = e.into();
+
+            let now = Self::now().ok()?;
+            if now >= registration.schedule.start_time {
+                return None;
+            }
+            let reward_asset: <T as Config>::AssetId =
+                requirements.reward.try_get_asset_id().ok()?.into();
+
+            let max_start_delay = registration.schedule.max_start_delay;
+            let steps = if max_start_delay == 0 {
+                0
             } else {
-                return Err(Error::<T>::EmptyMatching.into());
+                AUTO_MATCH_START_DELAY_STEPS
+            };
+
+            let mut sources: Vec<PlannedExecution<T::AccountId>> = Vec::new();
+            // the scanned index entries are limited, not only the candidates passing the filter
+            for source in Self::iter_indexed_sources(registration)
+                .take(T::MaxAutoMatchCandidates::get() as usize)
+                .filter(|(bucket, source)| {
+                    Self::is_candidate_source(registration, &reward_asset, *bucket, source)
+                })
+                .map(|(_, source)| source)
+            {
+                if sources.len() >= requirements.slots as usize {
+                    break;
+                }
+                // bound the schedule checks for the source
+                if <StoredMatches<T>>::iter_key_prefix(&source)
+                    .nth(AUTO_MATCH_MAX_SOURCE_MATCHES as usize)
+                    .is_some()
+                {
+                    continue;
+                }
+                let reward_amount: <T as Config>::AssetAmount = requirements
+                    .slot_reward(sources.len() as u8)
+                    .try_get_amount()
+                    .ok()?
+                    .into();
+                let planned_execution = (0..=steps)
+                    .map(|step| PlannedExecution {
+                        source: source.clone(),
+                        start_delay: max_start_delay / steps.max(1) * step,
+                    })
+                    .find(|planned_execution| {
+                        Self::check_planned_execution(
+                            job_id,
+                            registration,
+                            &requirements,
+                            &reward_asset,
+                            &reward_amount,
+                            now,
+                            planned_execution,
+                        )
+                        .is_ok()
+                    });
+                if let Some(planned_execution) = planned_execution {
+                    sources.push(planned_execution);
+                }
+            }
+
+            if sources.len() < requirements.required_slots() as usize {
+                return None;
             }
+            Some(Match {
+                job_id: job_id.clone(),
+                sources,
+            })
+        }
+
+        /// Returns the advertised sources accepting the job's reward asset with sufficient memory and remaining storage capacity.
+        ///
+        /// This is a pre-selection based on the advertisement indices. Candidates might still fail other checks in matching, e.g. on their schedule.
+        pub fn candidate_sources(
+            registration: &JobRegistrationFor<T>,
+        ) -> Result<Vec<T::AccountId>, DispatchError> {
+            let e: <T as Config>::RegistrationExtra = registration.extra.clone().into();
+            let requirements: JobRequirementsFor<T> = e.into();
+            let reward_asset: <T as Config>::AssetId = requirements
+                .reward
+                .try_get_asset_id()
+                .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                .into();
+
+            Ok(Self::iter_candidate_sources(registration, &reward_asset).collect())
+        }
+
+        /// Returns the fee per execution a source charges for a job, according to the source's pricing for the job's reward asset.
+        pub fn quote_fee_per_execution(
+            registration: &JobRegistrationFor<T>,
+            source: &T::AccountId,
+        ) -> Result<T::AssetAmount, DispatchError> {
+            let e: <T as Config>::RegistrationExtra = registration.extra.clone().into();
+            let requirements: JobRequirementsFor<T> = e.into();
+            let reward_asset: <T as Config>::AssetId = requirements
+                .reward
+                .try_get_asset_id()
+                .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                .into();
+            let pricing = <StoredAdvertisementPricing<T>>::get(source, &reward_asset)
+                .ok_or(Error::<T>::AdvertisementPricingNotFound)?;
+
+            Ok(Self::fee_per_execution(registration, &pricing)?)
+        }
+
+        /// Validates a match for a registered job like [`Pallet::propose_matching`] does, without changing storage.
+        ///
+        /// Returns the remaining reward (not spent on assigned fees) of the job if the match is valid.
+        pub fn dry_run_match(m: &Match<T::AccountId>) -> Result<RewardFor<T>, DispatchError> {
+            with_transaction(|| {
+                let result = Self::process_matching(once(m)).and_then(|remaining_rewards| {
+                    remaining_rewards
+                        .into_iter()
+                        .next()
+                        .map(|(_, remaining_reward, _)| remaining_reward)
+                        .ok_or(Error::<T>::EmptyMatching.into())
+                });
+                // always revert the changes of matching
+                TransactionOutcome::Rollback(result)
+            })
+        }
+
+        /// Iterates the candidate sources for a job, see [`Self::candidate_sources`].
+        fn iter_candidate_sources<'a>(
+            registration: &'a JobRegistrationFor<T>,
+            reward_asset: &'a <T as Config>::AssetId,
+        ) -> impl Iterator<Item = T::AccountId> + 'a {
+            Self::iter_indexed_sources(registration)
+                .filter(move |(bucket, source)| {
+                    Self::is_candidate_source(registration, reward_asset, *bucket, source)
+                })
+                .map(|(_, source)| source)
+        }
+
+        /// Iterates the entries of [`StoredSourcesByMemoryBucket`] as `(bucket, source)`, starting with the bucket of the job's memory.
+        fn iter_indexed_sources(
+            registration: &JobRegistrationFor<T>,
+        ) -> impl Iterator<Item = (u8, T::AccountId)> {
+            (resource_bucket(registration.memory.into())..=MAX_MEMORY_BUCKET).flat_map(|bucket| {
+                <StoredSourcesByMemoryBucket<T>>::iter_key_prefix(bucket)
+                    .map(move |source| (bucket, source))
+            })
+        }
+
+        /// Checks if a source in a memory `bucket` accepts the job's reward asset with sufficient memory and remaining storage capacity.
+        fn is_candidate_source(
+            registration: &JobRegistrationFor<T>,
+            reward_asset: &<T as Config>::AssetId,
+            bucket: u8,
+            source: &T::AccountId,
+        ) -> bool {
+            <StoredSourcesByAsset<T>>::contains_key(reward_asset, source)
+                // the lowest bucket also contains sources with less memory than required
+                && (bucket > resource_bucket(registration.memory.into())
+                    || <StoredAdvertisementRestriction<T>>::get(source)
+                        .map_or(false, |ad| ad.max_memory >= registration.memory))
+                && <StoredStorageCapacity<T>>::get(source).map_or(false, |capacity| {
+                    capacity > 0 && capacity >= registration.storage.into()
+                })
+        }
+
+        /// Sets the remaining storage capacity of a source and keeps [`StoredSourcesByStorageBucket`] consistent.
+        fn set_storage_capacity(source: &T::AccountId, capacity: Option<i64>) {
+            if let Some(old) = <StoredStorageCapacity<T>>::get(source) {
+                <StoredSourcesByStorageBucket<T>>::remove(storage_bucket(old), source);
+            }
+            if let Some(capacity) = capacity {
+                <StoredSourcesByStorageBucket<T>>::insert(storage_bucket(capacity), source, ());
+            }
+            <StoredStorageCapacity<T>>::set(source, capacity);
         }
 
         /// Returns true if the source has currently at least one match (not necessarily assigned).
@@ -827,6 +1706,493 @@ pub mod pallet {
             <StoredMatches<T>>::iter_prefix_values(&source).any(|_| true)
         }
 
+        /// Returns true if the job has currently at least one source matched (not necessarily assigned).
+        fn has_matched_sources(job_id: &JobId<T::AccountId>) -> bool {
+            <StoredMatchedSources<T>>::iter_prefix_values(job_id).any(|_| true)
+        }
+
+        /// Removes a job together with all its remaining matches from storage, restores the matched sources' capacity
+        /// and sets the job's terminal [JobStatus].
+        ///
+        /// The stakes of acknowledged assignments are settled, slashing missed executions only if the schedule already ended.
+        /// In that case, the missed executions are also recorded in the sources' [Reputation].
+        /// The job's budget is kept so that it can be refunded with [`Self::refund`].
+        fn clear_job(
+            job_id: &JobId<T::AccountId>,
+            registration: &JobRegistrationFor<T>,
+        ) -> Result<(), DispatchError> {
+            let status = <StoredJobStatus<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(Error::<T>::JobStatusNotFound)?;
+            let (_, end) = registration
+                .schedule
+                .range(registration.schedule.max_start_delay)
+                .ok_or(Error::<T>::CalculationOverflow)?;
+            let now = Self::now()?;
+            let ended = now >= end;
+            // all assigned sources already sent their last report
+            let reported =
+                matches!(status, JobStatus::Assigned(_)) && !Self::has_matched_sources(job_id);
+
+            for (source, _) in <StoredMatchedSources<T>>::drain_prefix(job_id) {
+                if let Some(assignment) = <StoredMatches<T>>::take(&source, job_id) {
+                    Self::settle_stake(job_id, &source, &assignment, ended)?;
+                    if ended {
+                        Self::add_to_job_sla(job_id, &assignment.sla);
+                        let missed = assignment.sla.total.saturating_sub(assignment.sla.met);
+                        if assignment.acknowledged {
+                            Self::update_reputation(&source, |r| {
+                                r.missed = r.missed.saturating_add(missed)
+                            });
+                        }
+                    }
+                }
+                Self::set_storage_capacity(
+                    &source,
+                    <StoredStorageCapacity<T>>::get(&source)
+                        .unwrap_or(0)
+                        .checked_add(registration.storage.into()),
+                );
+            }
+
+            let sla = <StoredJobSLA<T>>::take(&job_id.0, &job_id.1);
+            let terminal_status = if status == JobStatus::Open {
+                if now >= registration.schedule.start_time {
+                    JobStatus::Expired
+                } else {
+                    JobStatus::Cancelled
+                }
+            } else if ended || reported {
+                if sla.met == 0 {
+                    JobStatus::Expired
+                } else if sla.met >= sla.total {
+                    JobStatus::Completed
+                } else {
+                    JobStatus::PartiallyFulfilled
+                }
+            } else {
+                JobStatus::Cancelled
+            };
+            Self::set_job_status(job_id, terminal_status)?;
+
+            <StoredPendingMatch<T>>::remove(&job_id.0, &job_id.1);
+            <StoredJobRegistration<T>>::remove(&job_id.0, &job_id.1);
+            Ok(())
+        }
+
+        /// Returns the payments for the executions in progress that acknowledged sources of a job did not report yet.
+        ///
+        /// Executions not started yet, executions already ended and assignments never acknowledged are not paid.
+        fn reserved_payments(
+            job_id: &JobId<T::AccountId>,
+            registration: &JobRegistrationFor<T>,
+        ) -> Result<Vec<(T::AccountId, RewardFor<T>)>, DispatchError> {
+            let now = Self::now()?;
+            let mut payments = Vec::new();
+            for source in <StoredMatchedSources<T>>::iter_key_prefix(job_id) {
+                let assignment = match <StoredMatches<T>>::get(&source, job_id) {
+                    Some(assignment) if assignment.acknowledged => assignment,
+                    _ => continue,
+                };
+                let execution = match execution_in_progress(
+                    &registration.schedule,
+                    assignment.start_delay,
+                    now,
+                ) {
+                    Some(execution) => execution,
+                    None => continue,
+                };
+                // the source already reported as many executions as started until now
+                if assignment.sla.met > execution {
+                    continue;
+                }
+                payments.push((source, assignment.fee_per_execution.clone()));
+            }
+            Ok(payments)
+        }
+
+        /// Removes the sources that did not acknowledge a pending match until its deadline from the job and restores their capacity.
+        ///
+        /// The pending match is closed with [`Self::close_pending_match`]. If some of the matched sources acknowledged,
+        /// the job continues with the acknowledged sources only, and the executions of the removed sources count as missed.
+        ///
+        /// Returns the number of sources removed.
+        fn time_out_match(job_id: &JobId<T::AccountId>) -> Result<u64, DispatchError> {
+            let pending = match <StoredPendingMatch<T>>::take(&job_id.0, &job_id.1) {
+                Some(pending) => pending,
+                None => return Ok(0),
+            };
+            let status = <StoredJobStatus<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(Error::<T>::JobStatusNotFound)?;
+            let registration = <StoredJobRegistration<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
+
+            let unacknowledged: Vec<(T::AccountId, AssignmentFor<T>)> =
+                <StoredMatchedSources<T>>::iter_key_prefix(job_id)
+                    .filter_map(|source| {
+                        <StoredMatches<T>>::get(&source, job_id)
+                            .filter(|assignment| !assignment.acknowledged)
+                            .map(|assignment| (source, assignment))
+                    })
+                    .collect();
+            let mut dropped: Vec<T::AccountId> = Vec::with_capacity(unacknowledged.len());
+            for (source, assignment) in unacknowledged {
+                Self::remove_unacknowledged_match(
+                    job_id,
+                    &source,
+                    &assignment,
+                    &status,
+                    &registration,
+                );
+                dropped.push(source);
+            }
+            let removed = dropped.len() as u64;
+            Self::deposit_event(Event::MatchAcknowledgementTimedOut(job_id.clone(), dropped));
+
+            Self::close_pending_match(job_id, pending, &status, &registration)?;
+
+            Ok(removed)
+        }
+
+        /// Removes the unacknowledged match of a source from the job and restores the source's capacity.
+        fn remove_unacknowledged_match(
+            job_id: &JobId<T::AccountId>,
+            source: &T::AccountId,
+            assignment: &AssignmentFor<T>,
+            status: &JobStatus,
+            registration: &JobRegistrationFor<T>,
+        ) {
+            <StoredMatches<T>>::remove(source, job_id);
+            <StoredMatchedSources<T>>::remove(job_id, source);
+            Self::set_storage_capacity(
+                source,
+                <StoredStorageCapacity<T>>::get(source)
+                    .unwrap_or(0)
+                    .checked_add(registration.storage.into()),
+            );
+            if status != &JobStatus::Matched {
+                // the executions of the removed slot will never be reported
+                Self::add_to_job_sla(job_id, &assignment.sla);
+            }
+        }
+
+        /// Closes a pending match after its unacknowledged sources were removed.
+        ///
+        /// If none of the matched sources acknowledged, the job is open for matching again or gets cancelled if its start time passed,
+        /// and the matcher reward is clawed back if configured by [`Config::ClawBackMatcherReward`]. A failed claw-back is only logged.
+        fn close_pending_match(
+            job_id: &JobId<T::AccountId>,
+            pending: PendingMatchFor<T>,
+            status: &JobStatus,
+            registration: &JobRegistrationFor<T>,
+        ) -> Result<(), DispatchError> {
+            if status == &JobStatus::Matched {
+                if let (true, Some((matcher, matcher_reward))) =
+                    (T::ClawBackMatcherReward::get(), pending.matcher)
+                {
+                    // the job gets reopened or cancelled even if the matcher cannot pay back its reward
+                    let clawed_back = with_transaction(|| {
+                        match T::RewardManager::claw_back_matcher_reward(
+                            matcher_reward,
+                            T::Lookup::unlookup(matcher.clone()),
+                        )
+                        .and_then(|clawed_back| {
+                            Self::restore_budget(job_id, &clawed_back)?;
+                            Ok(clawed_back)
+                        }) {
+                            Ok(clawed_back) => TransactionOutcome::Commit(Ok(clawed_back)),
+                            Err(e) => TransactionOutcome::Rollback(Err(e)),
+                        }
+                    });
+                    match clawed_back {
+                        Ok(clawed_back) => Self::deposit_event(Event::MatcherRewardClawedBack(
+                            job_id.clone(),
+                            matcher,
+                            clawed_back,
+                        )),
+                        Err(e) => log::warn!(
+                            target: "runtime::acurast_marketplace",
+                            "failed to claw back matcher reward of job {:?}: {:?}",
+                            job_id,
+                            e
+                        ),
+                    }
+                }
+
+                if Self::now()? < registration.schedule.start_time {
+                    Self::set_job_status(job_id, JobStatus::Open)?;
+                } else {
+                    // the job can no longer be matched
+                    Self::set_job_status(job_id, JobStatus::Cancelled)?;
+                    <StoredJobRegistration<T>>::remove(&job_id.0, &job_id.1);
+                    Self::refund(job_id)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Removes a source whose attestation got revoked from a match it did not acknowledge yet, as if the acknowledgement deadline passed for it.
+        ///
+        /// Only jobs that require verified sources are affected. The pending match is closed once all remaining sources acknowledged.
+        fn revoke_unacknowledged_match(
+            job_id: &JobId<T::AccountId>,
+            source: &T::AccountId,
+            assignment: &AssignmentFor<T>,
+        ) -> Result<(), DispatchError> {
+            let pending = match <StoredPendingMatch<T>>::get(&job_id.0, &job_id.1) {
+                Some(pending) => pending,
+                None => return Ok(()),
+            };
+            let registration = <StoredJobRegistration<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(pallet_acurast::Error::<T>::JobRegistrationNotFound)?;
+            if !registration.allow_only_verified_sources {
+                return Ok(());
+            }
+            let status = <StoredJobStatus<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(Error::<T>::JobStatusNotFound)?;
+
+            Self::remove_unacknowledged_match(job_id, source, assignment, &status, &registration);
+            Self::deposit_event(Event::MatchRevoked(job_id.clone(), source.clone()));
+
+            if <StoredMatchedSources<T>>::iter_key_prefix(job_id).all(|other| {
+                <StoredMatches<T>>::get(&other, job_id)
+                    .map_or(true, |assignment| assignment.acknowledged)
+            }) {
+                <StoredPendingMatch<T>>::remove(&job_id.0, &job_id.1);
+                Self::close_pending_match(job_id, pending, &status, &registration)?;
+            }
+            Ok(())
+        }
+
+        /// Adds the [SLA] of an ended assignment to the job's accumulated [SLA].
+        fn add_to_job_sla(job_id: &JobId<T::AccountId>, sla: &SLA) {
+            <StoredJobSLA<T>>::mutate(&job_id.0, &job_id.1, |job_sla| {
+                job_sla.total = job_sla.total.saturating_add(sla.total);
+                job_sla.met = job_sla.met.saturating_add(sla.met);
+            });
+        }
+
+        /// Changes the status of a job after validating the transition and emits [`Event::JobStatusChanged`].
+        ///
+        /// A terminal status is removed from storage after [`Config::JobStatusRetention`] blocks.
+        fn set_job_status(job_id: &JobId<T::AccountId>, to: JobStatus) -> Result<(), Error<T>> {
+            let from = <StoredJobStatus<T>>::get(&job_id.0, &job_id.1)
+                .ok_or(Error::<T>::JobStatusNotFound)?;
+            ensure!(
+                from.can_transition_to(&to),
+                Error::<T>::InvalidJobStatusTransition
+            );
+
+            <StoredJobStatus<T>>::insert(&job_id.0, &job_id.1, to);
+            if to.is_terminal() {
+                let expiry = <frame_system::Pallet<T>>::block_number()
+                    .saturating_add(T::JobStatusRetention::get());
+                <StoredJobStatusExpiry<T>>::insert(expiry, job_id, ());
+            }
+
+            Self::deposit_event(Event::JobStatusChanged {
+                job_id: job_id.clone(),
+                from,
+                to,
+            });
+            Ok(())
+        }
+
+        /// Returns the reputation score of a source derived from its SLA history.
+        pub fn reputation_score(source: &T::AccountId) -> Permill {
+            <StoredReputation<T>>::get(source).score()
+        }
+
+        /// Updates the [Reputation] of a source, but only if the source has a valid attestation.
+        fn update_reputation(source: &T::AccountId, f: impl FnOnce(&mut Reputation)) {
+            if ensure_source_verified::<T>(source).is_err() {
+                return;
+            }
+            let reputation = <StoredReputation<T>>::mutate(source, |r| {
+                f(r);
+                r.clone()
+            });
+            Self::deposit_event(Event::ReputationUpdated(source.clone(), reputation));
+        }
+
+        /// Returns the total stake locked for an assignment, covering all executions of the SLA.
+        fn total_stake(assignment: &AssignmentFor<T>) -> Result<RewardFor<T>, Error<T>> {
+            let stake_per_execution: T::AssetAmount = assignment
+                .stake_per_execution
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                .into();
+            let mut stake = assignment.stake_per_execution.clone();
+            stake
+                .with_amount(
+                    stake_per_execution
+                        .checked_mul(&assignment.sla.total.into())
+                        .ok_or(Error::<T>::CalculationOverflow)?
+                        .into(),
+                )
+                .map_err(|_| Error::<T>::RewardConversionFailed)?;
+            Ok(stake)
+        }
+
+        /// Settles the stake locked for an acknowledged assignment.
+        ///
+        /// If `slash` is true, the stake for each execution missed according to the SLA is transferred to the consumer.
+        /// The rest of the stake is released to the source.
+        fn settle_stake(
+            job_id: &JobId<T::AccountId>,
+            source: &T::AccountId,
+            assignment: &AssignmentFor<T>,
+            slash: bool,
+        ) -> Result<(), DispatchError> {
+            if !assignment.acknowledged {
+                // stake only gets locked on acknowledgment
+                return Ok(());
+            }
+
+            let stake_per_execution: T::AssetAmount = assignment
+                .stake_per_execution
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                .into();
+            let total: T::AssetAmount = Self::total_stake(assignment)?
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                .into();
+            let slashed: T::AssetAmount = if slash {
+                stake_per_execution
+                    .checked_mul(
+                        &assignment
+                            .sla
+                            .total
+                            .saturating_sub(assignment.sla.met)
+                            .into(),
+                    )
+                    .ok_or(Error::<T>::CalculationOverflow)?
+            } else {
+                0u8.into()
+            };
+            let released = total
+                .checked_sub(&slashed)
+                .ok_or(Error::<T>::CalculationOverflow)?;
+
+            if slashed > 0u8.into() {
+                let mut stake = assignment.stake_per_execution.clone();
+                stake
+                    .with_amount(slashed.into())
+                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                T::RewardManager::release_stake(
+                    stake.clone(),
+                    T::Lookup::unlookup(job_id.0.clone()),
+                )?;
+                Self::deposit_event(Event::StakeSlashed(job_id.clone(), source.clone(), stake));
+            }
+            if released > 0u8.into() {
+                let mut stake = assignment.stake_per_execution.clone();
+                stake
+                    .with_amount(released.into())
+                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                T::RewardManager::release_stake(stake, T::Lookup::unlookup(source.clone()))?;
+            }
+            Ok(())
+        }
+
+        /// Reduces the budget of a job by a payment made from its locked reward.
+        fn consume_budget(
+            job_id: &JobId<T::AccountId>,
+            payment: &RewardFor<T>,
+        ) -> Result<(), Error<T>> {
+            let amount: T::AssetAmount = payment
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                .into();
+            <StoredJobBudget<T>>::try_mutate(&job_id.0, &job_id.1, |b| -> Result<(), Error<T>> {
+                let budget = b.as_mut().ok_or(Error::<T>::JobBudgetNotFound)?;
+                let remaining: T::AssetAmount = budget
+                    .try_get_amount()
+                    .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                    .into();
+                budget
+                    .with_amount(
+                        remaining
+                            .checked_sub(&amount)
+                            .ok_or(Error::<T>::InsufficientJobBudget)?
+                            .into(),
+                    )
+                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                Ok(())
+            })
+        }
+
+        /// Adds a payment reclaimed for a job back to its budget.
+        fn restore_budget(
+            job_id: &JobId<T::AccountId>,
+            payment: &RewardFor<T>,
+        ) -> Result<(), Error<T>> {
+            let amount: T::AssetAmount = payment
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                .into();
+            <StoredJobBudget<T>>::try_mutate(&job_id.0, &job_id.1, |b| -> Result<(), Error<T>> {
+                let budget = b.as_mut().ok_or(Error::<T>::JobBudgetNotFound)?;
+                let remaining: T::AssetAmount = budget
+                    .try_get_amount()
+                    .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                    .into();
+                budget
+                    .with_amount(
+                        remaining
+                            .checked_add(&amount)
+                            .ok_or(Error::<T>::CalculationOverflow)?
+                            .into(),
+                    )
+                    .map_err(|_| Error::<T>::RewardConversionFailed)?;
+                Ok(())
+            })
+        }
+
+        /// Removes a stored execution output and unreserves its deposit from the consumer.
+        fn remove_execution_output(
+            job_id: &JobId<T::AccountId>,
+            source: &T::AccountId,
+            execution: u64,
+        ) {
+            if let Some(record) = <StoredExecutionOutput<T>>::take(job_id, (source, execution)) {
+                T::Currency::unreserve(&job_id.0, record.deposit);
+            }
+        }
+
+        /// Refunds the reward of the slots left unassigned by a match from the job's budget to the consumer.
+        fn refund_unassigned_slots(
+            job_id: &JobId<T::AccountId>,
+            unassigned_reward: RewardFor<T>,
+        ) -> Result<(), DispatchError> {
+            let amount: T::AssetAmount = unassigned_reward
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                .into();
+            if amount > 0u8.into() {
+                Self::consume_budget(job_id, &unassigned_reward)?;
+                T::RewardManager::refund_reward(
+                    unassigned_reward,
+                    T::Lookup::unlookup(job_id.0.clone()),
+                )?;
+            }
+            Ok(())
+        }
+
+        /// Refunds the remaining budget of a job to the consumer and removes it from storage.
+        fn refund(job_id: &JobId<T::AccountId>) -> Result<(), DispatchError> {
+            if let Some(budget) = <StoredJobBudget<T>>::take(&job_id.0, &job_id.1) {
+                let remaining: T::AssetAmount = budget
+                    .try_get_amount()
+                    .map_err(|_| Error::<T>::InvalidAssetAmount)?
+                    .into();
+                if remaining > 0u8.into() {
+                    T::RewardManager::refund_reward(budget, T::Lookup::unlookup(job_id.0.clone()))?;
+                }
+            }
+            Ok(())
+        }
+
         /// Checks of a new job schedule fits with the existing schedule for a processor.
         fn fits_schedule(
             source: &T::AccountId,
@@ -877,27 +2243,41 @@ pub mod pallet {
         }
 
         /// Calculates the total reward amount.
-        fn total_reward_amount(
+        pub fn total_reward_amount(
             registration: &JobRegistrationFor<T>,
         ) -> Result<T::AssetAmount, Error<T>> {
             let e: <T as Config>::RegistrationExtra = registration.extra.clone().into();
             let requirements: JobRequirementsFor<T> = e.into();
 
-            let reward_amount: T::AssetAmount = requirements
-                .reward
-                .try_get_amount()
-                .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
-                .into();
+            Self::slots_reward_amount(registration, &requirements, 0..requirements.slots)
+        }
 
-            Ok(reward_amount
-                .checked_mul(&((requirements.slots as u128).into()))
-                .ok_or(Error::<T>::CalculationOverflow)?
-                .checked_mul(&registration.schedule.execution_count().into())
-                .ok_or(Error::<T>::CalculationOverflow)?)
+        /// Calculates the reward amount offered for all executions of the given slots.
+        fn slots_reward_amount(
+            registration: &JobRegistrationFor<T>,
+            requirements: &JobRequirementsFor<T>,
+            slots: impl Iterator<Item = u8>,
+        ) -> Result<T::AssetAmount, Error<T>> {
+            let mut amount: T::AssetAmount = 0u8.into();
+            for slot in slots {
+                let reward_amount: T::AssetAmount = requirements
+                    .slot_reward(slot)
+                    .try_get_amount()
+                    .map_err(|_| Error::<T>::JobRegistrationUnsupportedReward)?
+                    .into();
+                amount = amount
+                    .checked_add(
+                        &reward_amount
+                            .checked_mul(&registration.schedule.execution_count().into())
+                            .ok_or(Error::<T>::CalculationOverflow)?,
+                    )
+                    .ok_or(Error::<T>::CalculationOverflow)?;
+            }
+            Ok(amount)
         }
 
         /// Calculates the fee per job execution.
-        fn fee_per_execution(
+        pub fn fee_per_execution(
             registration: &JobRegistrationFor<T>,
             pricing: &PricingVariantFor<T>,
         ) -> Result<T::AssetAmount, Error<T>> {
@@ -0,0 +1,260 @@
+use frame_support::{
+    traits::{Get, GetStorageVersion},
+    weights::Weight,
+};
+
+use crate::{Config, Pallet};
+
+/// Translates the job registrations to the current [crate::JobRequirements] and backfills [crate::StoredJobBudget] and
+/// [crate::StoredMatchedSources], which did not exist before version 1.
+///
+/// The budget of an open job is its total reward. The budget of a matched job is the fee of the executions its matched sources
+/// did not report yet, since the share of the matcher was already paid out of the remaining reward.
+pub mod v1 {
+    use frame_support::{log, pallet_prelude::*, storage_alias};
+    use pallet_acurast::{JobId, JobRegistration, Script};
+    use sp_runtime::traits::{CheckedAdd, CheckedMul};
+    use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+    use super::*;
+    use crate::{
+        JobRequirements, JobRequirementsFor, JobStatus, PlannedExecution, Reward, RewardFor,
+        StoredJobBudget, StoredJobStatus, StoredMatchedSources, SLA,
+    };
+
+    /// The job requirements as stored before version 1.
+    #[derive(Encode, Decode)]
+    pub struct OldJobRequirements<Reward, AccountId> {
+        pub slots: u8,
+        pub reward: Reward,
+        pub instant_match: Option<Vec<PlannedExecution<AccountId>>>,
+    }
+
+    /// The assignment as stored before version 1.
+    #[derive(Encode, Decode)]
+    pub struct OldAssignment<Reward> {
+        pub slot: u8,
+        pub start_delay: u64,
+        pub fee_per_execution: Reward,
+        pub acknowledged: bool,
+        pub sla: SLA,
+    }
+
+    type OldJobRegistrationFor<T> = JobRegistration<
+        <T as frame_system::Config>::AccountId,
+        OldJobRequirements<RewardFor<T>, <T as frame_system::Config>::AccountId>,
+    >;
+
+    /// The job registrations of [pallet_acurast] with the job requirements as stored before version 1.
+    #[storage_alias]
+    pub(crate) type StoredJobRegistration<T: Config> = StorageDoubleMap<
+        pallet_acurast::Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        Blake2_128Concat,
+        Script,
+        OldJobRegistrationFor<T>,
+    >;
+
+    /// The matches with the assignments as stored before version 1.
+    #[storage_alias]
+    pub(crate) type StoredMatches<T: Config> = StorageDoubleMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        Blake2_128Concat,
+        JobId<<T as frame_system::Config>::AccountId>,
+        OldAssignment<RewardFor<T>>,
+    >;
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut weight = Weight::zero();
+
+        // the fee reserved for the executions not reported yet by the matched sources of each job
+        let mut reserved = BTreeMap::<JobId<T::AccountId>, T::AssetAmount>::new();
+        for (source, job_id, assignment) in StoredMatches::<T>::iter() {
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+            <StoredMatchedSources<T>>::insert(&job_id, &source, ());
+
+            let amount = assignment
+                .fee_per_execution
+                .try_get_amount()
+                .ok()
+                .map(|fee| -> T::AssetAmount { fee.into() })
+                .and_then(|fee| {
+                    fee.checked_mul(
+                        &assignment
+                            .sla
+                            .total
+                            .saturating_sub(assignment.sla.met)
+                            .into(),
+                    )
+                })
+                .unwrap_or(0u8.into());
+            let total = reserved.entry(job_id).or_insert(0u8.into());
+            if let Some(sum) = total.checked_add(&amount) {
+                *total = sum;
+            }
+        }
+
+        let registrations = StoredJobRegistration::<T>::iter().collect::<Vec<_>>();
+        weight = weight.saturating_add(T::DbWeight::get().reads(registrations.len() as u64));
+        let mut failed: u32 = 0;
+        for (consumer, script, registration) in registrations {
+            let requirements: JobRequirementsFor<T> = JobRequirements {
+                slots: registration.extra.slots,
+                min_slots: None,
+                reward: registration.extra.reward,
+                slot_rewards: None,
+                instant_match: registration.extra.instant_match,
+                min_reputation: None,
+                max_output_size: None,
+            };
+            let extra = match <T as pallet_acurast::Config>::RegistrationExtra::decode(
+                &mut &requirements.encode()[..],
+            ) {
+                Ok(extra) => extra,
+                Err(_) => {
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let status = <StoredJobStatus<T>>::get(&consumer, &script);
+            let budget_amount: Option<T::AssetAmount> = match status {
+                None | Some(JobStatus::Open) => requirements
+                    .reward
+                    .try_get_amount()
+                    .ok()
+                    .map(|reward| -> T::AssetAmount { reward.into() })
+                    .and_then(|reward| reward.checked_mul(&requirements.slots.into()))
+                    .and_then(|reward| {
+                        reward.checked_mul(&registration.schedule.execution_count().into())
+                    }),
+                Some(_) => Some(
+                    reserved
+                        .get(&(consumer.clone(), script.clone()))
+                        .cloned()
+                        .unwrap_or(0u8.into()),
+                ),
+            };
+            if !<StoredJobBudget<T>>::contains_key(&consumer, &script) {
+                let mut budget = requirements.reward.clone();
+                match budget_amount.map(|amount| budget.with_amount(amount.into()).is_ok()) {
+                    Some(true) => <StoredJobBudget<T>>::insert(&consumer, &script, budget),
+                    _ => failed += 1,
+                }
+            }
+
+            <pallet_acurast::StoredJobRegistration<T>>::insert(
+                &consumer,
+                &script,
+                JobRegistration {
+                    script: registration.script,
+                    allowed_sources: registration.allowed_sources,
+                    allow_only_verified_sources: registration.allow_only_verified_sources,
+                    schedule: registration.schedule,
+                    memory: registration.memory,
+                    network_requests: registration.network_requests,
+                    storage: registration.storage,
+                    extra,
+                },
+            );
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+        }
+
+        if failed > 0 {
+            log::warn!(
+                target: "runtime::acurast_marketplace",
+                "failed to migrate {} job registrations",
+                failed
+            );
+        }
+        weight
+    }
+}
+
+/// Adds the `stake_per_execution` to the stored [crate::PricingVariant]s and [crate::Assignment]s, which did not exist before version 2.
+///
+/// No stake was locked for assignments acknowledged before, so their stake is zero. The same applies to existing pricings until
+/// their sources advertise again.
+pub mod v2 {
+    use frame_support::{log, pallet_prelude::*};
+
+    use super::*;
+    use crate::{
+        Assignment, PricingVariant, Reward, RewardFor, SchedulingWindow,
+        StoredAdvertisementPricing, StoredMatches,
+    };
+
+    /// The pricing variant as stored before version 2.
+    #[derive(Encode, Decode)]
+    pub struct OldPricingVariant<AssetId, AssetAmount> {
+        pub reward_asset: AssetId,
+        pub fee_per_millisecond: AssetAmount,
+        pub fee_per_storage_byte: AssetAmount,
+        pub base_fee_per_execution: AssetAmount,
+        pub scheduling_window: SchedulingWindow,
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut weight = Weight::zero();
+
+        <StoredAdvertisementPricing<T>>::translate_values(
+            |old: OldPricingVariant<T::AssetId, T::AssetAmount>| {
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+                Some(PricingVariant {
+                    reward_asset: old.reward_asset,
+                    fee_per_millisecond: old.fee_per_millisecond,
+                    fee_per_storage_byte: old.fee_per_storage_byte,
+                    base_fee_per_execution: old.base_fee_per_execution,
+                    stake_per_execution: 0u8.into(),
+                    scheduling_window: old.scheduling_window,
+                })
+            },
+        );
+
+        let mut failed: u32 = 0;
+        <StoredMatches<T>>::translate_values(|old: v1::OldAssignment<RewardFor<T>>| {
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+            let mut stake = old.fee_per_execution.clone();
+            if stake.with_amount(T::AssetAmount::from(0u8).into()).is_err() {
+                failed += 1;
+                return None;
+            }
+            Some(Assignment {
+                slot: old.slot,
+                start_delay: old.start_delay,
+                fee_per_execution: old.fee_per_execution,
+                stake_per_execution: stake,
+                acknowledged: old.acknowledged,
+                sla: old.sla,
+            })
+        });
+
+        if failed > 0 {
+            log::warn!(
+                target: "runtime::acurast_marketplace",
+                "dropped {} matches with an unsupported reward",
+                failed
+            );
+        }
+        weight
+    }
+}
+
+/// Runs the migrations required to bring the storage of the pallet to its in-code version.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+
+    if on_chain_version < 1 {
+        weight = weight.saturating_add(v1::migrate::<T>());
+    }
+    if on_chain_version < 2 {
+        weight = weight.saturating_add(v2::migrate::<T>());
+    }
+
+    Pallet::<T>::current_storage_version().put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
@@ -98,6 +98,12 @@ impl ExtBuilder {
         .assimilate_storage(&mut t)
         .unwrap();
 
+        <pallet_acurast::GenesisConfig as GenesisBuild<Test>>::assimilate_storage(
+            &Default::default(),
+            &mut t,
+        )
+        .unwrap();
+
         let mut ext = sp_io::TestExternalities::new(t);
         ext.execute_with(|| System::set_block_number(1));
         ext
@@ -121,9 +127,9 @@ frame_support::construct_runtime!(
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Assets: pallet_assets::{Pallet, Config<T>, Event<T>, Storage},
         ParachainInfo: parachain_info::{Pallet, Storage, Config},
-        Acurast: pallet_acurast::{Pallet, Call, Storage, Event<T>},
+        Acurast: pallet_acurast::{Pallet, Call, Storage, Event<T>, Config},
         AcurastMarketplace: crate::{Pallet, Call, Storage, Event<T>},
-        MockPallet: mock_pallet::{Pallet, Event<T>}
+        MockPallet: mock_pallet::{Pallet, Storage, Event<T>}
     }
 );
 
@@ -141,6 +147,14 @@ parameter_types! {
     pub const MaxLocks: u32 = 50;
     pub const AcurastPalletId: PalletId = PalletId(*b"acrstpid");
     pub const ReportTolerance: u64 = 12000;
+    pub const MaxAutoMatchCandidates: u32 = 10;
+    pub const MinReputationHistory: u64 = 2;
+    pub const JobStatusRetention: BlockNumber = 100;
+    pub const AcknowledgementTimeout: BlockNumber = 10;
+    pub const ClawBackMatcherReward: bool = true;
+    pub const OutputDepositBase: AssetAmount = 1_000;
+    pub const OutputDepositPerByte: AssetAmount = 10;
+    pub const OutputRetention: BlockNumber = 100;
 }
 
 impl frame_system::Config for Test {
@@ -243,17 +257,36 @@ pub mod mock_pallet {
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
+    /// The balance of the marketplace pallet account as tracked by [super::MockRewardManager].
+    #[pallet::storage]
+    #[pallet::getter(fn pallet_balance)]
+    pub type PalletBalance<T> = StorageValue<_, u128, ValueQuery>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub (super) fn deposit_event)]
     pub enum Event<T: Config> {
         Locked(MockAsset),
         PayReward(MockAsset),
         PayMatcherReward(MockAsset),
+        ClawBackMatcherReward(MockAsset),
+        Refund(MockAsset),
+        LockStake(MockAsset),
+        ReleaseStake(MockAsset),
     }
 }
 
 pub struct MockRewardManager {}
 
+impl MockRewardManager {
+    /// Withdraws from the tracked balance of the marketplace pallet account.
+    fn withdraw<T: Config + mock_pallet::Config>(amount: u128) -> Result<(), DispatchError> {
+        mock_pallet::PalletBalance::<T>::try_mutate(|balance| -> Result<(), DispatchError> {
+            *balance = balance.checked_sub(amount).ok_or(Error::<T>::FailedToPay)?;
+            Ok(())
+        })
+    }
+}
+
 impl<T: Config + mock_pallet::Config> RewardManager<T> for MockRewardManager {
     type Reward = MockAsset;
 
@@ -261,6 +294,7 @@ impl<T: Config + mock_pallet::Config> RewardManager<T> for MockRewardManager {
         reward: Self::Reward,
         _owner: <<T>::Lookup as StaticLookup>::Source,
     ) -> Result<(), DispatchError> {
+        mock_pallet::PalletBalance::<T>::mutate(|balance| *balance += reward.amount);
         mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::Locked(reward));
         Ok(())
     }
@@ -269,15 +303,70 @@ impl<T: Config + mock_pallet::Config> RewardManager<T> for MockRewardManager {
         reward: Self::Reward,
         _target: <<T>::Lookup as StaticLookup>::Source,
     ) -> Result<(), DispatchError> {
+        Self::withdraw::<T>(reward.amount)?;
         mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::PayReward(reward));
         Ok(())
     }
 
     fn pay_matcher_reward(
+        remaining_rewards: Vec<Self::Reward>,
+        _matcher: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<Vec<Self::Reward>, DispatchError> {
+        let matcher_rewards: Vec<Self::Reward> = remaining_rewards
+            .iter()
+            .map(|reward| MockAsset {
+                id: reward.id,
+                amount: FeeManagerImpl::get_matcher_percentage().mul_floor(reward.amount),
+            })
+            .collect();
+        Self::withdraw::<T>(matcher_rewards.iter().map(|reward| reward.amount).sum())?;
+
+        if let Some(first) = remaining_rewards.first() {
+            mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::PayMatcherReward(
+                MockAsset {
+                    id: first.id,
+                    amount: remaining_rewards.iter().map(|reward| reward.amount).sum(),
+                },
+            ));
+        }
+        Ok(matcher_rewards)
+    }
+
+    fn claw_back_matcher_reward(
         reward: Self::Reward,
         _matcher: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<Self::Reward, DispatchError> {
+        mock_pallet::PalletBalance::<T>::mutate(|balance| *balance += reward.amount);
+        mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::ClawBackMatcherReward(
+            reward.clone(),
+        ));
+        Ok(reward)
+    }
+
+    fn refund_reward(
+        reward: Self::Reward,
+        _owner: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        Self::withdraw::<T>(reward.amount)?;
+        mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::Refund(reward));
+        Ok(())
+    }
+
+    fn lock_stake(
+        stake: Self::Reward,
+        _source: <<T>::Lookup as StaticLookup>::Source,
     ) -> Result<(), DispatchError> {
-        mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::PayMatcherReward(reward));
+        mock_pallet::PalletBalance::<T>::mutate(|balance| *balance += stake.amount);
+        mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::LockStake(stake));
+        Ok(())
+    }
+
+    fn release_stake(
+        stake: Self::Reward,
+        _target: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        Self::withdraw::<T>(stake.amount)?;
+        mock_pallet::Pallet::deposit_event(mock_pallet::Event::<T>::ReleaseStake(stake));
         Ok(())
     }
 }
@@ -291,6 +380,15 @@ impl Config for Test {
     type AssetAmount = AssetAmount;
     type RewardManager = MockRewardManager;
     type AssetValidator = PassAllAssets;
+    type MinReputationHistory = MinReputationHistory;
+    type MaxAutoMatchCandidates = MaxAutoMatchCandidates;
+    type JobStatusRetention = JobStatusRetention;
+    type AcknowledgementTimeout = AcknowledgementTimeout;
+    type ClawBackMatcherReward = ClawBackMatcherReward;
+    type Currency = Balances;
+    type OutputDepositBase = OutputDepositBase;
+    type OutputDepositPerByte = OutputDepositPerByte;
+    type OutputRetention = OutputRetention;
     type WeightInfo = weights::Weights<Test>;
 }
 
@@ -326,6 +424,7 @@ pub fn advertisement(
             fee_per_millisecond,
             fee_per_storage_byte,
             base_fee_per_execution: 0,
+            stake_per_execution: 0,
             scheduling_window: SchedulingWindow::Delta(2_628_000_000), // 1 month
         }];
     Advertisement {
@@ -4,12 +4,13 @@ use frame_support::{
     dispatch::RawOrigin,
     pallet_prelude::Member,
     sp_runtime::{
-        traits::{AccountIdConversion, Get, StaticLookup},
+        traits::{AccountIdConversion, CheckedAdd, Get, StaticLookup, Zero},
         DispatchError, Percent,
     },
     Never, PalletId, Parameter,
 };
-use xcm::prelude::AssetId;
+use sp_std::prelude::*;
+use xcm::v3::AssetId;
 
 /// Asset barrier that allows to customize which asset can be used as reward.
 pub trait AssetBarrier<Asset> {
@@ -68,9 +69,33 @@ pub trait RewardManager<T: frame_system::Config> {
         reward: Self::Reward,
         target: <T::Lookup as StaticLookup>::Source,
     ) -> Result<(), DispatchError>;
+    /// Pays the matcher its share of the remaining rewards and returns the share taken from each of them, in the same order.
     fn pay_matcher_reward(
+        remaining_rewards: Vec<Self::Reward>,
+        matcher: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<Vec<Self::Reward>, DispatchError>;
+    /// Reclaims (part of) a reward paid with [Self::pay_matcher_reward] from the matcher and holds it in the pallet account again.
+    ///
+    /// The full reward is reclaimed, including the fees deducted when it was paid, so the matcher bears these fees.
+    /// Returns the reward actually reclaimed.
+    fn claw_back_matcher_reward(
         reward: Self::Reward,
         matcher: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<Self::Reward, DispatchError>;
+    /// Refunds the unspent part of a locked reward to its owner.
+    fn refund_reward(
+        reward: Self::Reward,
+        owner: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError>;
+    /// Locks the stake of a source for the duration of an assignment.
+    fn lock_stake(
+        stake: Self::Reward,
+        source: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError>;
+    /// Releases (part of) a locked stake to the target, which is either the source itself or the consumer if the stake got slashed.
+    fn release_stake(
+        stake: Self::Reward,
+        target: <T::Lookup as StaticLookup>::Source,
     ) -> Result<(), DispatchError>;
 }
 
@@ -92,8 +117,36 @@ impl<T: frame_system::Config> RewardManager<T> for () {
     }
 
     fn pay_matcher_reward(
-        _reward: Self::Reward,
+        _remaining_rewards: Vec<Self::Reward>,
         _matcher: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<Vec<Self::Reward>, DispatchError> {
+        Ok(Vec::new())
+    }
+
+    fn claw_back_matcher_reward(
+        reward: Self::Reward,
+        _matcher: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<Self::Reward, DispatchError> {
+        Ok(reward)
+    }
+
+    fn refund_reward(
+        _reward: Self::Reward,
+        _owner: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    fn lock_stake(
+        _stake: Self::Reward,
+        _source: <<T>::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    fn release_stake(
+        _stake: Self::Reward,
+        _target: <<T>::Lookup as StaticLookup>::Source,
     ) -> Result<(), DispatchError> {
         Ok(())
     }
@@ -174,7 +227,7 @@ where
         let fee_pallet_account: T::AccountId = AssetSplit::pallet_id().into_account_truncating();
         pallet_acurast_assets::Pallet::<T>::transfer(
             pallet_origin.clone(),
-            id.clone().into(),
+            id.into(),
             T::Lookup::unlookup(fee_pallet_account),
             fee,
         )?;
@@ -189,17 +242,79 @@ where
     }
 
     fn pay_matcher_reward(
-        remaining_reward: Self::Reward,
+        remaining_rewards: Vec<Self::Reward>,
         matcher: <T::Lookup as StaticLookup>::Source,
-    ) -> Result<(), DispatchError> {
+    ) -> Result<Vec<Self::Reward>, DispatchError> {
         let matcher_fee_percentage = AssetSplit::get_matcher_percentage(); // TODO: fee will be indexed by version in the future
-        let amount = remaining_reward
-            .try_get_amount()
-            .map_err(|_| Error::<T>::InvalidAssetAmount)?;
-        let mut r = remaining_reward.clone();
-        r.with_amount(matcher_fee_percentage.mul_floor(amount))
-            .map_err(|_| Error::<T>::InvalidAssetAmount)?;
-
-        <Self as RewardManager<T>>::pay_reward(r, matcher)
+
+        let mut matcher_rewards: Vec<Self::Reward> = Vec::with_capacity(remaining_rewards.len());
+        let mut total_amount: <T as pallet_assets::Config>::Balance = Zero::zero();
+        for remaining_reward in remaining_rewards {
+            let amount = remaining_reward
+                .try_get_amount()
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?;
+            let matcher_amount = matcher_fee_percentage.mul_floor(amount);
+            total_amount = total_amount
+                .checked_add(&matcher_amount)
+                .ok_or(Error::<T>::CalculationOverflow)?;
+
+            let mut r = remaining_reward.clone();
+            r.with_amount(matcher_amount)
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?;
+            matcher_rewards.push(r);
+        }
+
+        // the matcher's share of all remaining rewards is payed out at once
+        if let Some(mut total) = matcher_rewards.first().cloned() {
+            total
+                .with_amount(total_amount)
+                .map_err(|_| Error::<T>::InvalidAssetAmount)?;
+            <Self as RewardManager<T>>::pay_reward(total, matcher)?;
+        }
+
+        Ok(matcher_rewards)
+    }
+
+    fn claw_back_matcher_reward(
+        reward: Self::Reward,
+        matcher: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<Self::Reward, DispatchError> {
+        // the fee deducted when the reward was paid already went to the fee manager account,
+        // the matcher is charged for it so that the full reward is restored to the consumer's budget
+        <Self as RewardManager<T>>::lock_reward(reward.clone(), matcher)?;
+        Ok(reward)
+    }
+
+    fn refund_reward(
+        reward: Self::Reward,
+        owner: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        let pallet_account: T::AccountId = <T as Config>::PalletId::get().into_account_truncating();
+        let raw_origin = RawOrigin::<T::AccountId>::Signed(pallet_account);
+        let pallet_origin: T::RuntimeOrigin = raw_origin.into();
+        let (id, amount) = match (reward.try_get_asset_id(), reward.try_get_amount()) {
+            (Ok(id), Ok(amount)) => (id, amount),
+            (Err(_err), _) => Err(Error::<T>::InvalidAssetId)?,
+            (_, Err(_err)) => Err(Error::<T>::InvalidAssetAmount)?,
+        };
+
+        // no fees are deducted since the refunded part of the reward was never spent
+        pallet_acurast_assets::Pallet::<T>::transfer(pallet_origin, id.into(), owner, amount)
+    }
+
+    fn lock_stake(
+        stake: Self::Reward,
+        source: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        // the stake is held in the pallet account just like a locked reward
+        <Self as RewardManager<T>>::lock_reward(stake, source)
+    }
+
+    fn release_stake(
+        stake: Self::Reward,
+        target: <T::Lookup as StaticLookup>::Source,
+    ) -> Result<(), DispatchError> {
+        // no fees are deducted from stakes, no matter if released to the source or slashed to the consumer
+        <Self as RewardManager<T>>::refund_reward(stake, target)
     }
 }
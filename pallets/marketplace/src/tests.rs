@@ -1,13 +1,23 @@
 #![cfg(test)]
 
-use frame_support::{assert_err, assert_ok, traits::Hooks};
+use frame_support::{
+    assert_err, assert_ok,
+    traits::{GetStorageVersion, Hooks, ReservableCurrency, StorageVersion},
+    weights::Weight,
+};
+use hex_literal::hex;
 
-use pallet_acurast::JobRegistrationFor;
 use pallet_acurast::Schedule;
+use pallet_acurast::{CertificateRevocationListUpdate, ListUpdateOperation};
+use pallet_acurast::{JobRegistration, JobRegistrationFor, Script};
+
+use sp_runtime::Permill;
 
 use crate::stub::*;
 use crate::{
-    mock::*, AdvertisementRestriction, Assignment, Error, ExecutionResult, JobStatus, Match, SLA,
+    migrations, mock::*, AdvertisementRestriction, Assignment, Error, ExecutionOutput,
+    ExecutionOutputRecord, ExecutionResult, JobStatus, Match, PendingMatch, PricingVariant,
+    Reputation, SchedulingWindow, SLA,
 };
 use crate::{JobRequirements, PlannedExecution};
 
@@ -33,8 +43,12 @@ fn test_match() {
         storage: 20_000u32,
         extra: JobRequirements {
             slots: 1,
+            min_slots: None,
             reward: asset(3_000_000 * 2),
+            slot_rewards: None,
             instant_match: None,
+            min_reputation: None,
+            max_output_size: None,
         },
     };
     let job_id = (alice_account_id(), registration.script.clone());
@@ -134,6 +148,7 @@ fn test_match() {
                     id: 0,
                     amount: 5_020_000
                 },
+                stake_per_execution: MockAsset { id: 0, amount: 0 },
                 acknowledged: true,
                 sla: SLA { total: 2, met: 1 },
             }),
@@ -163,15 +178,21 @@ fn test_match() {
             None,
             AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone()),
         );
-        // Job no longer assigned after last execution
+        // Job completed after last execution
         assert_eq!(
-            None,
+            Some(JobStatus::Completed),
             AcurastMarketplace::stored_job_status(alice_account_id(), script()),
         );
         assert_eq!(
             Some(100_000),
             AcurastMarketplace::stored_storage_capacity(processor_account_id())
         );
+        // unspent part of the reward got refunded
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        assert_eq!(0, MockPallet::pallet_balance());
 
         assert_eq!(
             events(),
@@ -188,11 +209,21 @@ fn test_match() {
                     registration.clone(),
                     alice_account_id()
                 )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: m.job_id.clone(),
+                    from: JobStatus::Open,
+                    to: JobStatus::Matched,
+                }),
                 RuntimeEvent::AcurastMarketplace(crate::Event::JobRegistrationMatched(m)),
                 RuntimeEvent::MockPallet(mock_pallet::Event::PayMatcherReward(MockAsset {
                     id: 0,
                     amount: 1_960_000 // this is before splitting of the configured percentage that actually is transfered to the matcher
                 })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Matched,
+                    to: JobStatus::Assigned(1),
+                }),
                 RuntimeEvent::AcurastMarketplace(crate::Event::JobRegistrationAssigned(
                     job_id.clone(),
                     processor_account_id(),
@@ -203,6 +234,7 @@ fn test_match() {
                             id: 0,
                             amount: 5_020_000
                         },
+                        stake_per_execution: MockAsset { id: 0, amount: 0 },
                         acknowledged: true,
                         sla: SLA { total: 2, met: 0 },
                     }
@@ -225,14 +257,24 @@ fn test_match() {
                             id: 0,
                             amount: 5_020_000
                         },
+                        stake_per_execution: MockAsset { id: 0, amount: 0 },
                         acknowledged: true,
                         sla: SLA { total: 2, met: 1 },
                     }
                 )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Assigned(1),
+                    to: JobStatus::Completed,
+                }),
                 RuntimeEvent::MockPallet(mock_pallet::Event::PayReward(MockAsset {
                     id: 0,
                     amount: 5_020_000
                 })),
+                RuntimeEvent::MockPallet(mock_pallet::Event::Refund(MockAsset {
+                    id: 0,
+                    amount: 1_764_000 // 12_000_000 - 2 * 5_020_000 - 10% * 1_960_000
+                })),
                 RuntimeEvent::AcurastMarketplace(crate::Event::ExecutionSuccess(
                     job_id.clone(),
                     operation_hash()
@@ -247,6 +289,7 @@ fn test_match() {
                             id: 0,
                             amount: 5_020_000
                         },
+                        stake_per_execution: MockAsset { id: 0, amount: 0 },
                         acknowledged: true,
                         sla: SLA { total: 2, met: 2 },
                     }
@@ -278,8 +321,12 @@ fn test_no_match_schedule_overlap() {
         storage: 20_000u32,
         extra: JobRequirements {
             slots: 1,
+            min_slots: None,
             reward: asset(3_000_000 * 2),
+            slot_rewards: None,
             instant_match: None,
+            min_reputation: None,
+            max_output_size: None,
         },
     };
     let job_id1 = (alice_account_id(), registration1.script.clone());
@@ -300,8 +347,12 @@ fn test_no_match_schedule_overlap() {
         storage: 20_000u32,
         extra: JobRequirements {
             slots: 1,
+            min_slots: None,
             reward: asset(3_000_000 * 2),
+            slot_rewards: None,
             instant_match: None,
+            min_reputation: None,
+            max_output_size: None,
         },
     };
     let _job_id2 = (alice_account_id(), registration2.script.clone());
@@ -386,6 +437,11 @@ fn test_no_match_schedule_overlap() {
                     registration2.clone(),
                     alice_account_id()
                 )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: m.job_id.clone(),
+                    from: JobStatus::Open,
+                    to: JobStatus::Matched,
+                }),
                 RuntimeEvent::AcurastMarketplace(crate::Event::JobRegistrationMatched(m)),
                 RuntimeEvent::MockPallet(mock_pallet::Event::PayMatcherReward(MockAsset {
                     id: 0,
@@ -419,8 +475,12 @@ fn test_more_reports_than_expected() {
         storage: 20_000u32,
         extra: JobRequirements {
             slots: 1,
+            min_slots: None,
             reward: asset(3_000_000 * 2),
+            slot_rewards: None,
             instant_match: None,
+            min_reputation: None,
+            max_output_size: None,
         },
     };
     let job_id = (alice_account_id(), registration.script.clone());
@@ -512,11 +572,21 @@ fn test_more_reports_than_expected() {
                     registration.clone(),
                     alice_account_id()
                 )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: m.job_id.clone(),
+                    from: JobStatus::Open,
+                    to: JobStatus::Matched,
+                }),
                 RuntimeEvent::AcurastMarketplace(crate::Event::JobRegistrationMatched(m)),
                 RuntimeEvent::MockPallet(mock_pallet::Event::PayMatcherReward(MockAsset {
                     id: 0,
                     amount: 1_960_000 // this is before splitting of the configured percentage that actually is transfered to the matcher
                 })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Matched,
+                    to: JobStatus::Assigned(1),
+                }),
                 RuntimeEvent::AcurastMarketplace(crate::Event::JobRegistrationAssigned(
                     job_id.clone(),
                     processor_account_id(),
@@ -527,6 +597,7 @@ fn test_more_reports_than_expected() {
                             id: 0,
                             amount: 5_020_000
                         },
+                        stake_per_execution: MockAsset { id: 0, amount: 0 },
                         acknowledged: true,
                         sla: SLA { total: 2, met: 0 },
                     }
@@ -549,6 +620,7 @@ fn test_more_reports_than_expected() {
                             id: 0,
                             amount: 5_020_000
                         },
+                        stake_per_execution: MockAsset { id: 0, amount: 0 },
                         acknowledged: true,
                         sla: SLA { total: 2, met: 1 },
                     }
@@ -571,6 +643,7 @@ fn test_more_reports_than_expected() {
                             id: 0,
                             amount: 5_020_000
                         },
+                        stake_per_execution: MockAsset { id: 0, amount: 0 },
                         acknowledged: true,
                         sla: SLA { total: 2, met: 2 },
                     }
@@ -580,6 +653,1749 @@ fn test_more_reports_than_expected() {
     });
 }
 
+#[test]
+fn test_deregister_open_job_refunds_reward() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let registration = job_registration();
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_eq!(
+            Some(MockAsset {
+                id: 0,
+                amount: 12_000_000
+            }),
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        assert_eq!(12_000_000, MockPallet::pallet_balance());
+
+        assert_ok!(Acurast::deregister(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.script.clone(),
+        ));
+        assert_eq!(
+            Some(JobStatus::Cancelled),
+            AcurastMarketplace::stored_job_status(alice_account_id(), script())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        assert_eq!(0, MockPallet::pallet_balance());
+
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::MockPallet(mock_pallet::Event::Locked(MockAsset {
+                    id: 0,
+                    amount: 12_000_000
+                })),
+                RuntimeEvent::Acurast(pallet_acurast::Event::JobRegistrationStored(
+                    registration.clone(),
+                    alice_account_id()
+                )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: (alice_account_id(), registration.script.clone()),
+                    from: JobStatus::Open,
+                    to: JobStatus::Cancelled,
+                }),
+                RuntimeEvent::MockPallet(mock_pallet::Event::Refund(MockAsset {
+                    id: 0,
+                    amount: 12_000_000
+                })),
+                RuntimeEvent::Acurast(pallet_acurast::Event::JobRegistrationRemoved(
+                    registration.script.clone(),
+                    alice_account_id()
+                )),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_deregister_job_mid_schedule() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let mut registration = job_registration();
+    registration.extra.slots = 2;
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![
+                    PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    },
+                    PlannedExecution {
+                        source: bob_account_id(),
+                        start_delay: 0,
+                    },
+                ],
+            }],
+        ));
+        // only the processor acknowledges
+        assert_ok!(AcurastMarketplace::acknowledge_match(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+        ));
+
+        // deregistration is prohibited before the job is overdue
+        assert_err!(
+            Acurast::deregister(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                registration.script.clone(),
+            ),
+            Error::<Test>::JobRegistrationUnmodifiable
+        );
+
+        // deregister during the first of two executions
+        later(registration.schedule.start_time + 3000);
+        let _ = events();
+        assert_ok!(Acurast::deregister(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.script.clone(),
+        ));
+
+        // the matched sources are released
+        for source in [processor_account_id(), bob_account_id()] {
+            assert_eq!(
+                None,
+                AcurastMarketplace::stored_matches(source.clone(), job_id.clone())
+            );
+            assert_eq!(
+                Some(100_000),
+                AcurastMarketplace::stored_storage_capacity(source)
+            );
+        }
+        assert_eq!(0, MockPallet::pallet_balance());
+
+        // the acknowledged source is paid for the execution in progress, the rest of the unpaid budget is refunded
+        let payments: Vec<RuntimeEvent> = events()
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    RuntimeEvent::MockPallet(
+                        mock_pallet::Event::PayReward(_) | mock_pallet::Event::Refund(_)
+                    )
+                )
+            })
+            .collect();
+        assert_eq!(
+            payments,
+            [
+                RuntimeEvent::MockPallet(mock_pallet::Event::PayReward(MockAsset {
+                    id: 0,
+                    amount: 5_020_000
+                })),
+                RuntimeEvent::MockPallet(mock_pallet::Event::Refund(MockAsset {
+                    id: 0,
+                    amount: 18_588_000 // 24_000_000 - 10% * (24_000_000 - 4 * 5_020_000) - 5_020_000
+                }))
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_finalize_job_with_missing_reports() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![PlannedExecution {
+                    source: processor_account_id(),
+                    start_delay: 0,
+                }],
+            }],
+        ));
+        assert_ok!(AcurastMarketplace::acknowledge_match(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+        ));
+
+        // only the first of two executions gets reported
+        later(registration.schedule.start_time + 3000);
+        assert_ok!(AcurastMarketplace::report(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+            false,
+            ExecutionResult::Success(operation_hash())
+        ));
+        assert_err!(
+            AcurastMarketplace::finalize_job(
+                RuntimeOrigin::signed(charlie_account_id()).into(),
+                job_id.clone(),
+            ),
+            Error::<Test>::CannotFinalizeJobBeforeScheduleEnd
+        );
+
+        later(
+            registration
+                .schedule
+                .range(registration.schedule.max_start_delay)
+                .unwrap()
+                .1,
+        );
+        assert_ok!(AcurastMarketplace::finalize_job(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            job_id.clone(),
+        ));
+
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone())
+        );
+        assert_eq!(
+            Some(JobStatus::PartiallyFulfilled),
+            AcurastMarketplace::stored_job_status(alice_account_id(), script())
+        );
+        assert_eq!(
+            Some(100_000),
+            AcurastMarketplace::stored_storage_capacity(processor_account_id())
+        );
+        assert_eq!(0, MockPallet::pallet_balance());
+
+        let events = events();
+        assert_eq!(
+            &events[events.len() - 3..],
+            [
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Assigned(1),
+                    to: JobStatus::PartiallyFulfilled,
+                }),
+                RuntimeEvent::MockPallet(mock_pallet::Event::Refund(MockAsset {
+                    id: 0,
+                    amount: 6_784_000 // 12_000_000 - 5_020_000 - 10% * 1_960_000
+                })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobFinalized(job_id.clone())),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_slash_stake_for_missed_executions() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let mut ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    ad.pricing
+        .iter_mut()
+        .for_each(|pricing| pricing.stake_per_execution = 1_000_000);
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![PlannedExecution {
+                    source: processor_account_id(),
+                    start_delay: 0,
+                }],
+            }],
+        ));
+        assert_ok!(AcurastMarketplace::acknowledge_match(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+        ));
+        // stake for both executions got locked
+        assert_eq!(
+            Some(RuntimeEvent::MockPallet(mock_pallet::Event::LockStake(
+                MockAsset {
+                    id: 0,
+                    amount: 2_000_000
+                }
+            ))),
+            events().into_iter().find(|e| matches!(
+                e,
+                RuntimeEvent::MockPallet(mock_pallet::Event::LockStake(_))
+            ))
+        );
+
+        // only the first of two executions gets reported
+        later(registration.schedule.start_time + 3000);
+        assert_ok!(AcurastMarketplace::report(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+            false,
+            ExecutionResult::Success(operation_hash())
+        ));
+
+        later(
+            registration
+                .schedule
+                .range(registration.schedule.max_start_delay)
+                .unwrap()
+                .1,
+        );
+        events();
+        assert_ok!(AcurastMarketplace::finalize_job(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            job_id.clone(),
+        ));
+        assert_eq!(0, MockPallet::pallet_balance());
+
+        assert_eq!(
+            events(),
+            [
+                // stake for the missed execution goes to the consumer
+                RuntimeEvent::MockPallet(mock_pallet::Event::ReleaseStake(MockAsset {
+                    id: 0,
+                    amount: 1_000_000
+                })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::StakeSlashed(
+                    job_id.clone(),
+                    processor_account_id(),
+                    MockAsset {
+                        id: 0,
+                        amount: 1_000_000
+                    }
+                )),
+                // stake for the met execution goes back to the source
+                RuntimeEvent::MockPallet(mock_pallet::Event::ReleaseStake(MockAsset {
+                    id: 0,
+                    amount: 1_000_000
+                })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Assigned(1),
+                    to: JobStatus::PartiallyFulfilled,
+                }),
+                RuntimeEvent::MockPallet(mock_pallet::Event::Refund(MockAsset {
+                    id: 0,
+                    amount: 6_784_000 // 12_000_000 - 5_020_000 - 10% * 1_960_000
+                })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobFinalized(job_id.clone())),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_reputation() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        // sources without history have a full score
+        assert_eq!(
+            Permill::one(),
+            AcurastMarketplace::reputation_score(&processor_account_id())
+        );
+
+        // reputation is only tracked for attested sources
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain(),
+        ));
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![PlannedExecution {
+                    source: processor_account_id(),
+                    start_delay: 0,
+                }],
+            }],
+        ));
+        assert_ok!(AcurastMarketplace::acknowledge_match(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+        ));
+
+        // first execution is reported within schedule
+        later(registration.schedule.start_time + 3000);
+        assert_ok!(AcurastMarketplace::report(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+            false,
+            ExecutionResult::Success(operation_hash())
+        ));
+        assert_eq!(
+            Reputation {
+                met: 1,
+                missed: 0,
+                jobs_completed: 0,
+                late_reports: 0,
+            },
+            AcurastMarketplace::stored_reputation(processor_account_id())
+        );
+
+        // second execution is reported after it ended
+        later(registration.schedule.start_time + registration.schedule.interval + 10_000);
+        events();
+        assert_ok!(AcurastMarketplace::report(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+            false,
+            ExecutionResult::Success(operation_hash())
+        ));
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::AcurastMarketplace(crate::Event::ReputationUpdated(
+                    processor_account_id(),
+                    Reputation {
+                        met: 1,
+                        missed: 0,
+                        jobs_completed: 0,
+                        late_reports: 1,
+                    }
+                )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::ReportedLate(
+                    job_id.clone(),
+                    processor_account_id()
+                )),
+            ]
+        );
+        // late report is not rewarded
+        assert_eq!(
+            Some(SLA { total: 2, met: 1 }),
+            AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone())
+                .map(|assignment| assignment.sla)
+        );
+
+        later(
+            registration
+                .schedule
+                .range(registration.schedule.max_start_delay)
+                .unwrap()
+                .1,
+        );
+        assert_ok!(AcurastMarketplace::finalize_job(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            job_id.clone(),
+        ));
+        assert_eq!(
+            Reputation {
+                met: 1,
+                missed: 1,
+                jobs_completed: 0,
+                late_reports: 1,
+            },
+            AcurastMarketplace::stored_reputation(processor_account_id())
+        );
+        assert_eq!(
+            Permill::from_percent(50),
+            AcurastMarketplace::reputation_score(&processor_account_id())
+        );
+
+        // a job requiring a higher reputation cannot be matched with the source
+        let mut registration2 = job_registration();
+        registration2.script = script_random_value();
+        registration2.schedule.start_time += 86_400_000;
+        registration2.schedule.end_time += 86_400_000;
+        registration2.extra.min_reputation = Some(Permill::from_percent(60));
+        let job_id2 = (alice_account_id(), registration2.script.clone());
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration2.clone(),
+        ));
+        assert_err!(
+            AcurastMarketplace::propose_matching(
+                RuntimeOrigin::signed(charlie_account_id()).into(),
+                vec![Match {
+                    job_id: job_id2.clone(),
+                    sources: vec![PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    }],
+                }],
+            ),
+            Error::<Test>::InsufficientReputationInMatch
+        );
+
+        // sources without attestation or enough history are not accepted by jobs requiring a minimum reputation
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_eq!(
+            Permill::one(),
+            AcurastMarketplace::reputation_score(&bob_account_id())
+        );
+        // registering the open job again with a lower minimum reputation
+        let mut registration3 = job_registration();
+        registration3.script = script_random_value();
+        registration3.schedule.start_time += 2 * 86_400_000;
+        registration3.schedule.end_time += 2 * 86_400_000;
+        registration3.extra.min_reputation = Some(Permill::from_percent(50));
+        let job_id3 = (alice_account_id(), registration3.script.clone());
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration3.clone(),
+        ));
+        assert_err!(
+            AcurastMarketplace::propose_matching(
+                RuntimeOrigin::signed(charlie_account_id()).into(),
+                vec![Match {
+                    job_id: job_id3.clone(),
+                    sources: vec![PlannedExecution {
+                        source: bob_account_id(),
+                        start_delay: 0,
+                    }],
+                }],
+            ),
+            Error::<Test>::InsufficientReputationInMatch
+        );
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id3.clone(),
+                sources: vec![PlannedExecution {
+                    source: processor_account_id(),
+                    start_delay: 0,
+                }],
+            }],
+        ));
+    });
+}
+
+#[test]
+fn test_auto_match() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+
+        // no weight left for matching
+        assert_eq!(
+            Weight::zero(),
+            AcurastMarketplace::auto_match(Weight::zero())
+        );
+        assert_eq!(
+            Some(JobStatus::Open),
+            AcurastMarketplace::stored_job_status(&job_id.0, &job_id.1)
+        );
+
+        events();
+        assert_ne!(Weight::zero(), AcurastMarketplace::auto_match(Weight::MAX));
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Open,
+                    to: JobStatus::Matched,
+                }),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobRegistrationMatched(Match {
+                    job_id: job_id.clone(),
+                    sources: vec![PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    }],
+                })),
+            ]
+        );
+        assert_eq!(
+            Some(JobStatus::Matched),
+            AcurastMarketplace::stored_job_status(&job_id.0, &job_id.1)
+        );
+        assert_eq!(
+            Some(Assignment {
+                slot: 0,
+                start_delay: 0,
+                fee_per_execution: MockAsset {
+                    id: 0,
+                    amount: 5_020_000
+                },
+                stake_per_execution: MockAsset { id: 0, amount: 0 },
+                acknowledged: false,
+                sla: SLA { total: 2, met: 0 },
+            }),
+            AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone()),
+        );
+        // without a matcher to reward, the remaining reward stays in the budget
+        assert_eq!(
+            Some(asset(12_000_000)),
+            AcurastMarketplace::stored_job_budget(&job_id.0, &job_id.1)
+        );
+        // all jobs visited, next run starts over
+        assert_eq!(None, AcurastMarketplace::stored_auto_match_cursor());
+
+        assert_ok!(AcurastMarketplace::acknowledge_match(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+        ));
+    });
+}
+
+#[test]
+fn test_candidate_sources() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let registration = job_registration();
+    let mut ad_other_asset = advertisement(1000, 1, 100_000, 50_000, 8);
+    ad_other_asset
+        .pricing
+        .iter_mut()
+        .for_each(|pricing| pricing.reward_asset = 1);
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            advertisement(1000, 1, 100_000, 50_000, 8),
+        ));
+        // memory in a lower bucket than required
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            advertisement(1000, 1, 100_000, 4_000, 8),
+        ));
+        // memory in the bucket required but less than required
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(dave_account_id()).into(),
+            advertisement(1000, 1, 100_000, 4_500, 8),
+        ));
+        // not enough storage capacity
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            advertisement(1000, 1, 10_000, 50_000, 8),
+        ));
+        // reward asset not accepted
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(eve_account_id()).into(),
+            ad_other_asset,
+        ));
+
+        assert_eq!(
+            Some(()),
+            AcurastMarketplace::stored_sources_by_asset(0, processor_account_id())
+        );
+        assert_eq!(
+            Some(()),
+            AcurastMarketplace::stored_sources_by_memory_bucket(16, processor_account_id())
+        );
+        assert_eq!(
+            Some(()),
+            AcurastMarketplace::stored_sources_by_storage_bucket(17, processor_account_id())
+        );
+        assert_eq!(
+            Ok(vec![processor_account_id()]),
+            AcurastMarketplace::candidate_sources(&registration)
+        );
+
+        // advertising more memory moves the source to another bucket
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            advertisement(1000, 1, 100_000, 10_000, 8),
+        ));
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_sources_by_memory_bucket(12, bob_account_id())
+        );
+        assert_eq!(
+            Some(()),
+            AcurastMarketplace::stored_sources_by_memory_bucket(14, bob_account_id())
+        );
+        let mut candidates = AcurastMarketplace::candidate_sources(&registration).unwrap();
+        candidates.sort();
+        let mut expected = vec![processor_account_id(), bob_account_id()];
+        expected.sort();
+        assert_eq!(expected, candidates);
+
+        assert_ok!(AcurastMarketplace::delete_advertisement(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+        ));
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_sources_by_asset(0, bob_account_id())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_sources_by_memory_bucket(14, bob_account_id())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_sources_by_storage_bucket(17, bob_account_id())
+        );
+        assert_eq!(
+            Ok(vec![processor_account_id()]),
+            AcurastMarketplace::candidate_sources(&registration)
+        );
+    });
+}
+
+#[test]
+fn test_quotes_and_dry_run_match() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_eq!(
+            Ok(5_020_000), // 1000 * 5000 + 1 * 20_000
+            AcurastMarketplace::quote_fee_per_execution(&registration, &processor_account_id())
+        );
+        assert_eq!(
+            Err(Error::<Test>::AdvertisementPricingNotFound.into()),
+            AcurastMarketplace::quote_fee_per_execution(&registration, &bob_account_id())
+        );
+        assert_eq!(
+            12_000_000,
+            AcurastMarketplace::total_reward_amount(&registration).unwrap()
+        );
+
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        events();
+
+        let m = Match {
+            job_id: job_id.clone(),
+            sources: vec![PlannedExecution {
+                source: processor_account_id(),
+                start_delay: 0,
+            }],
+        };
+        assert_eq!(
+            Ok(asset(1_960_000)), // 12_000_000 - 2 * 5_020_000
+            AcurastMarketplace::dry_run_match(&m)
+        );
+        assert_eq!(
+            Err(Error::<Test>::IncorrectSourceCountInMatch.into()),
+            AcurastMarketplace::dry_run_match(&Match {
+                job_id: job_id.clone(),
+                sources: vec![],
+            })
+        );
+
+        // storage is left untouched
+        assert_eq!(
+            Some(JobStatus::Open),
+            AcurastMarketplace::stored_job_status(&job_id.0, &job_id.1)
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone())
+        );
+        assert_eq!(
+            Some(100_000),
+            AcurastMarketplace::stored_storage_capacity(processor_account_id())
+        );
+        assert!(events().is_empty());
+
+        // the job can still be matched
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![m],
+        ));
+    });
+}
+
+fn job_registration() -> JobRegistrationFor<Test> {
+    JobRegistrationFor::<Test> {
+        script: script(),
+        allowed_sources: None,
+        allow_only_verified_sources: false,
+        schedule: Schedule {
+            duration: 5000,
+            start_time: 1_671_800_400_000, // 23.12.2022 13:00
+            end_time: 1_671_804_000_000,   // 23.12.2022 14:00 (one hour later)
+            interval: 1_800_000,           // 30min
+            max_start_delay: 5000,
+        },
+        memory: 5_000u32,
+        network_requests: 5,
+        storage: 20_000u32,
+        extra: JobRequirements {
+            slots: 1,
+            min_slots: None,
+            reward: asset(3_000_000 * 2),
+            slot_rewards: None,
+            instant_match: None,
+            min_reputation: None,
+            max_output_size: None,
+        },
+    }
+}
+
+#[test]
+fn test_job_status_retention() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(Acurast::deregister(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.script.clone(),
+        ));
+        assert_eq!(
+            Some(JobStatus::Cancelled),
+            AcurastMarketplace::stored_job_status(&job_id.0, &job_id.1)
+        );
+        let expiry = System::block_number() + 100;
+        assert_eq!(
+            Some(()),
+            AcurastMarketplace::stored_job_status_expiry(expiry, &job_id)
+        );
+
+        // a terminal status cannot be changed other than by registering the job again
+        assert_err!(
+            AcurastMarketplace::acknowledge_match(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                job_id.clone(),
+            ),
+            Error::<Test>::CannotAcknowledgeWhenNotMatched
+        );
+
+        // status is removed after the retention period
+        AcurastMarketplace::on_initialize(expiry);
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_job_status(&job_id.0, &job_id.1)
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_job_status_expiry(expiry, &job_id)
+        );
+
+        // a terminated job can be registered again and is not affected by a stale expiry
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(Acurast::deregister(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.script.clone(),
+        ));
+        later(now + 1000);
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        AcurastMarketplace::on_initialize(expiry);
+        assert_eq!(
+            Some(JobStatus::Open),
+            AcurastMarketplace::stored_job_status(&job_id.0, &job_id.1)
+        );
+
+        let status_changes: Vec<RuntimeEvent> = events()
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged { .. })
+                )
+            })
+            .collect();
+        assert_eq!(
+            status_changes,
+            [
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Open,
+                    to: JobStatus::Cancelled,
+                }),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Open,
+                    to: JobStatus::Cancelled,
+                }),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Cancelled,
+                    to: JobStatus::Open,
+                }),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_acknowledgement_timeout() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let registration = job_registration();
+    let job_id = (alice_account_id(), registration.script.clone());
+    let m = Match {
+        job_id: job_id.clone(),
+        sources: vec![PlannedExecution {
+            source: processor_account_id(),
+            start_delay: 0,
+        }],
+    };
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![m.clone()],
+        ));
+        let deadline = System::block_number() + 10;
+        assert_eq!(
+            Some(PendingMatch {
+                deadline,
+                matcher: Some((
+                    charlie_account_id(),
+                    MockAsset {
+                        id: 0,
+                        amount: 196_000
+                    }
+                )),
+            }),
+            AcurastMarketplace::stored_pending_match(alice_account_id(), script())
+        );
+        assert_eq!(
+            Some(80_000),
+            AcurastMarketplace::stored_storage_capacity(processor_account_id())
+        );
+        let _ = events();
+
+        // nothing happens before the deadline
+        AcurastMarketplace::on_initialize(deadline - 1);
+        assert_eq!(
+            Some(JobStatus::Matched),
+            AcurastMarketplace::stored_job_status(alice_account_id(), script())
+        );
+
+        AcurastMarketplace::on_initialize(deadline);
+        assert_eq!(
+            Some(JobStatus::Open),
+            AcurastMarketplace::stored_job_status(alice_account_id(), script())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_pending_match(alice_account_id(), script())
+        );
+        assert_eq!(
+            Some(100_000),
+            AcurastMarketplace::stored_storage_capacity(processor_account_id())
+        );
+        // the full matcher reward is back in the job's budget, the matcher bears the fees deducted when it was paid
+        assert_eq!(
+            Some(MockAsset {
+                id: 0,
+                amount: 12_000_000
+            }),
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        assert_eq!(12_000_000, MockPallet::pallet_balance());
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::AcurastMarketplace(crate::Event::MatchAcknowledgementTimedOut(
+                    job_id.clone(),
+                    vec![processor_account_id()]
+                )),
+                RuntimeEvent::MockPallet(mock_pallet::Event::ClawBackMatcherReward(MockAsset {
+                    id: 0,
+                    amount: 196_000
+                })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::MatcherRewardClawedBack(
+                    job_id.clone(),
+                    charlie_account_id(),
+                    MockAsset {
+                        id: 0,
+                        amount: 196_000
+                    }
+                )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Matched,
+                    to: JobStatus::Open,
+                }),
+            ]
+        );
+
+        // acknowledging late fails
+        assert_err!(
+            AcurastMarketplace::acknowledge_match(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                job_id.clone(),
+            ),
+            Error::<Test>::CannotAcknowledgeWhenNotMatched
+        );
+
+        // the job can be matched again, but gets cancelled if the sources do not acknowledge before its start
+        later(now + 1000);
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![m.clone()],
+        ));
+        let deadline = System::block_number() + 10;
+        later(registration.schedule.start_time);
+        AcurastMarketplace::on_initialize(deadline);
+        assert_eq!(
+            Some(JobStatus::Cancelled),
+            AcurastMarketplace::stored_job_status(alice_account_id(), script())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        assert_eq!(0, MockPallet::pallet_balance());
+    });
+}
+
+#[test]
+fn test_attestation_revoked() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let registration = JobRegistrationFor::<Test> {
+        allow_only_verified_sources: true,
+        ..job_registration()
+    };
+    let job_id = (alice_account_id(), registration.script.clone());
+    let updates = vec![CertificateRevocationListUpdate {
+        operation: ListUpdateOperation::Add,
+        item: (
+            hex!("302f31193017060355040513103534663539333730353432663561393531123010060355040c0c095374726f6e67426f78")
+                .to_vec()
+                .try_into()
+                .unwrap(),
+            cert_serial_number(),
+        ),
+    }];
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(Acurast::submit_attestation(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            attestation_chain(),
+        ));
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![PlannedExecution {
+                    source: processor_account_id(),
+                    start_delay: 0,
+                }],
+            }],
+        ));
+        let _ = events();
+
+        // the revoked source is removed from the unacknowledged match and the job can be matched again
+        assert_ok!(Acurast::update_certificate_revocation_list(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            updates.clone(),
+        ));
+        Acurast::on_idle(System::block_number(), Weight::MAX);
+        assert_eq!(
+            Some(JobStatus::Open),
+            AcurastMarketplace::stored_job_status(alice_account_id(), script())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_matches(processor_account_id(), job_id.clone())
+        );
+        assert_eq!(
+            None,
+            AcurastMarketplace::stored_pending_match(alice_account_id(), script())
+        );
+        assert_eq!(
+            Some(100_000),
+            AcurastMarketplace::stored_storage_capacity(processor_account_id())
+        );
+        assert_eq!(
+            events(),
+            [
+                RuntimeEvent::Acurast(pallet_acurast::Event::CertificateRecovationListUpdated(
+                    alice_account_id(),
+                    updates
+                )),
+                RuntimeEvent::Acurast(pallet_acurast::Event::AttestationRevoked(
+                    processor_account_id()
+                )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::MatchRevoked(
+                    job_id.clone(),
+                    processor_account_id()
+                )),
+                RuntimeEvent::MockPallet(mock_pallet::Event::ClawBackMatcherReward(MockAsset {
+                    id: 0,
+                    amount: 196_000
+                })),
+                RuntimeEvent::AcurastMarketplace(crate::Event::MatcherRewardClawedBack(
+                    job_id.clone(),
+                    charlie_account_id(),
+                    MockAsset {
+                        id: 0,
+                        amount: 196_000
+                    }
+                )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::JobStatusChanged {
+                    job_id: job_id.clone(),
+                    from: JobStatus::Matched,
+                    to: JobStatus::Open,
+                }),
+            ]
+        );
+
+        // the revoked source cannot be matched again
+        assert_err!(
+            AcurastMarketplace::propose_matching(
+                RuntimeOrigin::signed(charlie_account_id()).into(),
+                vec![Match {
+                    job_id: job_id.clone(),
+                    sources: vec![PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    }],
+                }],
+            ),
+            Error::<Test>::UnverifiedSourceInMatch
+        );
+    });
+}
+
+#[test]
+fn test_match_multiple_reward_assets() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let mut ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    ad.pricing
+        .try_push(PricingVariant {
+            reward_asset: 1,
+            fee_per_millisecond: 1000,
+            fee_per_storage_byte: 1,
+            base_fee_per_execution: 0,
+            stake_per_execution: 0,
+            scheduling_window: SchedulingWindow::Delta(2_628_000_000), // 1 month
+        })
+        .unwrap();
+    let registration1 = job_registration();
+    // second job is rewarded in another asset and scheduled right after the first job
+    let mut registration2 = job_registration();
+    registration2.schedule.start_time = 1_671_804_000_000; // 23.12.2022 14:00
+    registration2.schedule.end_time = 1_671_807_600_000; // 23.12.2022 15:00
+    registration2.extra.reward = MockAsset {
+        id: 1,
+        amount: 3_000_000 * 2,
+    };
+    let job_id1 = (alice_account_id(), registration1.script.clone());
+    let job_id2 = (bob_account_id(), registration2.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration1.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            registration2.clone(),
+        ));
+
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![
+                Match {
+                    job_id: job_id1.clone(),
+                    sources: vec![PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    }],
+                },
+                Match {
+                    job_id: job_id2.clone(),
+                    sources: vec![PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    }],
+                },
+            ],
+        ));
+        assert_eq!(
+            Some(JobStatus::Matched),
+            AcurastMarketplace::stored_job_status(&job_id1.0, &job_id1.1)
+        );
+        assert_eq!(
+            Some(JobStatus::Matched),
+            AcurastMarketplace::stored_job_status(&job_id2.0, &job_id2.1)
+        );
+
+        // the matcher is paid in each asset and each job's budget is only charged in its own asset
+        assert_eq!(
+            Some(MockAsset {
+                id: 0,
+                amount: 11_804_000
+            }),
+            AcurastMarketplace::stored_job_budget(&job_id1.0, &job_id1.1)
+        );
+        assert_eq!(
+            Some(MockAsset {
+                id: 1,
+                amount: 11_804_000
+            }),
+            AcurastMarketplace::stored_job_budget(&job_id2.0, &job_id2.1)
+        );
+        let matcher_payments: Vec<RuntimeEvent> = events()
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    RuntimeEvent::MockPallet(mock_pallet::Event::PayMatcherReward(_))
+                )
+            })
+            .collect();
+        assert_eq!(
+            matcher_payments,
+            [
+                RuntimeEvent::MockPallet(mock_pallet::Event::PayMatcherReward(MockAsset {
+                    id: 0,
+                    amount: 1_960_000
+                })),
+                RuntimeEvent::MockPallet(mock_pallet::Event::PayMatcherReward(MockAsset {
+                    id: 1,
+                    amount: 1_960_000
+                })),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_match_min_slots_with_slot_rewards() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let mut registration = job_registration();
+    registration.extra.slots = 3;
+    registration.extra.min_slots = Some(2);
+    registration.extra.slot_rewards = Some(
+        vec![asset(6_000_000), asset(5_500_000), asset(5_100_000)]
+            .try_into()
+            .unwrap(),
+    );
+    let job_id = (alice_account_id(), registration.script.clone());
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        // slot rewards have to be provided for all slots
+        let mut invalid_registration = registration.clone();
+        invalid_registration.extra.slot_rewards = Some(vec![asset(6_000_000)].try_into().unwrap());
+        assert_err!(
+            Acurast::register(
+                RuntimeOrigin::signed(alice_account_id()).into(),
+                invalid_registration,
+            ),
+            Error::<Test>::JobRegistrationInvalidSlotRewards
+        );
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(bob_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        // (6_000_000 + 5_500_000 + 5_100_000) * 2 executions
+        assert_eq!(33_200_000, MockPallet::pallet_balance());
+
+        // less than min_slots
+        assert_err!(
+            AcurastMarketplace::propose_matching(
+                RuntimeOrigin::signed(charlie_account_id()).into(),
+                vec![Match {
+                    job_id: job_id.clone(),
+                    sources: vec![PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    }],
+                }],
+            ),
+            Error::<Test>::IncorrectSourceCountInMatch
+        );
+
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![
+                    PlannedExecution {
+                        source: processor_account_id(),
+                        start_delay: 0,
+                    },
+                    PlannedExecution {
+                        source: bob_account_id(),
+                        start_delay: 0,
+                    },
+                ],
+            }],
+        ));
+        assert_eq!(
+            Some(1),
+            AcurastMarketplace::stored_matches(bob_account_id(), job_id.clone())
+                .map(|assignment| assignment.slot)
+        );
+
+        // the budget is reduced by the matcher reward, 10% * ((6_000_000 + 5_500_000) * 2 - 5_020_000 * 2 * 2),
+        // and the reward of the unassigned slot, 5_100_000 * 2, which is refunded
+        assert_eq!(
+            Some(MockAsset {
+                id: 0,
+                amount: 22_708_000
+            }),
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        assert_eq!(22_708_000, MockPallet::pallet_balance());
+        let refund: Vec<RuntimeEvent> = events()
+            .into_iter()
+            .filter(|e| matches!(e, RuntimeEvent::MockPallet(mock_pallet::Event::Refund(_))))
+            .collect();
+        assert_eq!(
+            refund,
+            [RuntimeEvent::MockPallet(mock_pallet::Event::Refund(
+                MockAsset {
+                    id: 0,
+                    amount: 10_200_000
+                }
+            ))]
+        );
+    });
+}
+
+#[test]
+fn test_execution_output_store() {
+    let now = 1_671_789_600_000; // 23.12.2022 10:00;
+
+    let ad = advertisement(1000, 1, 100_000, 50_000, 8);
+    let mut registration = job_registration();
+    registration.extra.max_output_size = Some(4);
+    let job_id = (alice_account_id(), registration.script.clone());
+    let output = |bytes: &[u8]| -> ExecutionOutput { bytes.to_vec().try_into().unwrap() };
+
+    ExtBuilder::default().build().execute_with(|| {
+        // pretend current time
+        later(now);
+
+        assert_ok!(AcurastMarketplace::advertise(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            ad.clone(),
+        ));
+        assert_ok!(Acurast::register(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            registration.clone(),
+        ));
+        assert_ok!(AcurastMarketplace::propose_matching(
+            RuntimeOrigin::signed(charlie_account_id()).into(),
+            vec![Match {
+                job_id: job_id.clone(),
+                sources: vec![PlannedExecution {
+                    source: processor_account_id(),
+                    start_delay: 0,
+                }],
+            }],
+        ));
+        assert_ok!(AcurastMarketplace::acknowledge_match(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+        ));
+
+        let mut iter = registration.schedule.iter(0).unwrap();
+        later(iter.next().unwrap() + 1000);
+        assert_err!(
+            AcurastMarketplace::report_with_output(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                job_id.clone(),
+                false,
+                ExecutionResult::Success(operation_hash()),
+                output(&[1, 2, 3, 4, 5]),
+            ),
+            Error::<Test>::ExecutionOutputTooLarge
+        );
+        events();
+        assert_ok!(AcurastMarketplace::report_with_output(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+            false,
+            ExecutionResult::Success(operation_hash()),
+            output(&[1, 2, 3, 4]),
+        ));
+        let first_expiry = System::block_number() + 100;
+
+        // a second output for the same execution is rejected and the first deposit stays reserved
+        assert_err!(
+            AcurastMarketplace::report_with_output(
+                RuntimeOrigin::signed(processor_account_id()).into(),
+                job_id.clone(),
+                false,
+                ExecutionResult::Success(operation_hash()),
+                output(&[9]),
+            ),
+            Error::<Test>::ExecutionOutputAlreadyStored
+        );
+        assert_eq!(1_040, Balances::reserved_balance(alice_account_id()));
+
+        later(iter.next().unwrap() + 1000);
+        assert_ok!(AcurastMarketplace::report_with_output(
+            RuntimeOrigin::signed(processor_account_id()).into(),
+            job_id.clone(),
+            true,
+            ExecutionResult::Success(operation_hash()),
+            output(&[5, 6]),
+        ));
+        let second_expiry = System::block_number() + 100;
+
+        // deposits are 1_000 + 10 per byte
+        assert_eq!(2_060, Balances::reserved_balance(alice_account_id()));
+        assert_eq!(
+            Some(ExecutionOutputRecord {
+                output: output(&[1, 2, 3, 4]),
+                deposit: 1_040,
+                expiry: first_expiry,
+            }),
+            AcurastMarketplace::stored_execution_output(
+                job_id.clone(),
+                (processor_account_id(), 0)
+            )
+        );
+        assert_eq!(
+            Some(output(&[5, 6])),
+            AcurastMarketplace::stored_execution_output(
+                job_id.clone(),
+                (processor_account_id(), 1)
+            )
+            .map(|record| record.output)
+        );
+        let stored: Vec<RuntimeEvent> = events()
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    RuntimeEvent::AcurastMarketplace(crate::Event::ExecutionOutputStored(..))
+                )
+            })
+            .collect();
+        assert_eq!(
+            stored,
+            [
+                RuntimeEvent::AcurastMarketplace(crate::Event::ExecutionOutputStored(
+                    job_id.clone(),
+                    processor_account_id(),
+                    0
+                )),
+                RuntimeEvent::AcurastMarketplace(crate::Event::ExecutionOutputStored(
+                    job_id.clone(),
+                    processor_account_id(),
+                    1
+                )),
+            ]
+        );
+
+        // only the consumer can remove outputs
+        assert_err!(
+            AcurastMarketplace::remove_execution_outputs(
+                RuntimeOrigin::signed(bob_account_id()).into(),
+                job_id.clone(),
+                1,
+            ),
+            Error::<Test>::OnlyConsumerCanRemoveOutputs
+        );
+        assert_ok!(AcurastMarketplace::remove_execution_outputs(
+            RuntimeOrigin::signed(alice_account_id()).into(),
+            job_id.clone(),
+            1,
+        ));
+        assert_eq!(
+            events(),
+            [RuntimeEvent::AcurastMarketplace(
+                crate::Event::ExecutionOutputsRemoved(job_id.clone(), 1)
+            )]
+        );
+        let remaining: Vec<_> =
+            crate::StoredExecutionOutput::<Test>::iter_prefix_values(&job_id).collect();
+        assert_eq!(1, remaining.len());
+        assert_eq!(
+            remaining[0].deposit,
+            Balances::reserved_balance(alice_account_id())
+        );
+
+        // the remaining output expires after the retention period
+        AcurastMarketplace::on_initialize(first_expiry);
+        AcurastMarketplace::on_initialize(second_expiry);
+        assert_eq!(0, Balances::reserved_balance(alice_account_id()));
+        assert_eq!(
+            0,
+            crate::StoredExecutionOutput::<Test>::iter_prefix(&job_id).count()
+        );
+    });
+}
+
+#[test]
+fn test_migrate_job_budget_and_matched_sources() {
+    let registration = job_registration();
+    let matched_job_id = (alice_account_id(), script_random_value());
+    let old_registration = |script: Script| JobRegistration {
+        script,
+        allowed_sources: registration.allowed_sources.clone(),
+        allow_only_verified_sources: registration.allow_only_verified_sources,
+        schedule: registration.schedule.clone(),
+        memory: registration.memory,
+        network_requests: registration.network_requests,
+        storage: registration.storage,
+        extra: migrations::v1::OldJobRequirements {
+            slots: 1,
+            reward: asset(3_000_000 * 2),
+            instant_match: None,
+        },
+    };
+
+    ExtBuilder::default().build().execute_with(|| {
+        StorageVersion::new(0).put::<AcurastMarketplace>();
+        // an open job and a job whose source reported one of two executions
+        migrations::v1::StoredJobRegistration::<Test>::insert(
+            alice_account_id(),
+            script(),
+            old_registration(script()),
+        );
+        crate::StoredJobStatus::<Test>::insert(alice_account_id(), script(), JobStatus::Open);
+        migrations::v1::StoredJobRegistration::<Test>::insert(
+            &matched_job_id.0,
+            &matched_job_id.1,
+            old_registration(matched_job_id.1.clone()),
+        );
+        crate::StoredJobStatus::<Test>::insert(
+            &matched_job_id.0,
+            &matched_job_id.1,
+            JobStatus::Assigned(1),
+        );
+        migrations::v1::StoredMatches::<Test>::insert(
+            processor_account_id(),
+            &matched_job_id,
+            migrations::v1::OldAssignment {
+                slot: 0,
+                start_delay: 0,
+                fee_per_execution: asset(5_020_000),
+                acknowledged: true,
+                sla: SLA { total: 2, met: 1 },
+            },
+        );
+
+        migrations::migrate::<Test>();
+
+        assert_eq!(
+            Some(registration.clone()),
+            Acurast::stored_job_registration(alice_account_id(), script())
+        );
+        assert_eq!(
+            Some(asset(12_000_000)),
+            AcurastMarketplace::stored_job_budget(alice_account_id(), script())
+        );
+        // only the fee of the execution not reported yet is left in the budget
+        assert_eq!(
+            Some(asset(5_020_000)),
+            AcurastMarketplace::stored_job_budget(&matched_job_id.0, &matched_job_id.1)
+        );
+        assert_eq!(
+            Some(()),
+            AcurastMarketplace::stored_matched_sources(&matched_job_id, processor_account_id())
+        );
+        assert_eq!(
+            StorageVersion::new(2),
+            AcurastMarketplace::on_chain_storage_version()
+        );
+    });
+}
+
+#[test]
+fn test_migrate_stake_per_execution() {
+    let job_id = (alice_account_id(), script());
+
+    ExtBuilder::default().build().execute_with(|| {
+        StorageVersion::new(1).put::<AcurastMarketplace>();
+        frame_support::storage::unhashed::put(
+            &crate::StoredAdvertisementPricing::<Test>::hashed_key_for(processor_account_id(), 0),
+            &migrations::v2::OldPricingVariant {
+                reward_asset: 0u32,
+                fee_per_millisecond: 1_000u128,
+                fee_per_storage_byte: 1,
+                base_fee_per_execution: 0,
+                scheduling_window: SchedulingWindow::End(4133980799000),
+            },
+        );
+        migrations::v1::StoredMatches::<Test>::insert(
+            processor_account_id(),
+            &job_id,
+            migrations::v1::OldAssignment {
+                slot: 0,
+                start_delay: 0,
+                fee_per_execution: asset(5_020_000),
+                acknowledged: true,
+                sla: SLA { total: 2, met: 1 },
+            },
+        );
+
+        migrations::migrate::<Test>();
+
+        assert_eq!(
+            Some(PricingVariant {
+                reward_asset: 0,
+                fee_per_millisecond: 1_000,
+                fee_per_storage_byte: 1,
+                base_fee_per_execution: 0,
+                stake_per_execution: 0,
+                scheduling_window: SchedulingWindow::End(4133980799000),
+            }),
+            AcurastMarketplace::stored_advertisement_pricing(processor_account_id(), 0)
+        );
+        // no stake was locked on acknowledgement
+        assert_eq!(
+            Some(Assignment {
+                slot: 0,
+                start_delay: 0,
+                fee_per_execution: asset(5_020_000),
+                stake_per_execution: asset(0),
+                acknowledged: true,
+                sla: SLA { total: 2, met: 1 },
+            }),
+            AcurastMarketplace::stored_matches(processor_account_id(), &job_id)
+        );
+        assert_eq!(
+            StorageVersion::new(2),
+            AcurastMarketplace::on_chain_storage_version()
+        );
+    });
+}
+
 fn next_block() {
     if System::block_number() >= 1 {
         // pallet_acurast_marketplace::on_finalize(System::block_number());
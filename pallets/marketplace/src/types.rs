@@ -1,4 +1,5 @@
-use frame_support::{pallet_prelude::*, storage::bounded_vec::BoundedVec};
+use frame_support::{pallet_prelude::*, storage::bounded_vec::BoundedVec, traits::Currency};
+use sp_runtime::Permill;
 use sp_std::prelude::*;
 
 use pallet_acurast::{JobId, JobRegistration};
@@ -8,12 +9,27 @@ use crate::Config;
 
 pub const MAX_PRICING_VARIANTS: u32 = 100;
 pub const MAX_EXECUTIONS_PER_JOB: u64 = 10000;
+/// The maximum number of slots of a job, as limited by the type of [JobRequirements::slots].
+pub const MAX_SLOTS: u32 = u8::MAX as u32;
+/// The number of steps in which the on-chain matcher spreads the start delays tried over `[0, max_start_delay]`.
+pub const AUTO_MATCH_START_DELAY_STEPS: u64 = 4;
+/// The maximum number of matches of a source considered by the on-chain matcher. Sources with more matches are skipped,
+/// bounding the schedule checks per candidate.
+pub const AUTO_MATCH_MAX_SOURCE_MATCHES: u32 = 32;
+
+/// The highest [crate::utils::resource_bucket] of an advertised `max_memory`.
+pub const MAX_MEMORY_BUCKET: u8 = u32::BITS as u8;
 
 pub const EXECUTION_OPERATION_HASH_MAX_LENGTH: u32 = 256;
 pub const EXECUTION_FAILURE_MESSAGE_MAX_LENGTH: u32 = 1024;
+pub const EXECUTION_OUTPUT_MAX_LENGTH: u32 = 4096;
 
 pub type ExecutionOperationHash = BoundedVec<u8, ConstU32<EXECUTION_OPERATION_HASH_MAX_LENGTH>>;
 pub type ExecutionFailureMessage = BoundedVec<u8, ConstU32<EXECUTION_FAILURE_MESSAGE_MAX_LENGTH>>;
+pub type ExecutionOutput = BoundedVec<u8, ConstU32<EXECUTION_OUTPUT_MAX_LENGTH>>;
+
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 pub type JobRegistrationForMarketplace<T> =
     JobRegistration<<T as frame_system::Config>::AccountId, <T as Config>::RegistrationExtra>;
@@ -77,6 +93,10 @@ pub struct PricingVariant<AssetId, AssetAmount> {
     pub fee_per_storage_byte: AssetAmount,
     /// A fixed base fee for each execution (for each slot and at each interval) in [reward_asset].
     pub base_fee_per_execution: AssetAmount,
+    /// The stake per execution in [reward_asset] that is locked when a match is acknowledged.
+    ///
+    /// The stake for each execution missed according to the SLA is slashed and transferred to the consumer, the rest is released after the schedule ended.
+    pub stake_per_execution: AssetAmount,
     /// The scheduling window in which to accept matches for this pricing.
     pub scheduling_window: SchedulingWindow,
 }
@@ -96,6 +116,8 @@ pub struct Assignment<Reward> {
     pub start_delay: u64,
     /// The fee owed to source for each execution.
     pub fee_per_execution: Reward,
+    /// The stake locked by the source for each execution, slashed for each execution missed.
+    pub stake_per_execution: Reward,
     /// If this assignment was acknowledged.
     pub acknowledged: bool,
     /// Keeps track of the SLA.
@@ -104,16 +126,72 @@ pub struct Assignment<Reward> {
 
 pub type AssignmentFor<T> = Assignment<RewardFor<T>>;
 
-/// The allowed sources update operation.
+/// A [Match] that is not yet acknowledged by all matched sources.
+///
+/// Sources that did not acknowledge until the `deadline` are removed from the job.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq)]
+pub struct PendingMatch<AccountId, BlockNumber, Reward> {
+    /// The block in which the acknowledgement deadline elapses.
+    pub deadline: BlockNumber,
+    /// The matcher and its reward paid for proposing the match, if the match was proposed with [crate::Call::propose_matching].
+    pub matcher: Option<(AccountId, Reward)>,
+}
+
+pub type PendingMatchFor<T> = PendingMatch<
+    <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::BlockNumber,
+    RewardFor<T>,
+>;
+
+/// The status of a job in the marketplace.
+///
+/// A job ends in one of the terminal statuses, which are retained for a limited period before getting removed from storage.
 #[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Copy)]
 pub enum JobStatus {
     /// Status after a job got registered.
     Open,
-    /// Status after a valid match for a job got submitted.
+    /// Status after a valid match for a job got submitted. Goes back to [JobStatus::Open] if no source acknowledges in time.
     Matched,
     /// Status after a number of acknowledgments were submitted by sources.
     Assigned(u8),
-    // The implicit final status leads to removal of job from status storage.
+    /// Terminal status after all executions of a job were reported.
+    Completed,
+    /// Terminal status after a job got deregistered before the end of its schedule.
+    Cancelled,
+    /// Terminal status after a job's schedule ended without any execution reported, or a job got deregistered after it was overdue without being matched.
+    Expired,
+    /// Terminal status after a job's schedule ended with only some of the executions reported.
+    PartiallyFulfilled,
+}
+
+impl JobStatus {
+    /// Returns true if no further transition is expected, except for registering the job again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed
+                | JobStatus::Cancelled
+                | JobStatus::Expired
+                | JobStatus::PartiallyFulfilled
+        )
+    }
+
+    /// Returns true if a job is allowed to transition from this status to the `next` status.
+    pub fn can_transition_to(&self, next: &JobStatus) -> bool {
+        match (self, next) {
+            (JobStatus::Open, JobStatus::Matched) => true,
+            (JobStatus::Open, JobStatus::Cancelled | JobStatus::Expired) => true,
+            (JobStatus::Matched, JobStatus::Assigned(count)) => *count == 1,
+            // none of the matched sources acknowledged in time
+            (JobStatus::Matched, JobStatus::Open) => true,
+            (JobStatus::Assigned(count), JobStatus::Assigned(next_count)) => {
+                count.checked_add(1) == Some(*next_count)
+            }
+            (JobStatus::Matched | JobStatus::Assigned(_), next) => next.is_terminal(),
+            (status, JobStatus::Open) => status.is_terminal(),
+            _ => false,
+        }
+    }
 }
 
 impl Default for JobStatus {
@@ -125,12 +203,48 @@ impl Default for JobStatus {
 /// Keeps track of the SLA during and after a job's schedule is completed.
 ///
 /// Also used to ensure that Acurast does not accept more than the expected number of reports (and pays out no more rewards).
-#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Copy)]
+#[derive(
+    RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Copy, Default,
+)]
 pub struct SLA {
     pub total: u64,
     pub met: u64,
 }
 
+/// Keeps track of the SLA history of a source over all its assignments, from which its reputation score is derived.
+///
+/// Only updated for sources with a valid attestation, since the history of non-attested devices cannot be attributed to a physical device.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Default)]
+pub struct Reputation {
+    /// The number of executions reported within schedule.
+    pub met: u64,
+    /// The number of executions missed according to the SLA at the end of an assignment.
+    pub missed: u64,
+    /// The number of assignments completed with a final report of the source.
+    pub jobs_completed: u64,
+    /// The number of reports received after the execution they were intended for ended.
+    pub late_reports: u64,
+}
+
+impl Reputation {
+    /// The number of executions that were due, either met or missed.
+    pub fn due(&self) -> u64 {
+        self.met.saturating_add(self.missed)
+    }
+
+    /// The share of executions met out of all executions that were due.
+    ///
+    /// Sources without any history have a full score. Jobs requiring a minimum reputation only accept sources with a
+    /// history of at least [`crate::Config::MinReputationHistory`] executions.
+    pub fn score(&self) -> Permill {
+        let due = self.due();
+        if due == 0 {
+            return Permill::one();
+        }
+        Permill::from_rational(self.met, due)
+    }
+}
+
 pub type JobRequirementsFor<T> =
     JobRequirements<RewardFor<T>, <T as frame_system::Config>::AccountId>;
 
@@ -140,13 +254,43 @@ pub struct JobRequirements<Reward, AccountId>
 where
     Reward: Parameter + Member,
 {
-    /// The number of execution slots to be assigned to distinct sources. Either all or no slot get assigned by matching.
+    /// The number of execution slots to be assigned to distinct sources.
     pub slots: u8,
+    /// The minimum number of slots to be assigned by matching. If [None], either all or no slot get assigned by matching.
+    ///
+    /// The budget for the slots not assigned gets refunded on matching.
+    pub min_slots: Option<u8>,
     /// Reward offered for each slot and scheduled execution of the job.
     pub reward: Reward,
+    /// Optional rewards offered for each scheduled execution of the individual slots, overriding `reward`. Must contain an entry for each slot in the same asset as `reward`.
+    pub slot_rewards: Option<BoundedVec<Reward, ConstU32<MAX_SLOTS>>>,
     /// Optional match provided with the job requirements. If provided, it gets processed instantaneously during
     /// registration call and validation errors lead to abortion of the call.
     pub instant_match: Option<Vec<PlannedExecution<AccountId>>>,
+    /// The minimum [Reputation] score of sources accepted in matching. If [None], sources are accepted regardless of their reputation.
+    /// Otherwise, only attested sources with a history of at least [`crate::Config::MinReputationHistory`] executions are accepted.
+    pub min_reputation: Option<Permill>,
+    /// The maximum size in bytes of execution outputs that sources are allowed to store on-chain when reporting, reserving a deposit from the consumer.
+    /// If [None], no outputs are stored for the job.
+    pub max_output_size: Option<u32>,
+}
+
+impl<Reward, AccountId> JobRequirements<Reward, AccountId>
+where
+    Reward: Parameter + Member,
+{
+    /// The minimum number of slots to be assigned by matching.
+    pub fn required_slots(&self) -> u8 {
+        self.min_slots.unwrap_or(self.slots)
+    }
+
+    /// The reward offered for each scheduled execution of the `slot`.
+    pub fn slot_reward(&self, slot: u8) -> &Reward {
+        self.slot_rewards
+            .as_ref()
+            .and_then(|rewards| rewards.get(slot as usize))
+            .unwrap_or(&self.reward)
+    }
 }
 
 /// A (one-sided) matching of a job to sources such that the requirements of both sides, consumer and source, are met.
@@ -154,7 +298,7 @@ where
 pub struct Match<AccountId> {
     /// The job to match.
     pub job_id: JobId<AccountId>,
-    /// The sources to match each of the job's slots with.
+    /// The sources to match the job's slots with, in the order of the slots. Slots without a source remain unassigned.
     pub sources: Vec<PlannedExecution<AccountId>>,
 }
 
@@ -167,6 +311,20 @@ pub struct PlannedExecution<AccountId> {
     pub start_delay: u64,
 }
 
+/// An execution output stored on-chain with the deposit reserved from the consumer for storing it.
+#[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq)]
+pub struct ExecutionOutputRecord<Balance, BlockNumber> {
+    /// The output of the execution.
+    pub output: ExecutionOutput,
+    /// The deposit reserved from the consumer.
+    pub deposit: Balance,
+    /// The block in which the output gets removed from storage unless the consumer removed it before.
+    pub expiry: BlockNumber,
+}
+
+pub type ExecutionOutputRecordFor<T> =
+    ExecutionOutputRecord<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
 #[derive(RuntimeDebug, Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq)]
 pub enum ExecutionResult {
     /// Success with operation hash.
@@ -1,5 +1,5 @@
 use crate::Config;
-use pallet_acurast::JobRegistrationFor;
+use pallet_acurast::{JobRegistrationFor, Schedule};
 use sp_std::prelude::*;
 
 /// Checks if a consumer is whitelisted/
@@ -32,3 +32,45 @@ pub fn is_source_whitelisted<T: Config>(
         })
         .unwrap_or(true)
 }
+
+/// Returns the bucket of a resource amount used in the advertisement indices.
+///
+/// Bucket `0` only contains `0`, bucket `b > 0` contains all amounts in `[2^(b-1), 2^b)`.
+pub fn resource_bucket(amount: u64) -> u8 {
+    (u64::BITS - amount.leading_zeros()) as u8
+}
+
+/// Returns the index of the first execution of a schedule that did not end before `time`, respecting `start_delay`.
+pub(crate) fn execution_index(schedule: &Schedule, start_delay: u64, time: u64) -> Option<u64> {
+    let (start, _) = schedule.range(start_delay)?;
+    let relative = time.saturating_sub(start);
+    let index = relative.checked_div(schedule.interval)?;
+    if relative.checked_rem(schedule.interval)? < schedule.duration {
+        Some(index)
+    } else {
+        index.checked_add(1)
+    }
+}
+
+/// Returns the index of the execution of a schedule in progress at `time`, respecting `start_delay`.
+pub(crate) fn execution_in_progress(
+    schedule: &Schedule,
+    start_delay: u64,
+    time: u64,
+) -> Option<u64> {
+    let (start, _) = schedule.range(start_delay)?;
+    let relative = time.checked_sub(start)?;
+    let index = relative.checked_div(schedule.interval)?;
+    if index < schedule.execution_count()
+        && relative.checked_rem(schedule.interval)? < schedule.duration
+    {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+/// Returns the [resource_bucket] of a remaining storage capacity. Negative capacities are in bucket `0`.
+pub(crate) fn storage_bucket(capacity: i64) -> u8 {
+    resource_bucket(capacity.max(0) as u64)
+}
@@ -14,7 +14,7 @@
 // --wasm-execution=compiled
 // --pallet=pallet_acurast_marketplace
 // --extrinsic
-// advertise,delete_advertisement
+// advertise,delete_advertisement,deregister,finalize_job,auto_match_job,report_with_output,remove_execution_outputs
 // --steps=50
 // --repeat=20
 // --output=./src/weights.rs
@@ -34,6 +34,11 @@ pub trait WeightInfo {
     fn propose_matching() -> Weight;
     fn acknowledge_match() -> Weight;
     fn report() -> Weight;
+    fn finalize_job(x: u32, ) -> Weight;
+    fn auto_match_job(c: u32, ) -> Weight;
+    fn report_with_output(b: u32, ) -> Weight;
+    fn remove_execution_outputs(n: u32, ) -> Weight;
+    fn deregister(x: u32, ) -> Weight;
 }
 
 /// Weights for pallet_acurast_marketplace using the Substrate node and recommended hardware.
@@ -75,4 +80,109 @@ impl<T: frame_system::Config> WeightInfo for Weights<T> {
             .saturating_add(T::DbWeight::get().reads(2))
             .saturating_add(T::DbWeight::get().writes(3))
     }
+    // Storage: AcurastMarketplace StoredJobStatus (r:1 w:1)
+    // Storage: Acurast StoredJobRegistration (r:1 w:1)
+    // Storage: AcurastMarketplace StoredMatchedSources (r:x w:x)
+    // Storage: AcurastMarketplace StoredMatches (r:x w:x)
+    // Storage: AcurastMarketplace StoredStorageCapacity (r:x w:x)
+    // Storage: AcurastMarketplace StoredSourcesByStorageBucket (r:0 w:x)
+    // Storage: AcurastMarketplace StoredReputation (r:x w:x)
+    // Storage: Acurast StoredAttestation (r:x w:0)
+    // Storage: AcurastMarketplace StoredJobSLA (r:1 w:1)
+    // Storage: AcurastMarketplace StoredJobStatusExpiry (r:0 w:1)
+    // Storage: AcurastMarketplace StoredPendingMatch (r:0 w:1)
+    // Storage: AcurastMarketplace StoredJobBudget (r:1 w:1)
+    // Storage: Assets Asset (r:1 w:1)
+    // Storage: Assets Account (r:2 w:2)
+    /// The range of component `x` is `[1, 255]`.
+    fn finalize_job(x: u32, ) -> Weight {
+        // Minimum execution time: 96_412 nanoseconds.
+        Weight::from_ref_time(98_735_000)
+            // Standard Error: 6_214
+            .saturating_add(Weight::from_ref_time(31_407_000).saturating_mul(x.into()))
+            .saturating_add(T::DbWeight::get().reads(7))
+            .saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(x.into())))
+            .saturating_add(T::DbWeight::get().writes(9))
+            .saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(x.into())))
+    }
+    // Storage: AcurastMarketplace StoredAutoMatchCursor (r:1 w:1)
+    // Storage: Acurast StoredJobRegistration (r:8161 w:0)
+    // Storage: AcurastMarketplace StoredJobStatus (r:1 w:1)
+    // Storage: AcurastMarketplace StoredSourcesByMemoryBucket (r:c w:0)
+    // Storage: AcurastMarketplace StoredSourcesByAsset (r:c w:0)
+    // Storage: AcurastMarketplace StoredAdvertisementRestriction (r:c w:0)
+    // Storage: AcurastMarketplace StoredAdvertisementPricing (r:c w:0)
+    // Storage: AcurastMarketplace StoredStorageCapacity (r:c w:c)
+    // Storage: AcurastMarketplace StoredSourcesByStorageBucket (r:0 w:c)
+    // Storage: AcurastMarketplace StoredMatches (r:16830 w:255)
+    // Storage: AcurastMarketplace StoredMatchedSources (r:0 w:c)
+    // Storage: AcurastMarketplace StoredReputation (r:c w:0)
+    // Storage: AcurastMarketplace StoredJobBudget (r:1 w:1)
+    // Storage: AcurastMarketplace StoredPendingMatch (r:0 w:1)
+    /// The range of component `c` is `[1, 255]`.
+    fn auto_match_job(c: u32, ) -> Weight {
+        // Minimum execution time: 142_307 nanoseconds.
+        Weight::from_ref_time(118_942_000)
+            // Standard Error: 11_382
+            .saturating_add(Weight::from_ref_time(312_514_000).saturating_mul(c.into()))
+            .saturating_add(T::DbWeight::get().reads(5))
+            .saturating_add(T::DbWeight::get().reads((105_u64).saturating_mul(c.into())))
+            .saturating_add(T::DbWeight::get().writes(5))
+            .saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(c.into())))
+    }
+    // Storage: Acurast StoredJobRegistration (r:1 w:0)
+    // Storage: AcurastMarketplace StoredMatches (r:1 w:1)
+    // Storage: AcurastMarketplace StoredJobBudget (r:1 w:1)
+    // Storage: Acurast StoredAttestation (r:1 w:0)
+    // Storage: AcurastMarketplace StoredReputation (r:1 w:1)
+    // Storage: Assets Asset (r:1 w:1)
+    // Storage: Assets Account (r:2 w:2)
+    // Storage: System Account (r:1 w:1)
+    // Storage: AcurastMarketplace StoredExecutionOutput (r:1 w:1)
+    // Storage: AcurastMarketplace StoredExecutionOutputExpiry (r:0 w:1)
+    /// The range of component `b` is `[0, 4096]`.
+    fn report_with_output(b: u32, ) -> Weight {
+        // Minimum execution time: 121_846 nanoseconds.
+        Weight::from_ref_time(126_493_000)
+            // Standard Error: 38
+            .saturating_add(Weight::from_ref_time(1_147).saturating_mul(b.into()))
+            .saturating_add(T::DbWeight::get().reads(10))
+            .saturating_add(T::DbWeight::get().writes(10))
+    }
+    // Storage: AcurastMarketplace StoredExecutionOutput (r:n w:n)
+    // Storage: System Account (r:1 w:1)
+    /// The range of component `n` is `[1, 1000]`.
+    fn remove_execution_outputs(n: u32, ) -> Weight {
+        // Minimum execution time: 31_208 nanoseconds.
+        Weight::from_ref_time(24_761_000)
+            // Standard Error: 2_104
+            .saturating_add(Weight::from_ref_time(13_582_000).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(1))
+            .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(1))
+            .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+    }
+    // Storage: AcurastMarketplace StoredJobStatus (r:1 w:1)
+    // Storage: Acurast StoredJobRegistration (r:1 w:1)
+    // Storage: AcurastMarketplace StoredMatchedSources (r:x w:x)
+    // Storage: AcurastMarketplace StoredMatches (r:x w:x)
+    // Storage: AcurastMarketplace StoredStorageCapacity (r:x w:x)
+    // Storage: AcurastMarketplace StoredSourcesByStorageBucket (r:0 w:x)
+    // Storage: AcurastMarketplace StoredJobSLA (r:1 w:1)
+    // Storage: AcurastMarketplace StoredJobStatusExpiry (r:0 w:1)
+    // Storage: AcurastMarketplace StoredPendingMatch (r:0 w:1)
+    // Storage: AcurastMarketplace StoredJobBudget (r:1 w:1)
+    // Storage: Assets Asset (r:1 w:1)
+    // Storage: Assets Account (r:x w:x)
+    /// The range of component `x` is `[1, 255]`.
+    fn deregister(x: u32, ) -> Weight {
+        // Minimum execution time: 104_958 nanoseconds.
+        Weight::from_ref_time(107_216_000)
+            // Standard Error: 9_873
+            .saturating_add(Weight::from_ref_time(58_132_000).saturating_mul(x.into()))
+            .saturating_add(T::DbWeight::get().reads(6))
+            .saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(x.into())))
+            .saturating_add(T::DbWeight::get().writes(8))
+            .saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(x.into())))
+    }
 }
@@ -14,7 +14,7 @@
 // --wasm-execution=compiled
 // --pallet=pallet_acurast_marketplace
 // --extrinsic
-// register,deregister,update_allowed_sources
+// register,update_allowed_sources
 // --steps=50
 // --repeat=20
 // --output=./src/weights_with_hooks.rs
@@ -27,10 +27,11 @@
 use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use sp_std::marker::PhantomData;
 use pallet_acurast;
+use crate::{weights::WeightInfo, MAX_SLOTS};
 
 /// Weights for pallet_acurast_marketplace using the Substrate node and recommended hardware.
 pub struct Weights<T, W>(PhantomData<(T, W)>);
-impl<T: frame_system::Config + pallet_acurast::Config, W: pallet_acurast::WeightInfo> pallet_acurast::WeightInfo for Weights<T, W> {
+impl<T: crate::Config, W: pallet_acurast::WeightInfo> pallet_acurast::WeightInfo for Weights<T, W> {
     // Storage: AcurastMarketplace StoredJobStatus (r:1 w:1)
     // Storage: AcurastMarketplace StoredAdIndex (r:1 w:0)
     // Storage: AcurastMarketplace StoredCapacity (r:1 w:1)
@@ -45,13 +46,9 @@ impl<T: frame_system::Config + pallet_acurast::Config, W: pallet_acurast::Weight
             .saturating_add(T::DbWeight::get().reads(8))
             .saturating_add(T::DbWeight::get().writes(8))
     }
-    // Storage: AcurastMarketplace StoredJobStatus (r:1 w:1)
-    // Storage: Acurast StoredJobRegistration (r:0 w:1)
     fn deregister() -> Weight {
-        // Minimum execution time:  nanoseconds.
-        Weight::from_ref_time(50_437_000)
-            .saturating_add(T::DbWeight::get().reads(1))
-            .saturating_add(T::DbWeight::get().writes(2))
+        // benchmarked separately since it depends on the number of matched slots
+        <T as crate::Config>::WeightInfo::deregister(MAX_SLOTS)
     }
     fn update_allowed_sources() -> Weight {
         W::update_allowed_sources()
@@ -62,4 +59,28 @@ impl<T: frame_system::Config + pallet_acurast::Config, W: pallet_acurast::Weight
     fn update_certificate_revocation_list() -> Weight {
         W::update_certificate_revocation_list()
     }
+    fn set_attestation_policy() -> Weight {
+        W::set_attestation_policy()
+    }
+    fn update_trusted_roots() -> Weight {
+        W::update_trusted_roots()
+    }
+    fn import_certificate_revocation_list(x: u32) -> Weight {
+        W::import_certificate_revocation_list(x)
+    }
+    // Storage: AcurastMarketplace StoredMatches (r:2 w:1)
+    // Storage: AcurastMarketplace StoredPendingMatch (r:1 w:1)
+    // Storage: Acurast StoredJobRegistration (r:1 w:0)
+    // Storage: AcurastMarketplace StoredJobStatus (r:1 w:1)
+    // Storage: AcurastMarketplace StoredMatchedSources (r:2 w:1)
+    fn revoke_attestation() -> Weight {
+        // the hook revokes the unacknowledged matches of the source, estimated with one revoked match
+        W::revoke_attestation()
+            .saturating_add(Weight::from_ref_time(40_000_000))
+            .saturating_add(T::DbWeight::get().reads(7))
+            .saturating_add(T::DbWeight::get().writes(4))
+    }
+    fn remove_revoked_serial_numbers(x: u32) -> Weight {
+        W::remove_revoked_serial_numbers(x)
+    }
 }
@@ -1,5 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -13,41 +14,145 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::inherent::Vec;
-    use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+    use frame_support::{
+        dispatch::{DispatchResult, GetDispatchInfo},
+        pallet_prelude::*,
+        sp_runtime::{
+            traits::{Saturating, Verify},
+            MultiAddress,
+        },
+    };
     use frame_system::pallet_prelude::*;
-    use xcm::v2::prelude::*;
-    use xcm::v2::Instruction::{DescendOrigin, Transact};
-    use xcm::v2::{
+    use xcm::v3::prelude::*;
+    use xcm::v3::Instruction::{
+        BurnAsset, BuyExecution, DepositAsset, DescendOrigin, ExpectTransactStatus,
+        ReceiveTeleportedAsset, RefundSurplus, ReportError, ReserveAssetDeposited, SetAppendix,
+        Transact, WithdrawAsset,
+    };
+    use xcm::v3::{
+        ExecuteXcm,
         Junction::{AccountId32, Parachain},
         Junctions::X1,
         SendXcm, Xcm,
     };
-    use xcm::v2::{OriginKind, SendError};
+    use xcm::v3::{
+        MaybeErrorCode, OriginKind, QueryId, QueryResponseInfo, Response, SendError, XcmContext,
+    };
+    use xcm::WrapVersion;
+    use xcm_executor::traits::{TransactAsset, WeightBounds};
 
-    use acurast_common::{AllowedSourcesUpdate, JobRegistration, Script};
-    use pallet_acurast_marketplace::Advertisement;
+    use acurast_common::{AllowedSourcesUpdate, AttestationChain, JobId, JobRegistration, Script};
+    use pallet_acurast_marketplace::{Advertisement, ExecutionResult, Match};
+    use pallet_acurast_processor_manager::{ProcessorPairing, ProcessorPairingUpdate};
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + pallet_xcm::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// The overarching call type, notified by `pallet_xcm` with the outcome of proxied calls.
+        type RuntimeCall: From<Call<Self>> + Into<<Self as pallet_xcm::Config>::RuntimeCall>;
+        /// The overarching origin type, used to verify that outcomes are reported by the Acurast parachain.
+        type RuntimeOrigin: From<<Self as frame_system::Config>::RuntimeOrigin>
+            + Into<Result<pallet_xcm::Origin, <Self as Config>::RuntimeOrigin>>;
         /// Extra structure to include in the registration of a job.
         type RegistrationExtra: Parameter + Member;
         type AssetId: Parameter + Member;
         type AssetAmount: Parameter;
         type XcmSender: SendXcm;
+        /// Wraps messages in the XCM version negotiated with the Acurast parachain, usually `PolkadotXcm`.
+        type VersionWrapper: WrapVersion;
         type AcurastPalletId: Get<u8>;
         type AcurastMarketplacePalletId: Get<u8>;
+        type AcurastProcessorManagerPalletId: Get<u8>;
+        /// The proof of processor pairings, the same type as `pallet_acurast_processor_manager::Config::Proof` on the Acurast parachain.
+        type ProcessorPairingProof: Parameter + Member + Verify;
         type AcurastParachainId: Get<u32>;
+        /// The location of this chain as seen from the Acurast parachain, receiving the fees not used for the execution.
+        type SelfLocation: Get<MultiLocation>;
+        /// The maximum weight of the `Transact` sent to the Acurast parachain if no [XcmExecutionConfig] is stored.
+        #[pallet::constant]
+        type DefaultTransactWeight: Get<Weight>;
+        /// The number of blocks after which a proxied call whose outcome was not reported is considered timed out.
+        #[pallet::constant]
+        type ProxyCallTimeout: Get<Self::BlockNumber>;
+        /// The asset transactor of `pallet_xcm`'s `XcmExecutor`, checking teleported rewards out of this chain like `InitiateTeleport`.
+        type AssetTransactor: TransactAsset;
+        /// The weight of validating and delivering a message with [Config::XcmSender], charged to every proxied call.
+        #[pallet::constant]
+        type XcmSendWeight: Get<Weight>;
     }
 
     #[pallet::error]
     pub enum Error<T> {
         XcmError,
+        /// The reported outcome does not belong to a pending proxied call.
+        ProxyCallNotFound,
+        /// The response is not the outcome of a proxied call.
+        UnexpectedResponse,
+        /// No [RewardAssetTransfer] is configured for the reward asset, or the reward is not fungible.
+        RewardAssetNotTransferable,
+        /// The reward could not be withdrawn from the caller's account.
+        RewardTransferFailed,
+    }
+
+    /// Configures how messages sent to the Acurast parachain pay for their execution.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct XcmExecutionConfig {
+        /// The concrete fungible asset used to pay for the execution, as seen from the Acurast parachain.
+        pub fee_asset: MultiLocation,
+        /// The amount of `fee_asset` withdrawn from this chain's sovereign account on the Acurast parachain.
+        pub fee_amount: u128,
+        /// The maximum weight the `Transact` is allowed to use on the Acurast parachain.
+        pub weight_limit: Weight,
+    }
+
+    /// The configuration for paying the execution of messages sent to the Acurast parachain. Without a configuration, messages
+    /// are sent without paying for their execution.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_xcm_execution_config)]
+    pub type StoredXcmExecutionConfig<T: Config> = StorageValue<_, XcmExecutionConfig>;
+
+    /// How the reward of a job registered with [Pallet::register_with_reward] is transferred to the Acurast parachain.
+    #[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum RewardAssetTransfer {
+        /// The asset is burnt on this chain and minted on the Acurast parachain, which has to trust this chain as a teleporter.
+        Teleport,
+        /// This chain is the reserve of the asset: it is moved to the Acurast parachain's sovereign account on this chain
+        /// and its derivative is minted on the Acurast parachain.
+        ReserveTransfer,
+    }
+
+    /// The assets that can fund the reward of proxied job registrations as a map asset id -> [RewardAssetTransfer], with the
+    /// asset ids as seen from this chain.
+    #[pallet::storage]
+    #[pallet::getter(fn stored_reward_asset_transfer)]
+    pub type StoredRewardAssetTransfer<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetId, RewardAssetTransfer>;
+
+    /// A proxied call waiting for its outcome to be reported by the Acurast parachain.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct PendingProxyCall<AccountId, BlockNumber> {
+        pub extrinsic: ExtrinsicName,
+        pub caller: AccountId,
+        /// The block at which the call times out if no outcome was reported.
+        pub timeout: BlockNumber,
     }
 
+    /// The proxied calls waiting for their outcome as a map query id -> [PendingProxyCall].
+    #[pallet::storage]
+    #[pallet::getter(fn stored_pending_proxy_call)]
+    pub type StoredPendingProxyCall<T: Config> =
+        StorageMap<_, Blake2_128Concat, QueryId, PendingProxyCall<T::AccountId, T::BlockNumber>>;
+
+    /// The timeouts of pending proxied calls as a map block number -> query id -> ().
+    #[pallet::storage]
+    #[pallet::getter(fn stored_proxy_call_timeout)]
+    pub type StoredProxyCallTimeout<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::BlockNumber, Blake2_128Concat, QueryId, ()>;
+
+    /// The calls of `pallet_acurast` that can be proxied, with their call index on the Acurast parachain.
     #[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
-    pub enum ProxyCall<T: Config> {
+    pub enum AcurastCall<T: Config> {
         #[codec(index = 0u8)]
         Register {
             registration: JobRegistration<T::AccountId, T::RegistrationExtra>,
@@ -62,27 +167,122 @@ pub mod pallet {
             updates: Vec<AllowedSourcesUpdate<T::AccountId>>,
         },
 
+        #[codec(index = 5u8)]
+        SubmitAttestation { attestation_chain: AttestationChain },
+    }
+
+    /// The calls of `pallet_acurast_marketplace` that can be proxied, with their call index on the Acurast parachain.
+    #[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+    pub enum MarketplaceCall<T: Config> {
         #[codec(index = 0u8)]
         Advertise {
             advertisement: Advertisement<T::AccountId, T::AssetId, T::AssetAmount>,
         },
+
+        #[codec(index = 1u8)]
+        DeleteAdvertisement,
+
+        #[codec(index = 2u8)]
+        ProposeMatching { matches: Vec<Match<T::AccountId>> },
+
+        #[codec(index = 3u8)]
+        AcknowledgeMatch { job_id: JobId<T::AccountId> },
+
+        #[codec(index = 4u8)]
+        Report {
+            job_id: JobId<T::AccountId>,
+            last: bool,
+            execution_result: ExecutionResult,
+        },
     }
 
+    /// The calls of `pallet_acurast_processor_manager` that can be proxied, with their call index on the Acurast parachain.
     #[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+    pub enum ProcessorManagerCall<T: Config> {
+        #[codec(index = 0u8)]
+        UpdateProcessorPairings {
+            pairing_updates: Vec<ProcessorPairingUpdate<T::AccountId, T::ProcessorPairingProof>>,
+        },
+
+        #[codec(index = 1u8)]
+        PairWithManager {
+            pairing: ProcessorPairing<T::AccountId, T::ProcessorPairingProof>,
+        },
+
+        /// The Acurast parachain looks up accounts with `AccountIdLookup`.
+        #[codec(index = 2u8)]
+        RecoverFunds {
+            processor: MultiAddress<T::AccountId, ()>,
+            destination: MultiAddress<T::AccountId, ()>,
+        },
+
+        #[codec(index = 3u8)]
+        Heartbeat,
+    }
+
+    /// A call proxied to one of the pallets on the Acurast parachain.
+    #[derive(Clone, PartialEq, RuntimeDebug)]
+    pub enum ProxyCall<T: Config> {
+        Acurast(AcurastCall<T>),
+        Marketplace(MarketplaceCall<T>),
+        ProcessorManager(ProcessorManagerCall<T>),
+    }
+
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     pub enum ExtrinsicName {
         Register,
         Deregister,
         UpdateAllowedSources,
         Advertise,
+        DeleteAdvertisement,
+        ProposeMatching,
+        AcknowledgeMatch,
+        Report,
+        SubmitAttestation,
+        UpdateProcessorPairings,
+        PairWithManager,
+        RecoverFunds,
+        Heartbeat,
     }
 
     impl<T: Config> ProxyCall<T> {
         fn get_name(&self) -> ExtrinsicName {
             match self {
-                ProxyCall::Register { .. } => ExtrinsicName::Register,
-                ProxyCall::Deregister { .. } => ExtrinsicName::Deregister,
-                ProxyCall::UpdateAllowedSources { .. } => ExtrinsicName::UpdateAllowedSources,
-                ProxyCall::Advertise { .. } => ExtrinsicName::Advertise,
+                ProxyCall::Acurast(call) => match call {
+                    AcurastCall::Register { .. } => ExtrinsicName::Register,
+                    AcurastCall::Deregister { .. } => ExtrinsicName::Deregister,
+                    AcurastCall::UpdateAllowedSources { .. } => ExtrinsicName::UpdateAllowedSources,
+                    AcurastCall::SubmitAttestation { .. } => ExtrinsicName::SubmitAttestation,
+                },
+                ProxyCall::Marketplace(call) => match call {
+                    MarketplaceCall::Advertise { .. } => ExtrinsicName::Advertise,
+                    MarketplaceCall::DeleteAdvertisement => ExtrinsicName::DeleteAdvertisement,
+                    MarketplaceCall::ProposeMatching { .. } => ExtrinsicName::ProposeMatching,
+                    MarketplaceCall::AcknowledgeMatch { .. } => ExtrinsicName::AcknowledgeMatch,
+                    MarketplaceCall::Report { .. } => ExtrinsicName::Report,
+                },
+                ProxyCall::ProcessorManager(call) => match call {
+                    ProcessorManagerCall::UpdateProcessorPairings { .. } => {
+                        ExtrinsicName::UpdateProcessorPairings
+                    }
+                    ProcessorManagerCall::PairWithManager { .. } => ExtrinsicName::PairWithManager,
+                    ProcessorManagerCall::RecoverFunds { .. } => ExtrinsicName::RecoverFunds,
+                    ProcessorManagerCall::Heartbeat => ExtrinsicName::Heartbeat,
+                },
+            }
+        }
+
+        /// Encodes the call as dispatched on the Acurast parachain: the first byte is the id of the pallet on the Acurast
+        /// parachain, the second byte the call index and the remaining bytes are the SCALE encoded arguments.
+        pub fn encode_call(&self) -> Vec<u8> {
+            match self {
+                ProxyCall::Acurast(call) => (T::AcurastPalletId::get(), call).encode(),
+                ProxyCall::Marketplace(call) => {
+                    (T::AcurastMarketplacePalletId::get(), call).encode()
+                }
+                ProxyCall::ProcessorManager(call) => {
+                    (T::AcurastProcessorManagerPalletId::get(), call).encode()
+                }
             }
         }
     }
@@ -90,7 +290,16 @@ pub mod pallet {
     pub fn acurast_call<T: Config>(
         proxy_call: ProxyCall<T>,
         caller: T::AccountId,
-        pallet_id: u8,
+    ) -> DispatchResult {
+        acurast_call_with_reward::<T>(proxy_call, caller, None)
+    }
+
+    /// Proxies the call like [acurast_call], transferring the `reward` from the caller's account on this chain to the
+    /// caller's account on the Acurast parachain before the call is executed.
+    pub fn acurast_call_with_reward<T: Config>(
+        proxy_call: ProxyCall<T>,
+        caller: T::AccountId,
+        reward: Option<MultiAsset>,
     ) -> DispatchResult {
         // extract bytes from struct
         let account_bytes = caller.encode().try_into().unwrap();
@@ -98,33 +307,122 @@ pub mod pallet {
         let extrinsic = proxy_call.get_name();
 
         // create an encoded version of the call
-        let mut encoded_call = Vec::<u8>::new();
-        // first byte is the pallet id on the destination chain
-        encoded_call.push(pallet_id);
-        //second byte the position of the calling function on the enum,
-        // and then the arguments SCALE encoded in order.
-        encoded_call.append(&mut proxy_call.encode());
+        let encoded_call = proxy_call.encode_call();
+
+        let execution_config = <StoredXcmExecutionConfig<T>>::get();
+
+        // pay for the execution with the parachain's sovereign account, before descending to the user's account
+        if let Some(config) = &execution_config {
+            let fees: MultiAsset = (config.fee_asset, config.fee_amount).into();
+            xcm_message.push(WithdrawAsset(fees.clone().into()));
+            xcm_message.push(BuyExecution {
+                fees,
+                weight_limit: Unlimited,
+            });
+        }
+
+        let acurast_location = acurast_location::<T>();
+        let timeout = <frame_system::Pallet<T>>::block_number()
+            .saturating_add(<T as Config>::ProxyCallTimeout::get());
+        let notify = Call::<T>::handle_response {
+            query_id: 0,
+            response: Response::Null,
+        };
+        let max_weight = notify.get_dispatch_info().weight;
+        // the outcome is reported with the origin descended to the user's account
+        let querier = MultiLocation::new(
+            0,
+            X1(AccountId32 {
+                network: None,
+                id: account_bytes,
+            }),
+        );
+        let query_id = pallet_xcm::Pallet::<T>::new_notify_query(
+            acurast_location,
+            <T as Config>::RuntimeCall::from(notify),
+            timeout,
+            querier,
+        );
+
+        // the appendix is executed even if a later instruction failed: return the unused fees to the parachain's
+        // sovereign account
+        let mut appendix = Vec::new();
+        if execution_config.is_some() {
+            appendix.push(RefundSurplus);
+            appendix.push(DepositAsset {
+                assets: Wild(AllCounted(1)),
+                beneficiary: T::SelfLocation::get(),
+            });
+            xcm_message.push(SetAppendix(Xcm(appendix.clone())));
+        }
+
+        // credit the reward to the user's account, where it is locked when the job is registered
+        if let Some(reward) = reward {
+            let (reward, transfer) = withdraw_reward::<T>(reward, account_bytes)?;
+            xcm_message.push(match transfer {
+                RewardAssetTransfer::Teleport => ReceiveTeleportedAsset(reward.clone().into()),
+                RewardAssetTransfer::ReserveTransfer => {
+                    ReserveAssetDeposited(reward.clone().into())
+                }
+            });
+            xcm_message.push(DepositAsset {
+                assets: reward.into(),
+                beneficiary: MultiLocation::new(
+                    0,
+                    X1(AccountId32 {
+                        network: None,
+                        id: account_bytes,
+                    }),
+                ),
+            });
+        }
 
         // before calling transact, we want to use not the parachain origin, but a user's account
         xcm_message.push(DescendOrigin(X1(AccountId32 {
-            network: NetworkId::Any,
+            network: None,
             id: account_bytes,
         })));
 
+        // from here on, the appendix also reports the outcome of the call, with the descended origin the query expects;
+        // failures before are not reported and the call times out
+        appendix.push(ReportError(QueryResponseInfo {
+            destination: T::SelfLocation::get(),
+            query_id,
+            max_weight,
+        }));
+        xcm_message.push(SetAppendix(Xcm(appendix)));
+
         // put our transact message in the vector of instructions
         xcm_message.push(Transact {
-            origin_type: OriginKind::Xcm,
-            require_weight_at_most: 1_000_000_000u64,
+            origin_kind: OriginKind::Xcm,
+            require_weight_at_most: execution_config
+                .as_ref()
+                .map(|config| config.weight_limit)
+                .unwrap_or_else(T::DefaultTransactWeight::get),
             call: encoded_call.into(),
         });
+        // turn a failed dispatch of the call into an error reported by the appendix
+        xcm_message.push(ExpectTransactStatus(MaybeErrorCode::Success));
 
         // use router to send the xcm message
-        return match T::XcmSender::send_xcm(
-            (1, X1(Parachain(T::AcurastParachainId::get()))),
-            Xcm(xcm_message),
-        ) {
-            Ok(_) => {
-                Pallet::<T>::deposit_event(Event::XcmSent { extrinsic, caller });
+        return match deliver::<T>(acurast_location, Xcm(xcm_message)) {
+            Ok((message_hash, fees)) => {
+                <StoredPendingProxyCall<T>>::insert(
+                    query_id,
+                    PendingProxyCall {
+                        extrinsic: extrinsic.clone(),
+                        caller: caller.clone(),
+                        timeout,
+                    },
+                );
+                <StoredProxyCallTimeout<T>>::insert(timeout, query_id, ());
+                Pallet::<T>::deposit_event(Event::XcmSent {
+                    extrinsic,
+                    caller,
+                    message_hash,
+                    fees,
+                    query_id,
+                });
                 Ok(())
             }
             Err(error) => {
@@ -138,8 +436,136 @@ pub mod pallet {
         };
     }
 
+    fn acurast_location<T: Config>() -> MultiLocation {
+        MultiLocation::new(1, X1(Parachain(T::AcurastParachainId::get())))
+    }
+
+    /// Withdraws the `reward` from the caller's account on this chain according to its [RewardAssetTransfer], returning
+    /// the reward as seen from the Acurast parachain.
+    fn withdraw_reward<T: Config>(
+        reward: MultiAsset,
+        caller: [u8; 32],
+    ) -> Result<(MultiAsset, RewardAssetTransfer), DispatchError> {
+        let transfer = <StoredRewardAssetTransfer<T>>::get(&reward.id)
+            .ok_or(Error::<T>::RewardAssetNotTransferable)?;
+        ensure!(
+            matches!(reward.fun, Fungible(_)),
+            Error::<T>::RewardAssetNotTransferable
+        );
+        let acurast_location = acurast_location::<T>();
+        let reanchored = reward
+            .clone()
+            .reanchored(
+                &acurast_location,
+                <T as pallet_xcm::Config>::UniversalLocation::get(),
+            )
+            .map_err(|()| Error::<T>::RewardAssetNotTransferable)?;
+
+        let origin = MultiLocation::new(
+            0,
+            X1(AccountId32 {
+                network: None,
+                id: caller,
+            }),
+        );
+        let mut message = reward_withdrawal::<T>(reward.clone(), transfer);
+        let weight = <T as pallet_xcm::Config>::Weigher::weight(&mut message)
+            .map_err(|()| Error::<T>::RewardTransferFailed)?;
+        let hash = message.using_encoded(frame_support::sp_io::hashing::blake2_256);
+        let context = XcmContext {
+            origin: Some(origin),
+            message_hash: hash,
+            topic: None,
+        };
+        // a teleported reward leaves the chain like with `InitiateTeleport`, which checks it out of the checking account
+        if transfer == RewardAssetTransfer::Teleport {
+            T::AssetTransactor::can_check_out(&acurast_location, &reward, &context)
+                .map_err(|_| Error::<T>::RewardTransferFailed)?;
+        }
+        <T as pallet_xcm::Config>::XcmExecutor::execute_xcm_in_credit(
+            origin, message, hash, weight, weight,
+        )
+        .ensure_complete()
+        .map_err(|_| Error::<T>::RewardTransferFailed)?;
+        if transfer == RewardAssetTransfer::Teleport {
+            T::AssetTransactor::check_out(&acurast_location, &reward, &context);
+        }
+
+        Ok((reanchored, transfer))
+    }
+
+    /// The local message withdrawing the `reward` from the caller's account according to its `transfer`.
+    ///
+    /// Teleported rewards are burnt, rewards this chain is the reserve of are moved to the Acurast parachain's sovereign account.
+    fn reward_withdrawal<T: Config>(
+        reward: MultiAsset,
+        transfer: RewardAssetTransfer,
+    ) -> Xcm<<T as pallet_xcm::Config>::RuntimeCall> {
+        Xcm(vec![
+            WithdrawAsset(reward.clone().into()),
+            match transfer {
+                RewardAssetTransfer::Teleport => BurnAsset(reward.into()),
+                RewardAssetTransfer::ReserveTransfer => DepositAsset {
+                    assets: reward.into(),
+                    beneficiary: acurast_location::<T>(),
+                },
+            },
+        ])
+    }
+
+    /// The weight of withdrawing the `reward` from the caller's account, the maximum over all [RewardAssetTransfer]s.
+    ///
+    /// Includes looking up the [RewardAssetTransfer] and checking teleported rewards out of the teleport checking account.
+    pub fn reward_withdrawal_weight<T: Config>(reward: &MultiAsset) -> Weight {
+        let weight = [
+            RewardAssetTransfer::Teleport,
+            RewardAssetTransfer::ReserveTransfer,
+        ]
+        .into_iter()
+        .map(|transfer| {
+            <T as pallet_xcm::Config>::Weigher::weight(&mut reward_withdrawal::<T>(
+                reward.clone(),
+                transfer,
+            ))
+            .unwrap_or_default()
+        })
+        .fold(Weight::zero(), Weight::max);
+        // reads: StoredRewardAssetTransfer and the checking account, writes: the checking account
+        weight.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+    }
+
+    /// The weight of proxying a call with [acurast_call]: building the message, registering the query for its outcome,
+    /// delivering the message with [Config::XcmSender] and storing the pending call.
+    pub fn proxy_call_weight<T: Config>() -> Weight {
+        // reads: StoredXcmExecutionConfig, PolkadotXcm QueryCounter and SupportedVersion
+        // writes: PolkadotXcm QueryCounter, Queries and VersionDiscoveryQueue, StoredPendingProxyCall, StoredProxyCallTimeout
+        T::XcmSendWeight::get()
+            .saturating_add(Weight::from_ref_time(10_000))
+            .saturating_add(T::DbWeight::get().reads_writes(3, 5))
+    }
+
+    /// Delivers the `message` to `destination`, returning its hash and the delivery fees charged by the router.
+    ///
+    /// Messages are built with XCM v3. If the Acurast parachain negotiated v2, the router sends the message downgraded,
+    /// so it is only sent if it can be expressed in the version negotiated with `destination`. Proxied calls report their
+    /// outcome with instructions only available in XCM v3.
+    fn deliver<T: Config>(
+        destination: MultiLocation,
+        message: Xcm<()>,
+    ) -> Result<(XcmHash, MultiAssets), SendError> {
+        T::VersionWrapper::wrap_version::<()>(&destination, message.clone())
+            .map_err(|()| SendError::DestinationUnsupported)?;
+        let (ticket, fees) = validate_send::<T::XcmSender>(destination, message)?;
+        let message_hash = T::XcmSender::deliver(ticket)?;
+        Ok((message_hash, fees))
+    }
+
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::event]
@@ -148,64 +574,315 @@ pub mod pallet {
         XcmSent {
             extrinsic: ExtrinsicName,
             caller: T::AccountId,
+            message_hash: XcmHash,
+            fees: MultiAssets,
+            query_id: QueryId,
         },
         XcmNotSent {
             extrinsic: ExtrinsicName,
             error: SendError,
             caller: T::AccountId,
         },
+        XcmExecutionConfigUpdated {
+            config: Option<XcmExecutionConfig>,
+        },
+        RewardAssetTransferUpdated {
+            asset_id: AssetId,
+            transfer: Option<RewardAssetTransfer>,
+        },
+        /// The Acurast parachain reported the successful execution of the proxied call `query_id`.
+        ProxyCallSucceeded {
+            query_id: QueryId,
+            extrinsic: ExtrinsicName,
+            caller: T::AccountId,
+        },
+        /// The Acurast parachain reported that the proxied call `query_id` failed at the instruction `index`.
+        ProxyCallFailed {
+            query_id: QueryId,
+            extrinsic: ExtrinsicName,
+            caller: T::AccountId,
+            index: u32,
+            error: XcmError,
+        },
+        /// The outcome of the proxied call `query_id` was not reported before its timeout.
+        ProxyCallTimedOut {
+            query_id: QueryId,
+            extrinsic: ExtrinsicName,
+            caller: T::AccountId,
+        },
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::migrate::<T>()
+        }
+
+        fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+            let mut weight = Weight::zero();
+            for (query_id, _) in <StoredProxyCallTimeout<T>>::drain_prefix(block_number) {
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+                // the outcome might have been reported in the meantime
+                if let Some(pending) = <StoredPendingProxyCall<T>>::take(query_id) {
+                    Self::deposit_event(Event::ProxyCallTimedOut {
+                        query_id,
+                        extrinsic: pending.extrinsic,
+                        caller: pending.caller,
+                    });
+                }
+            }
+            weight
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Registers a job by providing a [Registration]. If a job for the same script was previously registered, it will be overwritten.
-        // TODO: Define proxy weight
         #[pallet::call_index(0)]
-        #[pallet::weight(10_000)]
+        #[pallet::weight(proxy_call_weight::<T>())]
         pub fn register(
             origin: OriginFor<T>,
             registration: JobRegistration<T::AccountId, T::RegistrationExtra>,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-            let proxy_call = ProxyCall::Register { registration };
-            acurast_call::<T>(proxy_call, caller, T::AcurastPalletId::get())
+            let proxy_call = ProxyCall::Acurast(AcurastCall::Register { registration });
+            acurast_call::<T>(proxy_call, caller)
         }
 
         /// Deregisters a job for the given script.
         #[pallet::call_index(1)]
-        #[pallet::weight(10_000)]
+        #[pallet::weight(proxy_call_weight::<T>())]
         pub fn deregister(origin: OriginFor<T>, script: Script) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-            let proxy_call = ProxyCall::Deregister { script };
-            acurast_call::<T>(proxy_call, caller, T::AcurastPalletId::get())
+            let proxy_call = ProxyCall::Acurast(AcurastCall::Deregister { script });
+            acurast_call::<T>(proxy_call, caller)
         }
 
         /// Updates the allowed sources list of a [Registration].
         #[pallet::call_index(2)]
-        #[pallet::weight(10_000)]
+        #[pallet::weight(proxy_call_weight::<T>())]
         pub fn update_allowed_sources(
             origin: OriginFor<T>,
             script: Script,
             updates: Vec<AllowedSourcesUpdate<T::AccountId>>,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-            let proxy_call = ProxyCall::UpdateAllowedSources { script, updates };
-            acurast_call::<T>(proxy_call, caller, T::AcurastPalletId::get())
+            let proxy_call =
+                ProxyCall::Acurast(AcurastCall::UpdateAllowedSources { script, updates });
+            acurast_call::<T>(proxy_call, caller)
         }
 
         /// Advertise resources by providing a [Advertisement]. If an advertisement for the same script was previously registered, it will be overwritten.
         #[pallet::call_index(4)]
-        #[pallet::weight(10_000)]
+        #[pallet::weight(proxy_call_weight::<T>())]
         pub fn advertise(
             origin: OriginFor<T>,
             advertisement: Advertisement<T::AccountId, T::AssetId, T::AssetAmount>,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-            let proxy_call = ProxyCall::Advertise { advertisement };
-            acurast_call::<T>(proxy_call, caller, T::AcurastMarketplacePalletId::get())
+            let proxy_call = ProxyCall::Marketplace(MarketplaceCall::Advertise { advertisement });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Sets or removes the [XcmExecutionConfig] for messages sent to the Acurast parachain. Can only be called by root.
+        #[pallet::call_index(5)]
+        #[pallet::weight(Weight::from_ref_time(10_000).saturating_add(T::DbWeight::get().writes(1)))]
+        pub fn set_xcm_execution_config(
+            origin: OriginFor<T>,
+            config: Option<XcmExecutionConfig>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            <StoredXcmExecutionConfig<T>>::set(config.clone());
+            Self::deposit_event(Event::XcmExecutionConfigUpdated { config });
+            Ok(())
+        }
+
+        /// Records the outcome of a proxied call reported by the Acurast parachain. Called by `pallet_xcm` when the response
+        /// to the query `query_id` arrives.
+        #[pallet::call_index(6)]
+        #[pallet::weight(Weight::from_ref_time(10_000).saturating_add(T::DbWeight::get().reads_writes(1, 2)))]
+        pub fn handle_response(
+            origin: OriginFor<T>,
+            query_id: QueryId,
+            response: Response,
+        ) -> DispatchResult {
+            let responder =
+                pallet_xcm::ensure_response(<T as Config>::RuntimeOrigin::from(origin))?;
+            ensure!(
+                responder == acurast_location::<T>(),
+                DispatchError::BadOrigin
+            );
+            let outcome = match response {
+                Response::ExecutionResult(outcome) => outcome,
+                _ => return Err(Error::<T>::UnexpectedResponse.into()),
+            };
+            let pending =
+                <StoredPendingProxyCall<T>>::take(query_id).ok_or(Error::<T>::ProxyCallNotFound)?;
+            <StoredProxyCallTimeout<T>>::remove(pending.timeout, query_id);
+
+            Self::deposit_event(match outcome {
+                None => Event::ProxyCallSucceeded {
+                    query_id,
+                    extrinsic: pending.extrinsic,
+                    caller: pending.caller,
+                },
+                Some((index, error)) => Event::ProxyCallFailed {
+                    query_id,
+                    extrinsic: pending.extrinsic,
+                    caller: pending.caller,
+                    index,
+                    error,
+                },
+            });
+            Ok(())
+        }
+
+        /// Deletes the advertisement of the caller.
+        #[pallet::call_index(7)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn delete_advertisement(origin: OriginFor<T>) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::Marketplace(MarketplaceCall::DeleteAdvertisement);
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Proposes [Match]es of jobs to sources.
+        #[pallet::call_index(8)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn propose_matching(
+            origin: OriginFor<T>,
+            matches: Vec<Match<T::AccountId>>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::Marketplace(MarketplaceCall::ProposeMatching { matches });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Acknowledges the match of the job `job_id` with the caller as source.
+        #[pallet::call_index(9)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn acknowledge_match(
+            origin: OriginFor<T>,
+            job_id: JobId<T::AccountId>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::Marketplace(MarketplaceCall::AcknowledgeMatch { job_id });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Reports the [ExecutionResult] of an execution of the job `job_id` by the caller as source.
+        #[pallet::call_index(10)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn report(
+            origin: OriginFor<T>,
+            job_id: JobId<T::AccountId>,
+            last: bool,
+            execution_result: ExecutionResult,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::Marketplace(MarketplaceCall::Report {
+                job_id,
+                last,
+                execution_result,
+            });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Submits an [AttestationChain] for the caller.
+        #[pallet::call_index(11)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn submit_attestation(
+            origin: OriginFor<T>,
+            attestation_chain: AttestationChain,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call =
+                ProxyCall::Acurast(AcurastCall::SubmitAttestation { attestation_chain });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Updates the processors paired with the caller as manager.
+        #[pallet::call_index(12)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn update_processor_pairings(
+            origin: OriginFor<T>,
+            pairing_updates: Vec<ProcessorPairingUpdate<T::AccountId, T::ProcessorPairingProof>>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call =
+                ProxyCall::ProcessorManager(ProcessorManagerCall::UpdateProcessorPairings {
+                    pairing_updates,
+                });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Pairs the caller as processor with the manager of the [ProcessorPairing].
+        #[pallet::call_index(13)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn pair_with_manager(
+            origin: OriginFor<T>,
+            pairing: ProcessorPairing<T::AccountId, T::ProcessorPairingProof>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call =
+                ProxyCall::ProcessorManager(ProcessorManagerCall::PairWithManager { pairing });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Recovers the funds of a `processor` managed by the caller to `destination`.
+        #[pallet::call_index(14)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn recover_funds(
+            origin: OriginFor<T>,
+            processor: T::AccountId,
+            destination: T::AccountId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::ProcessorManager(ProcessorManagerCall::RecoverFunds {
+                processor: MultiAddress::Id(processor),
+                destination: MultiAddress::Id(destination),
+            });
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Sends a heartbeat for the caller as processor.
+        #[pallet::call_index(15)]
+        #[pallet::weight(proxy_call_weight::<T>())]
+        pub fn heartbeat(origin: OriginFor<T>) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::ProcessorManager(ProcessorManagerCall::Heartbeat);
+            acurast_call::<T>(proxy_call, caller)
+        }
+
+        /// Sets or removes the [RewardAssetTransfer] of the asset `asset_id`, as seen from this chain. Can only be called by root.
+        #[pallet::call_index(16)]
+        #[pallet::weight(Weight::from_ref_time(10_000).saturating_add(T::DbWeight::get().writes(1)))]
+        pub fn set_reward_asset_transfer(
+            origin: OriginFor<T>,
+            asset_id: AssetId,
+            transfer: Option<RewardAssetTransfer>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            <StoredRewardAssetTransfer<T>>::set(&asset_id, transfer);
+            Self::deposit_event(Event::RewardAssetTransferUpdated { asset_id, transfer });
+            Ok(())
+        }
+
+        /// Registers a job like [Pallet::register], funding its reward from the caller's account on this chain.
+        ///
+        /// The `reward`, as seen from this chain, is transferred to the caller's account on the Acurast parachain in the
+        /// same message as the registration, so the reward can be locked when the job is registered. Any part of the
+        /// `reward` not locked remains in the caller's account on the Acurast parachain.
+        #[pallet::call_index(17)]
+        #[pallet::weight(proxy_call_weight::<T>().saturating_add(reward_withdrawal_weight::<T>(reward)))]
+        pub fn register_with_reward(
+            origin: OriginFor<T>,
+            registration: JobRegistration<T::AccountId, T::RegistrationExtra>,
+            reward: MultiAsset,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proxy_call = ProxyCall::Acurast(AcurastCall::Register { registration });
+            acurast_call_with_reward::<T>(proxy_call, caller, Some(reward))
         }
     }
 }
@@ -0,0 +1,65 @@
+use frame_support::{
+    traits::{Get, GetStorageVersion},
+    weights::Weight,
+};
+
+use crate::{Config, Pallet};
+
+/// Translates the [crate::StoredXcmExecutionConfig] from XCM v2 to XCM v3, which is used since version 1.
+///
+/// A configuration whose fee asset cannot be expressed in XCM v3 is dropped and has to be set again.
+pub mod v1 {
+    use frame_support::{log, pallet_prelude::*};
+    use xcm::{v2::MultiLocation as OldMultiLocation, v3::MultiLocation};
+
+    use super::*;
+    use crate::{StoredXcmExecutionConfig, XcmExecutionConfig};
+
+    /// The execution configuration as stored before version 1.
+    #[derive(Encode, Decode)]
+    pub struct OldXcmExecutionConfig {
+        pub fee_asset: OldMultiLocation,
+        pub fee_amount: u128,
+        pub weight_limit: u64,
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let result =
+            <StoredXcmExecutionConfig<T>>::translate(|old: Option<OldXcmExecutionConfig>| {
+                old.and_then(|old| {
+                    let fee_asset = MultiLocation::try_from(old.fee_asset).ok();
+                    if fee_asset.is_none() {
+                        log::warn!(
+                            target: "runtime::acurast_proxy",
+                            "dropped execution config not expressible in XCM v3"
+                        );
+                    }
+                    fee_asset.map(|fee_asset| XcmExecutionConfig {
+                        fee_asset,
+                        fee_amount: old.fee_amount,
+                        weight_limit: Weight::from_ref_time(old.weight_limit),
+                    })
+                })
+            });
+        if result.is_err() {
+            log::warn!(
+                target: "runtime::acurast_proxy",
+                "failed to decode execution config stored before version 1"
+            );
+        }
+        T::DbWeight::get().reads_writes(1, 1)
+    }
+}
+
+/// Runs the migrations required to bring the storage of the pallet to its in-code version.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+
+    if on_chain_version < 1 {
+        weight = weight.saturating_add(v1::migrate::<T>());
+    }
+
+    Pallet::<T>::current_storage_version().put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
@@ -23,14 +23,14 @@ impl Reward for AcurastAsset {
 
     fn with_amount(&mut self, amount: Self::AssetAmount) -> Result<&Self, Self::Error> {
         self.0 = MultiAsset {
-            id: self.0.id.clone(),
+            id: self.0.id,
             fun: Fungible(amount),
         };
         Ok(self)
     }
 
     fn try_get_asset_id(&self) -> Result<Self::AssetId, Self::Error> {
-        Ok(self.0.id.clone())
+        Ok(self.0.id)
     }
 
     fn try_get_amount(&self) -> Result<Self::AssetAmount, Self::Error> {
@@ -43,29 +43,32 @@ impl Reward for AcurastAsset {
 
 pub mod acurast_runtime {
     use frame_support::{
-        construct_runtime, parameter_types,
+        construct_runtime, match_types, parameter_types,
         sp_runtime::{testing::Header, traits::AccountIdLookup, AccountId32},
-        traits::{AsEnsureOriginWithArg, Everything, Nothing},
+        traits::{AsEnsureOriginWithArg, ConstU128, ConstU32, ContainsPair, Everything, Nothing},
+        weights::Weight,
         PalletId,
     };
     use pallet_xcm::XcmPassthrough;
     use polkadot_parachain::primitives::Sibling;
     use sp_core::*;
-    use sp_runtime::DispatchError;
+    use sp_runtime::{DispatchError, DispatchResult, MultiSignature};
     use sp_std::prelude::*;
     use xcm::latest::prelude::*;
     use xcm_builder::{
-        AccountId32Aliases, AllowUnpaidExecutionFrom, CurrencyAdapter as XcmCurrencyAdapter,
-        EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds, IsConcrete, LocationInverter,
-        NativeAsset, ParentIsPreset, SiblingParachainConvertsVia, SignedAccountId32AsNative,
+        AccountId32Aliases, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
+        ConvertedConcreteId, CurrencyAdapter as XcmCurrencyAdapter, EnsureXcmOrigin,
+        FixedRateOfFungible, FixedWeightBounds, FungiblesAdapter, IsConcrete, NativeAsset,
+        NoChecking, ParentIsPreset, SiblingParachainConvertsVia, SignedAccountId32AsNative,
         SignedToAccountId32, SovereignSignedViaLocation,
     };
-    use xcm_executor::XcmExecutor;
+    use xcm_executor::{traits::JustTry, XcmExecutor};
 
     pub use pallet_acurast;
     use pallet_acurast_assets::traits::AssetValidator;
     pub use pallet_acurast_marketplace;
     use pallet_acurast_marketplace::{AssetBarrier, AssetRewardManager, JobRequirements};
+    pub use pallet_acurast_processor_manager;
 
     use super::{AcurastAsset, AcurastAssetAmount, AcurastAssetId, InternalAssetId};
 
@@ -80,8 +83,21 @@ pub mod acurast_runtime {
     );
     pub type LocalAssetTransactor =
         XcmCurrencyAdapter<Balances, IsConcrete<KsmLocation>, LocationToAccountId, AccountId, ()>;
+    /// Transacts the assets indexed by `pallet_acurast_assets`.
+    pub type FungiblesTransactor = FungiblesAdapter<
+        Assets,
+        ConvertedConcreteId<InternalAssetId, AcurastAssetAmount, AcurastAssets, JustTry>,
+        LocationToAccountId,
+        AccountId,
+        NoChecking,
+        CheckAccount,
+    >;
+    pub type AssetTransactors = (LocalAssetTransactor, FungiblesTransactor);
     pub type XcmRouter = crate::tests::ParachainXcmRouter<MsgQueue>;
-    pub type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    pub type Barrier = (
+        AllowTopLevelPaidExecutionFrom<Everything>,
+        AllowUnpaidExecutionFrom<ParentLocation>,
+    );
     pub type XcmOriginToCallOrigin = (
         SovereignSignedViaLocation<LocationToAccountId, RuntimeOrigin>,
         SignedAccountId32AsNative<RelayNetwork, RuntimeOrigin>,
@@ -90,6 +106,21 @@ pub mod acurast_runtime {
         XcmPassthrough<RuntimeOrigin>,
     );
 
+    /// Trusts sibling parachains as the reserve of the assets they define, such as the assets of their `pallet_assets`.
+    pub struct SiblingAssets;
+
+    impl ContainsPair<MultiAsset, MultiLocation> for SiblingAssets {
+        fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+            matches!(
+                origin,
+                MultiLocation {
+                    parents: 1,
+                    interior: X1(Parachain(_))
+                }
+            ) && matches!(&asset.id, Concrete(location) if location.starts_with(origin))
+        }
+    }
+
     pub struct AcurastBarrier;
 
     impl AssetBarrier<AcurastAsset> for AcurastBarrier {
@@ -126,8 +157,9 @@ pub mod acurast_runtime {
             ParachainInfo: parachain_info::{Pallet, Storage, Config},
             MsgQueue: super::mock_msg_queue::{Pallet, Storage, Event<T>},
             PolkadotXcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
-            Acurast: pallet_acurast::{Pallet, Call, Storage, Event<T>} = 40,
+            Acurast: pallet_acurast::{Pallet, Call, Storage, Event<T>, Config} = 40,
             AcurastMarketplace: pallet_acurast_marketplace::{Pallet, Call, Storage, Event<T>} = 41,
+            AcurastProcessorManager: pallet_acurast_processor_manager::{Pallet, Call, Storage, Event<T>} = 42,
         }
     );
 
@@ -136,6 +168,14 @@ pub mod acurast_runtime {
         pub const IsRelay: bool = false;
         pub const AcurastPalletId: PalletId = PalletId(*b"acrstpid");
         pub const ReportTolerance: u64 = 12000;
+        pub const MaxAutoMatchCandidates: u32 = 10;
+        pub const MinReputationHistory: u64 = 2;
+        pub const JobStatusRetention: u64 = 100;
+        pub const AcknowledgementTimeout: u64 = 10;
+        pub const ClawBackMatcherReward: bool = true;
+        pub const OutputDepositBase: AcurastAssetAmount = 1_000;
+        pub const OutputDepositPerByte: AcurastAssetAmount = 10;
+        pub const OutputRetention: u64 = 100;
     }
     parameter_types! {
         pub const BlockHashCount: u64 = 250;
@@ -148,12 +188,19 @@ pub mod acurast_runtime {
     parameter_types! {
         pub const KsmLocation: MultiLocation = MultiLocation::parent();
         pub const RelayNetwork: NetworkId = NetworkId::Kusama;
-        pub Ancestry: MultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
+        pub UniversalLocation: InteriorMultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
+        pub CheckAccount: AccountId = PolkadotXcm::check_account();
+    }
+    match_types! {
+        pub type ParentLocation: impl Contains<MultiLocation> = {
+            MultiLocation { parents: 1, interior: Here }
+        };
     }
     parameter_types! {
-        pub const UnitWeightCost: u64 = 1;
-        pub KsmPerSecond: (AssetId, u128) = (Concrete(Parent.into()), 1);
+        pub const UnitWeightCost: Weight = Weight::from_ref_time(1);
+        pub KsmPerSecondPerByte: (AssetId, u128, u128) = (Concrete(Parent.into()), 1, 1);
         pub const MaxInstructions: u32 = 100;
+        pub const MaxAssetsIntoHolding: u32 = 64;
     }
 
     pub struct XcmConfig;
@@ -161,18 +208,27 @@ pub mod acurast_runtime {
     impl xcm_executor::Config for XcmConfig {
         type RuntimeCall = RuntimeCall;
         type XcmSender = XcmRouter;
-        type AssetTransactor = LocalAssetTransactor;
+        type AssetTransactor = AssetTransactors;
         type OriginConverter = XcmOriginToCallOrigin;
-        type IsReserve = NativeAsset;
+        type IsReserve = (NativeAsset, SiblingAssets);
         type IsTeleporter = ();
-        type LocationInverter = LocationInverter<Ancestry>;
+        type UniversalLocation = UniversalLocation;
         type Barrier = Barrier;
         type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-        type Trader = FixedRateOfFungible<KsmPerSecond, ()>;
+        type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
         type ResponseHandler = ();
         type AssetTrap = ();
         type AssetClaims = ();
         type SubscriptionService = ();
+        type AssetLocker = ();
+        type AssetExchanger = ();
+        type PalletInstancesInfo = ();
+        type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+        type FeeManager = ();
+        type MessageExporter = ();
+        type UniversalAliases = Nothing;
+        type CallDispatcher = RuntimeCall;
+        type SafeCallFilter = Everything;
     }
 
     impl pallet_balances::Config for Runtime {
@@ -285,9 +341,54 @@ pub mod acurast_runtime {
         type AssetAmount = AcurastAssetAmount;
         type RewardManager = AssetRewardManager<AcurastAsset, AcurastBarrier, FeeManagerImpl>;
         type AssetValidator = PassAllAssets;
+        type MinReputationHistory = MinReputationHistory;
+        type MaxAutoMatchCandidates = MaxAutoMatchCandidates;
+        type JobStatusRetention = JobStatusRetention;
+        type AcknowledgementTimeout = AcknowledgementTimeout;
+        type ClawBackMatcherReward = ClawBackMatcherReward;
+        type Currency = Balances;
+        type OutputDepositBase = OutputDepositBase;
+        type OutputDepositPerByte = OutputDepositPerByte;
+        type OutputRetention = OutputRetention;
         type WeightInfo = pallet_acurast_marketplace::weights::Weights<Runtime>;
     }
 
+    /// Manager ids are not tracked, every pairing update creates a new manager.
+    pub struct ManagerIdProvider;
+
+    impl pallet_acurast_processor_manager::ManagerIdProvider<Runtime> for ManagerIdProvider {
+        fn create_manager_id(_id: u128, _owner: &AccountId) -> DispatchResult {
+            Ok(())
+        }
+
+        fn manager_id_for(_owner: &AccountId) -> Result<u128, DispatchError> {
+            Err(DispatchError::Other("manager ids are not tracked"))
+        }
+
+        fn owner_for(_manager_id: u128) -> Result<AccountId, DispatchError> {
+            Err(DispatchError::Other("manager ids are not tracked"))
+        }
+    }
+
+    impl pallet_acurast_processor_manager::ProcessorAssetRecovery<Runtime> for ManagerIdProvider {
+        fn recover_assets(_processor: &AccountId, _destination: &AccountId) -> DispatchResult {
+            Ok(())
+        }
+    }
+
+    impl pallet_acurast_processor_manager::Config for Runtime {
+        type RuntimeEvent = RuntimeEvent;
+        type Proof = MultiSignature;
+        type ManagerId = u128;
+        type ManagerIdProvider = ManagerIdProvider;
+        type ProcessorAssetRecovery = ManagerIdProvider;
+        type MaxPairingUpdates = ConstU32<5>;
+        type Counter = u64;
+        type PairingProofExpirationTime = ConstU128<600000>;
+        type UnixTime = pallet_timestamp::Pallet<Runtime>;
+        type WeightInfo = ();
+    }
+
     impl pallet_xcm::Config for Runtime {
         type RuntimeEvent = RuntimeEvent;
         type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
@@ -298,11 +399,16 @@ pub mod acurast_runtime {
         type XcmTeleportFilter = Nothing;
         type XcmReserveTransferFilter = Everything;
         type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-        type LocationInverter = LocationInverter<Ancestry>;
+        type UniversalLocation = UniversalLocation;
         type RuntimeOrigin = RuntimeOrigin;
         type RuntimeCall = RuntimeCall;
         const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
         type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+        type Currency = Balances;
+        type CurrencyMatcher = ();
+        type TrustedLockers = ();
+        type SovereignAccountOf = LocationToAccountId;
+        type MaxLockers = ConstU32<8>;
     }
 
     impl super::mock_msg_queue::Config for Runtime {
@@ -314,7 +420,8 @@ pub mod acurast_runtime {
 pub mod proxy_runtime {
     use frame_support::{
         construct_runtime, parameter_types,
-        traits::{Everything, Nothing},
+        traits::{AsEnsureOriginWithArg, ConstU128, ConstU32, Everything, Nothing},
+        weights::Weight,
     };
     use pallet_xcm::XcmPassthrough;
     use polkadot_parachain::primitives::Sibling;
@@ -323,16 +430,17 @@ pub mod proxy_runtime {
     use sp_std::prelude::*;
     use xcm::latest::prelude::*;
     use xcm_builder::{
-        AccountId32Aliases, AllowUnpaidExecutionFrom, CurrencyAdapter as XcmCurrencyAdapter,
-        EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds, IsConcrete, LocationInverter,
-        NativeAsset, ParentIsPreset, SiblingParachainConvertsVia, SignedAccountId32AsNative,
-        SignedToAccountId32, SovereignSignedViaLocation,
+        AccountId32Aliases, AllowUnpaidExecutionFrom, AsPrefixedGeneralIndex, ConvertedConcreteId,
+        CurrencyAdapter as XcmCurrencyAdapter, EnsureXcmOrigin, FixedRateOfFungible,
+        FixedWeightBounds, FungiblesAdapter, IsConcrete, NativeAsset, NoChecking, ParentIsPreset,
+        SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
+        SovereignSignedViaLocation,
     };
-    use xcm_executor::{Config, XcmExecutor};
+    use xcm_executor::{traits::JustTry, Config, XcmExecutor};
 
     use pallet_acurast_marketplace::JobRequirements;
 
-    use crate::mock::{AcurastAsset, AcurastAssetAmount, AcurastAssetId};
+    use crate::mock::{AcurastAsset, AcurastAssetAmount, AcurastAssetId, InternalAssetId};
 
     pub type AccountId = AccountId32;
     pub type LocationToAccountId = (
@@ -352,6 +460,21 @@ pub mod proxy_runtime {
     );
     pub type LocalAssetTransactor =
         XcmCurrencyAdapter<Balances, IsConcrete<KsmLocation>, LocationToAccountId, AccountId, ()>;
+    /// Transacts the assets of `pallet_assets`, identified as `(0, X2(PalletInstance(50), GeneralIndex(id)))`.
+    pub type FungiblesTransactor = FungiblesAdapter<
+        Assets,
+        ConvertedConcreteId<
+            InternalAssetId,
+            AcurastAssetAmount,
+            AsPrefixedGeneralIndex<AssetsPalletLocation, InternalAssetId, JustTry>,
+            JustTry,
+        >,
+        LocationToAccountId,
+        AccountId,
+        NoChecking,
+        CheckAccount,
+    >;
+    pub type AssetTransactors = (LocalAssetTransactor, FungiblesTransactor);
     pub type XcmRouter = crate::tests::ParachainXcmRouter<MsgQueue>;
     pub type Barrier = AllowUnpaidExecutionFrom<Everything>;
 
@@ -360,18 +483,27 @@ pub mod proxy_runtime {
     impl Config for XcmConfig {
         type RuntimeCall = RuntimeCall;
         type XcmSender = XcmRouter;
-        type AssetTransactor = LocalAssetTransactor;
+        type AssetTransactor = AssetTransactors;
         type OriginConverter = XcmOriginToCallOrigin;
         type IsReserve = NativeAsset;
         type IsTeleporter = ();
-        type LocationInverter = LocationInverter<Ancestry>;
+        type UniversalLocation = UniversalLocation;
         type Barrier = Barrier;
         type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-        type Trader = FixedRateOfFungible<KsmPerSecond, ()>;
-        type ResponseHandler = ();
+        type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
+        type ResponseHandler = PolkadotXcm;
         type AssetTrap = ();
         type AssetClaims = ();
         type SubscriptionService = ();
+        type AssetLocker = ();
+        type AssetExchanger = ();
+        type PalletInstancesInfo = ();
+        type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+        type FeeManager = ();
+        type MessageExporter = ();
+        type UniversalAliases = Nothing;
+        type CallDispatcher = RuntimeCall;
+        type SafeCallFilter = Everything;
     }
 
     pub const MILLISECS_PER_BLOCK: u64 = 12000;
@@ -386,8 +518,9 @@ pub mod proxy_runtime {
             System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
             Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
             MsgQueue: super::mock_msg_queue::{Pallet, Storage, Event<T>},
-            PolkadotXcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
-            AcurastProxy: crate::{Pallet, Call, Event<T>} = 34,
+            PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config},
+            AcurastProxy: crate::{Pallet, Call, Storage, Event<T>} = 34,
+            Assets: pallet_assets::{Pallet, Call, Storage, Event<T>, Config<T>} = 50,
         }
     );
 
@@ -405,19 +538,27 @@ pub mod proxy_runtime {
         pub Admins: Vec<AccountId> = vec![];
     }
     parameter_types! {
-        pub const UnitWeightCost: u64 = 1;
-        pub KsmPerSecond: (AssetId, u128) = (Concrete(Parent.into()), 1);
+        pub const UnitWeightCost: Weight = Weight::from_ref_time(1);
+        pub KsmPerSecondPerByte: (AssetId, u128, u128) = (Concrete(Parent.into()), 1, 1);
         pub const MaxInstructions: u32 = 100;
+        pub const MaxAssetsIntoHolding: u32 = 64;
     }
     parameter_types! {
         pub const AcurastParachainId: u32 = 2000;
         pub const AcurastPalletId: u8 = 40;
         pub const AcurastMarketplacePalletId: u8 = 41;
+        pub const AcurastProcessorManagerPalletId: u8 = 42;
+        pub SelfLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(MsgQueue::parachain_id().into())));
+        pub const DefaultTransactWeight: Weight = Weight::from_ref_time(1_000_000_000);
+        pub const ProxyCallTimeout: u64 = 10;
+        pub const XcmSendWeight: Weight = Weight::from_ref_time(1_000_000);
+        pub AssetsPalletLocation: MultiLocation = PalletInstance(50).into();
     }
     parameter_types! {
         pub const KsmLocation: MultiLocation = MultiLocation::parent();
         pub const RelayNetwork: NetworkId = NetworkId::Kusama;
-        pub Ancestry: MultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
+        pub UniversalLocation: InteriorMultiLocation = Parachain(MsgQueue::parachain_id().into()).into();
+        pub CheckAccount: AccountId = PolkadotXcm::check_account();
     }
 
     impl frame_system::Config for Runtime {
@@ -474,22 +615,37 @@ pub mod proxy_runtime {
         type XcmTeleportFilter = Nothing;
         type XcmReserveTransferFilter = Everything;
         type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-        type LocationInverter = LocationInverter<Ancestry>;
+        type UniversalLocation = UniversalLocation;
         type RuntimeOrigin = RuntimeOrigin;
         type RuntimeCall = RuntimeCall;
         const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
         type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+        type Currency = Balances;
+        type CurrencyMatcher = ();
+        type TrustedLockers = ();
+        type SovereignAccountOf = LocationToAccountId;
+        type MaxLockers = ConstU32<8>;
     }
 
     impl crate::Config for Runtime {
         type RuntimeEvent = RuntimeEvent;
+        type RuntimeCall = RuntimeCall;
+        type RuntimeOrigin = RuntimeOrigin;
         type RegistrationExtra = JobRequirements<AcurastAsset, AccountId>;
         type AssetId = AcurastAssetId;
         type AssetAmount = AcurastAssetAmount;
         type XcmSender = XcmRouter;
+        type VersionWrapper = PolkadotXcm;
         type AcurastPalletId = AcurastPalletId;
         type AcurastMarketplacePalletId = AcurastMarketplacePalletId;
+        type AcurastProcessorManagerPalletId = AcurastProcessorManagerPalletId;
+        type ProcessorPairingProof = sp_runtime::MultiSignature;
         type AcurastParachainId = AcurastParachainId;
+        type SelfLocation = SelfLocation;
+        type DefaultTransactWeight = DefaultTransactWeight;
+        type ProxyCallTimeout = ProxyCallTimeout;
+        type AssetTransactor = AssetTransactors;
+        type XcmSendWeight = XcmSendWeight;
     }
 
     impl pallet_timestamp::Config for Runtime {
@@ -498,13 +654,34 @@ pub mod proxy_runtime {
         type MinimumPeriod = MinimumPeriod;
         type WeightInfo = ();
     }
+
+    impl pallet_assets::Config for Runtime {
+        type RuntimeEvent = RuntimeEvent;
+        type Balance = AcurastAssetAmount;
+        type AssetId = InternalAssetId;
+        type AssetIdParameter = codec::Compact<InternalAssetId>;
+        type Currency = Balances;
+        type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+        type ForceOrigin = frame_system::EnsureRoot<Self::AccountId>;
+        type AssetDeposit = ConstU128<0>;
+        type AssetAccountDeposit = ConstU128<0>;
+        type MetadataDepositBase = ConstU128<0>;
+        type MetadataDepositPerByte = ConstU128<0>;
+        type ApprovalDeposit = ConstU128<0>;
+        type StringLimit = ConstU32<50>;
+        type Freezer = ();
+        type Extra = ();
+        type WeightInfo = ();
+        type RemoveItemsLimit = ();
+    }
 }
 
 pub mod relay_chain {
     use frame_support::{
         construct_runtime, parameter_types,
         sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32},
-        traits::{Everything, Nothing},
+        traits::{ConstU32, Everything, Nothing},
+        weights::Weight,
     };
     use polkadot_parachain::primitives::Id as ParaId;
     use polkadot_runtime_parachains::{configuration, origin, shared, ump};
@@ -514,8 +691,7 @@ pub mod relay_chain {
         AccountId32Aliases, AllowUnpaidExecutionFrom, ChildParachainAsNative,
         ChildParachainConvertsVia, ChildSystemParachainAsSuperuser,
         CurrencyAdapter as XcmCurrencyAdapter, FixedRateOfFungible, FixedWeightBounds, IsConcrete,
-        LocationInverter, SignedAccountId32AsNative, SignedToAccountId32,
-        SovereignSignedViaLocation,
+        SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
     };
     use xcm_executor::{Config, XcmExecutor};
 
@@ -549,14 +725,23 @@ pub mod relay_chain {
         type OriginConverter = LocalOriginConverter;
         type IsReserve = ();
         type IsTeleporter = ();
-        type LocationInverter = LocationInverter<Ancestry>;
+        type UniversalLocation = UniversalLocation;
         type Barrier = Barrier;
         type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
-        type Trader = FixedRateOfFungible<KsmPerSecond, ()>;
+        type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
         type ResponseHandler = ();
         type AssetTrap = ();
         type AssetClaims = ();
         type SubscriptionService = ();
+        type AssetLocker = ();
+        type AssetExchanger = ();
+        type PalletInstancesInfo = ();
+        type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+        type FeeManager = ();
+        type MessageExporter = ();
+        type UniversalAliases = Nothing;
+        type CallDispatcher = RuntimeCall;
+        type SafeCallFilter = Everything;
     }
 
     construct_runtime!(
@@ -576,14 +761,15 @@ pub mod relay_chain {
     parameter_types! {
         pub const KsmLocation: MultiLocation = Here.into();
         pub const KusamaNetwork: NetworkId = NetworkId::Kusama;
-        pub const AnyNetwork: NetworkId = NetworkId::Any;
-        pub Ancestry: MultiLocation = Here.into();
-        pub UnitWeightCost: u64 = 1_000;
+        pub const AnyNetwork: Option<NetworkId> = None;
+        pub UniversalLocation: InteriorMultiLocation = Here;
+        pub UnitWeightCost: Weight = Weight::from_ref_time(1_000);
     }
     parameter_types! {
-        pub const BaseXcmWeight: u64 = 1_000;
-        pub KsmPerSecond: (AssetId, u128) = (Concrete(KsmLocation::get()), 1);
+        pub const BaseXcmWeight: Weight = Weight::from_ref_time(1_000);
+        pub KsmPerSecondPerByte: (AssetId, u128, u128) = (Concrete(KsmLocation::get()), 1, 1);
         pub const MaxInstructions: u32 = 100;
+        pub const MaxAssetsIntoHolding: u32 = 64;
     }
     parameter_types! {
         pub const FirstMessageFactorPercent: u64 = 100;
@@ -653,11 +839,16 @@ pub mod relay_chain {
         type XcmTeleportFilter = Everything;
         type XcmReserveTransferFilter = Everything;
         type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
-        type LocationInverter = LocationInverter<Ancestry>;
+        type UniversalLocation = UniversalLocation;
         type RuntimeOrigin = RuntimeOrigin;
         type RuntimeCall = RuntimeCall;
         const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
         type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+        type Currency = Balances;
+        type CurrencyMatcher = ();
+        type TrustedLockers = ();
+        type SovereignAccountOf = SovereignAccountOf;
+        type MaxLockers = ConstU32<8>;
     }
 
     impl ump::Config for Runtime {
@@ -748,19 +939,16 @@ pub mod mock_msg_queue {
             max_weight: Weight,
         ) -> Result<Weight, XcmError> {
             let hash = Encode::using_encoded(&xcm, T::Hashing::hash);
+            let message_hash = Encode::using_encoded(&xcm, sp_io::hashing::blake2_256);
             let (result, event) = match Xcm::<T::RuntimeCall>::try_from(xcm) {
                 Ok(xcm) => {
                     let location = (1, Parachain(sender.into()));
-                    match T::XcmExecutor::execute_xcm(location, xcm, max_weight.ref_time()) {
+                    match T::XcmExecutor::execute_xcm(location, xcm, message_hash, max_weight) {
                         Outcome::Error(e) => (Err(e.clone()), Event::Fail(Some(hash), e)),
-                        Outcome::Complete(w) => {
-                            (Ok(Weight::from_ref_time(w)), Event::Success(Some(hash)))
-                        }
+                        Outcome::Complete(w) => (Ok(w), Event::Success(Some(hash))),
                         // As far as the caller is concerned, this was dispatched without error, so
                         // we just report the weight used.
-                        Outcome::Incomplete(w, e) => {
-                            (Ok(Weight::from_ref_time(w)), Event::Fail(Some(hash), e))
-                        }
+                        Outcome::Incomplete(w, e) => (Ok(w), Event::Fail(Some(hash), e)),
                     }
                 }
                 Err(()) => (
@@ -822,8 +1010,7 @@ pub mod mock_msg_queue {
                         Self::deposit_event(Event::UnsupportedVersion(id));
                     }
                     Ok(Ok(x)) => {
-                        let outcome =
-                            T::XcmExecutor::execute_xcm(Parent, x.clone(), limit.ref_time());
+                        let outcome = T::XcmExecutor::execute_xcm(Parent, x.clone(), id, limit);
                         <ReceivedDmp<T>>::append(x);
                         Self::deposit_event(Event::ExecutedDownward(id, outcome));
                     }
@@ -15,9 +15,11 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use acurast_common::Schedule;
-use frame_support::{pallet_prelude::GenesisBuild, sp_runtime::traits::AccountIdConversion};
+use frame_support::{
+    pallet_prelude::GenesisBuild, sp_runtime::traits::AccountIdConversion, weights::Weight,
+};
 use hex_literal::hex;
-use polkadot_parachain::primitives::Id as ParaId;
+use polkadot_parachain::primitives::{Id as ParaId, Sibling};
 use sp_runtime::traits::ConstU32;
 use sp_runtime::{bounded_vec, BoundedVec};
 use xcm::prelude::*;
@@ -33,6 +35,7 @@ use pallet_acurast_marketplace::{
 
 use crate::mock::*;
 use crate::mock::{acurast_runtime::FeeManagerImpl, proxy_runtime::AccountId};
+use crate::{RewardAssetTransfer, XcmExecutionConfig};
 
 pub type RelayChainPalletXcm = pallet_xcm::Pallet<relay_chain::Runtime>;
 pub type AcurastPalletXcm = pallet_xcm::Pallet<acurast_runtime::Runtime>;
@@ -94,6 +97,7 @@ pub fn acurast_ext(para_id: u32) -> sp_io::TestExternalities {
             (pallet_fees_account(), INITIAL_BALANCE),
             (bob_account_id(), INITIAL_BALANCE),
             (processor_account_id(), INITIAL_BALANCE),
+            (sibling_account_id(2001), INITIAL_BALANCE),
         ],
     }
     .assimilate_storage(&mut t)
@@ -101,8 +105,12 @@ pub fn acurast_ext(para_id: u32) -> sp_io::TestExternalities {
 
     // give alice an initial balance of token 22 (backed by statemint) to pay for a job
     // get the MultiAsset representing token 22 with owned_asset()
+    // token 23 is a reserve asset of the proxy parachain, deposited when rewards are transferred with proxied registrations
     pallet_assets::GenesisConfig::<Runtime> {
-        assets: vec![(22, pallet_assets_account(), false, 1_000)],
+        assets: vec![
+            (22, pallet_assets_account(), false, 1_000),
+            (23, pallet_assets_account(), true, 1),
+        ],
         metadata: vec![(22, "test_payment".into(), "tpt".into(), 12.into())],
         accounts: vec![
             (22, alice_account_id(), INITIAL_BALANCE),
@@ -114,11 +122,17 @@ pub fn acurast_ext(para_id: u32) -> sp_io::TestExternalities {
 
     // make asset 22 a valid asset via Genesis
     pallet_acurast_assets::GenesisConfig::<Runtime> {
-        assets: vec![(22, 1000, 50, 22)],
+        assets: vec![(22, 1000, 50, 22), (23, 2001, 50, 23)],
     }
     .assimilate_storage(&mut t)
     .unwrap();
 
+    <pallet_acurast::GenesisConfig as GenesisBuild<Runtime>>::assimilate_storage(
+        &Default::default(),
+        &mut t,
+    )
+    .unwrap();
+
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| {
         System::set_block_number(1);
@@ -140,10 +154,36 @@ pub fn proxy_ext(para_id: u32) -> sp_io::TestExternalities {
     .assimilate_storage(&mut t)
     .unwrap();
 
+    // give alice an initial balance of token 23 to fund the rewards of jobs registered with `register_with_reward`
+    pallet_assets::GenesisConfig::<Runtime> {
+        assets: vec![(23, ALICE, true, 1)],
+        metadata: vec![],
+        accounts: vec![(23, ALICE, INITIAL_BALANCE)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    // send XCM v2 to the Acurast parachain until it negotiated its version
+    <pallet_xcm::GenesisConfig as GenesisBuild<Runtime>>::assimilate_storage(
+        &pallet_xcm::GenesisConfig {
+            safe_xcm_version: Some(2),
+        },
+        &mut t,
+    )
+    .unwrap();
+
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| {
         System::set_block_number(1);
         MsgQueue::set_para_id(para_id.into());
+        crate::StoredXcmExecutionConfig::<Runtime>::put(xcm_execution_config());
+        // proxied calls report their outcome with XCM v3 instructions
+        proxy_runtime::PolkadotXcm::force_xcm_version(
+            proxy_runtime::RuntimeOrigin::root(),
+            Box::new((Parent, Parachain(2000)).into()),
+            3,
+        )
+        .unwrap();
     });
     ext
 }
@@ -172,6 +212,16 @@ pub fn relay_ext() -> sp_io::TestExternalities {
 pub fn para_account_id(id: u32) -> relay_chain::AccountId {
     ParaId::from(id).into_account_truncating()
 }
+pub fn sibling_account_id(id: u32) -> AcurastAccountId {
+    Sibling::from(id).into_account_truncating()
+}
+pub fn xcm_execution_config() -> XcmExecutionConfig {
+    XcmExecutionConfig {
+        fee_asset: MultiLocation::parent(),
+        fee_amount: 1_000,
+        weight_limit: Weight::from_ref_time(1_000_000_000),
+    }
+}
 pub fn processor_account_id() -> AcurastAccountId {
     hex!("b8bc25a2b4c0386b8892b43e435b71fe11fa50533935f027949caf04bcce4694").into()
 }
@@ -196,6 +246,16 @@ pub fn owned_asset(amount: u128) -> AcurastAsset {
         fun: Fungible(amount),
     })
 }
+/// Token 23 of the proxy parachain, as seen from the proxy parachain.
+pub fn proxy_asset(amount: u128) -> MultiAsset {
+    MultiAsset {
+        id: Concrete(MultiLocation::new(
+            0,
+            X2(PalletInstance(50), GeneralIndex(23)),
+        )),
+        fun: Fungible(amount),
+    }
+}
 pub fn registration() -> JobRegistration<AccountId, JobRequirements<AcurastAsset, AccountId>> {
     JobRegistration {
         script: SCRIPT_BYTES.to_vec().try_into().unwrap(),
@@ -213,8 +273,12 @@ pub fn registration() -> JobRegistration<AccountId, JobRequirements<AcurastAsset
         storage: 20_000u32,
         extra: JobRequirements {
             slots: 1,
+            min_slots: None,
             reward: owned_asset(20000),
+            slot_rewards: None,
             instant_match: None,
+            min_reputation: None,
+            max_output_size: None,
         },
     }
 }
@@ -239,6 +303,7 @@ pub fn advertisement(
         fee_per_millisecond,
         fee_per_storage_byte: 0,
         base_fee_per_execution: 0,
+        stake_per_execution: 0,
         scheduling_window: SchedulingWindow::Delta(2_628_000_000), // 1 month
     }];
     Advertisement {
@@ -253,7 +318,7 @@ pub fn advertisement(
 #[cfg(test)]
 mod network_tests {
     use codec::Encode;
-    use frame_support::assert_ok;
+    use frame_support::{assert_noop, assert_ok};
     use xcm::latest::prelude::*;
     use xcm_simulator::TestExt;
 
@@ -281,8 +346,8 @@ mod network_tests {
                 Here,
                 Parachain(2000),
                 Xcm(vec![Transact {
-                    origin_type: OriginKind::SovereignAccount,
-                    require_weight_at_most: INITIAL_BALANCE as u64,
+                    origin_kind: OriginKind::SovereignAccount,
+                    require_weight_at_most: Weight::from_ref_time(INITIAL_BALANCE as u64),
                     call: remark.encode().into(),
                 }]),
             ));
@@ -311,8 +376,8 @@ mod network_tests {
                 Here,
                 Parent,
                 Xcm(vec![Transact {
-                    origin_type: OriginKind::SovereignAccount,
-                    require_weight_at_most: INITIAL_BALANCE as u64,
+                    origin_kind: OriginKind::SovereignAccount,
+                    require_weight_at_most: Weight::from_ref_time(INITIAL_BALANCE as u64),
                     call: remark.encode().into(),
                 }]),
             ));
@@ -342,8 +407,8 @@ mod network_tests {
                 Here,
                 (Parent, Parachain(2001)),
                 Xcm(vec![Transact {
-                    origin_type: OriginKind::SovereignAccount,
-                    require_weight_at_most: INITIAL_BALANCE as u64,
+                    origin_kind: OriginKind::SovereignAccount,
+                    require_weight_at_most: Weight::from_ref_time(INITIAL_BALANCE as u64),
                     call: remark.encode().into(),
                 }]),
             ));
@@ -370,7 +435,7 @@ mod network_tests {
                 Box::new(X1(Parachain(2000)).into().into()),
                 Box::new(
                     X1(AccountId32 {
-                        network: Any,
+                        network: None,
                         id: ALICE.into()
                     })
                     .into()
@@ -409,8 +474,7 @@ mod network_tests {
                 WithdrawAsset((Here, send_amount).into()),
                 buy_execution((Here, send_amount)),
                 DepositAsset {
-                    assets: All.into(),
-                    max_assets: 1,
+                    assets: AllCounted(1).into(),
                     beneficiary: Parachain(2001).into(),
                 },
             ]);
@@ -432,7 +496,7 @@ mod network_tests {
 
     /// Scenario:
     /// A parachain wants to be notified that a transfer worked correctly.
-    /// It sends a `QueryHolding` after the deposit to get notified on success.
+    /// It sends a `ReportHolding` after the deposit to get notified on success.
     ///
     /// Asserts that the balances are updated correctly and the expected XCM is sent.
     #[test]
@@ -448,15 +512,16 @@ mod network_tests {
                 WithdrawAsset((Here, send_amount).into()),
                 buy_execution((Here, send_amount)),
                 DepositAsset {
-                    assets: All.into(),
-                    max_assets: 1,
+                    assets: AllCounted(1).into(),
                     beneficiary: Parachain(2001).into(),
                 },
-                QueryHolding {
-                    query_id: query_id_set,
-                    dest: Parachain(2000).into(),
+                ReportHolding {
+                    response_info: QueryResponseInfo {
+                        destination: Parachain(2000).into(),
+                        query_id: query_id_set,
+                        max_weight: Weight::from_ref_time(1_000_000_000),
+                    },
                     assets: All.into(),
-                    max_response_weight: 1_000_000_000,
                 },
             ]);
             // Send withdraw and deposit with query holding
@@ -484,7 +549,8 @@ mod network_tests {
                 vec![Xcm(vec![QueryResponse {
                     query_id: query_id_set,
                     response: Response::Assets(MultiAssets::new()),
-                    max_weight: 1_000_000_000,
+                    max_weight: Weight::from_ref_time(1_000_000_000),
+                    querier: Some(Here.into()),
                 }])],
             );
         });
@@ -535,6 +601,246 @@ mod proxy_calls {
         });
     }
 
+    #[test]
+    fn register_without_execution_config() {
+        Network::reset();
+
+        ProxyParachain::execute_with(|| {
+            use crate::pallet::Call::{register, set_xcm_execution_config};
+            use proxy_runtime::RuntimeCall::AcurastProxy;
+
+            assert_ok!(AcurastProxy(set_xcm_execution_config { config: None })
+                .dispatch(proxy_runtime::RuntimeOrigin::root()));
+            assert_ok!(AcurastProxy(register {
+                registration: registration(),
+            })
+            .dispatch(proxy_runtime::RuntimeOrigin::signed(alice_account_id())));
+        });
+
+        // the unpaid message does not pass the barrier of the Acurast parachain
+        AcurastParachain::execute_with(|| {
+            use acurast_runtime::pallet_acurast::StoredJobRegistration;
+            use acurast_runtime::Runtime;
+            use pallet_acurast::Script;
+
+            let script: Script = SCRIPT_BYTES.to_vec().try_into().unwrap();
+            assert!(StoredJobRegistration::<Runtime>::get(ALICE, script).is_none());
+        });
+    }
+
+    #[test]
+    fn migrate_execution_config_to_v3() {
+        use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+        Network::reset();
+
+        ProxyParachain::execute_with(|| {
+            use proxy_runtime::{AcurastProxy, Runtime};
+
+            StorageVersion::new(0).put::<AcurastProxy>();
+            frame_support::storage::unhashed::put(
+                &crate::StoredXcmExecutionConfig::<Runtime>::hashed_key(),
+                &crate::migrations::v1::OldXcmExecutionConfig {
+                    fee_asset: xcm::v2::MultiLocation::parent(),
+                    fee_amount: 1_000,
+                    weight_limit: 1_000_000_000,
+                },
+            );
+
+            crate::migrations::migrate::<Runtime>();
+
+            assert_eq!(
+                Some(xcm_execution_config()),
+                AcurastProxy::stored_xcm_execution_config()
+            );
+            assert_eq!(
+                StorageVersion::new(1),
+                AcurastProxy::on_chain_storage_version()
+            );
+        });
+    }
+
+    #[test]
+    fn register_pays_execution() {
+        Network::reset();
+        register_job_alice();
+
+        // the fees not used for the execution are deposited back to the proxy parachain's sovereign account
+        AcurastParachain::execute_with(|| {
+            assert_eq!(
+                acurast_runtime::Balances::free_balance(&sibling_account_id(2001)),
+                INITIAL_BALANCE
+            );
+        });
+    }
+
+    #[test]
+    fn register_reports_outcome() {
+        Network::reset();
+        register_job_alice();
+
+        ProxyParachain::execute_with(|| {
+            use proxy_runtime::{AcurastProxy, RuntimeEvent, System};
+
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastProxy(crate::Event::ProxyCallSucceeded {
+                    extrinsic: crate::ExtrinsicName::Register,
+                    ..
+                })
+            )));
+            assert_eq!(None, AcurastProxy::stored_pending_proxy_call(0));
+        });
+    }
+
+    #[test]
+    fn update_allowed_sources_reports_failure() {
+        Network::reset();
+
+        // the job is not registered on the Acurast parachain
+        ProxyParachain::execute_with(|| {
+            use crate::pallet::Call::update_allowed_sources;
+            use proxy_runtime::RuntimeCall::AcurastProxy;
+
+            assert_ok!(AcurastProxy(update_allowed_sources {
+                script: SCRIPT_BYTES.to_vec().try_into().unwrap(),
+                updates: vec![],
+            })
+            .dispatch(proxy_runtime::RuntimeOrigin::signed(ALICE)));
+        });
+
+        ProxyParachain::execute_with(|| {
+            use proxy_runtime::{RuntimeEvent, System};
+
+            // the failed dispatch is reported by the `ExpectTransactStatus` following the `Transact`
+            assert!(System::events().iter().any(|event| matches!(
+                &event.event,
+                RuntimeEvent::AcurastProxy(crate::Event::ProxyCallFailed {
+                    extrinsic: crate::ExtrinsicName::UpdateAllowedSources,
+                    index: 5,
+                    error: XcmError::ExpectationFalse,
+                    ..
+                })
+            )));
+        });
+    }
+
+    #[test]
+    fn proxy_call_times_out() {
+        Network::reset();
+
+        ProxyParachain::execute_with(|| {
+            use crate::pallet::Call::{register, set_xcm_execution_config};
+            use frame_support::traits::Hooks;
+            use proxy_runtime::{AcurastProxy, RuntimeEvent, System};
+
+            // the unpaid message is not executed, so no outcome is reported
+            assert_ok!(
+                proxy_runtime::RuntimeCall::AcurastProxy(set_xcm_execution_config { config: None })
+                    .dispatch(proxy_runtime::RuntimeOrigin::root())
+            );
+            assert_ok!(proxy_runtime::RuntimeCall::AcurastProxy(register {
+                registration: registration(),
+            })
+            .dispatch(proxy_runtime::RuntimeOrigin::signed(ALICE)));
+
+            let (query_id, pending) =
+                crate::StoredPendingProxyCall::<proxy_runtime::Runtime>::iter()
+                    .next()
+                    .unwrap();
+            assert_eq!(11, pending.timeout);
+
+            AcurastProxy::on_initialize(11);
+            assert_eq!(
+                Some(RuntimeEvent::AcurastProxy(
+                    crate::Event::ProxyCallTimedOut {
+                        query_id,
+                        extrinsic: crate::ExtrinsicName::Register,
+                        caller: ALICE,
+                    }
+                )),
+                System::events().pop().map(|record| record.event)
+            );
+            assert_eq!(None, AcurastProxy::stored_pending_proxy_call(query_id));
+        });
+    }
+
+    #[test]
+    fn register_with_reward() {
+        Network::reset();
+
+        ProxyParachain::execute_with(|| {
+            use crate::pallet::Call::{register_with_reward, set_reward_asset_transfer};
+            use proxy_runtime::RuntimeCall::AcurastProxy;
+
+            assert_ok!(AcurastProxy(set_reward_asset_transfer {
+                asset_id: proxy_asset(0).id,
+                transfer: Some(RewardAssetTransfer::ReserveTransfer),
+            })
+            .dispatch(proxy_runtime::RuntimeOrigin::root()));
+
+            // the reward is token 23 as seen from the Acurast parachain
+            let mut registration = registration();
+            registration.extra.reward = AcurastAsset(MultiAsset {
+                id: Concrete(MultiLocation::new(
+                    1,
+                    X3(Parachain(2001), PalletInstance(50), GeneralIndex(23)),
+                )),
+                fun: Fungible(20000),
+            });
+            assert_ok!(AcurastProxy(register_with_reward {
+                registration,
+                reward: proxy_asset(100_000),
+            })
+            .dispatch(proxy_runtime::RuntimeOrigin::signed(ALICE)));
+
+            // this chain is the reserve of the reward, held by the Acurast parachain's sovereign account
+            assert_eq!(
+                proxy_runtime::Assets::balance(23, ALICE),
+                INITIAL_BALANCE - 100_000
+            );
+            assert_eq!(
+                proxy_runtime::Assets::balance(23, sibling_account_id(2000)),
+                100_000
+            );
+        });
+
+        // the reward is deposited to alice's account and locked from there
+        AcurastParachain::execute_with(|| {
+            use acurast_runtime::{AcurastMarketplace, Assets};
+            use pallet_acurast::Script;
+            use pallet_acurast_marketplace::Reward;
+
+            let script: Script = SCRIPT_BYTES.to_vec().try_into().unwrap();
+            let budget = AcurastMarketplace::stored_job_budget(ALICE, script)
+                .unwrap()
+                .try_get_amount()
+                .unwrap();
+            assert!(budget > 0);
+            assert_eq!(Assets::balance(23, ALICE), 100_000 - budget);
+            assert_eq!(Assets::balance(23, pallet_assets_account()), budget);
+        });
+    }
+
+    #[test]
+    fn register_with_reward_requires_transfer_config() {
+        Network::reset();
+
+        ProxyParachain::execute_with(|| {
+            use crate::pallet::Call::register_with_reward;
+            use proxy_runtime::RuntimeCall::AcurastProxy;
+
+            assert_noop!(
+                AcurastProxy(register_with_reward {
+                    registration: registration(),
+                    reward: proxy_asset(100_000),
+                })
+                .dispatch(proxy_runtime::RuntimeOrigin::signed(ALICE)),
+                crate::Error::<proxy_runtime::Runtime>::RewardAssetNotTransferable
+            );
+        });
+    }
+
     #[test]
     fn deregister() {
         Network::reset();
@@ -678,4 +984,200 @@ mod proxy_calls {
             )));
         });
     }
+
+    #[test]
+    fn delete_advertisement() {
+        advertise_bob();
+
+        ProxyParachain::execute_with(|| {
+            use crate::pallet::Call::delete_advertisement;
+            use proxy_runtime::RuntimeCall::AcurastProxy;
+
+            assert_ok!(AcurastProxy(delete_advertisement {})
+                .dispatch(proxy_runtime::RuntimeOrigin::signed(bob_account_id())));
+        });
+
+        AcurastParachain::execute_with(|| {
+            use acurast_runtime::pallet_acurast_marketplace::Event::AdvertisementRemoved;
+            use acurast_runtime::pallet_acurast_marketplace::StoredAdvertisementRestriction;
+            use acurast_runtime::{Runtime, RuntimeEvent, System};
+
+            assert!(StoredAdvertisementRestriction::<Runtime>::get(BOB).is_none());
+            assert!(System::events().iter().any(|event| matches!(
+                event.event,
+                RuntimeEvent::AcurastMarketplace(AdvertisementRemoved(..))
+            )));
+        });
+    }
+
+    /// Asserts that proxied calls are encoded like the calls of the pallets on the Acurast parachain.
+    #[test]
+    fn proxy_calls_encode_like_acurast_calls() {
+        use crate::{AcurastCall, MarketplaceCall, ProcessorManagerCall, ProxyCall};
+        use acurast_common::{AttestationChain, ListUpdate};
+        use acurast_runtime::{
+            pallet_acurast, pallet_acurast_marketplace, pallet_acurast_processor_manager,
+            RuntimeCall,
+        };
+        use codec::Encode;
+        use pallet_acurast::{AllowedSourcesUpdate, ListUpdateOperation, Script};
+        use pallet_acurast_marketplace::{ExecutionResult, Match, PlannedExecution};
+        use pallet_acurast_processor_manager::ProcessorPairing;
+        use sp_runtime::{MultiAddress, MultiSignature};
+
+        type ProxyCallFor = ProxyCall<proxy_runtime::Runtime>;
+
+        let script: Script = SCRIPT_BYTES.to_vec().try_into().unwrap();
+        let job_id = (ALICE, script.clone());
+        let updates = vec![AllowedSourcesUpdate {
+            operation: ListUpdateOperation::Add,
+            item: BOB,
+        }];
+        let attestation_chain = AttestationChain {
+            certificate_chain: vec![vec![1u8; 10].try_into().unwrap()].try_into().unwrap(),
+        };
+        let matches = vec![Match {
+            job_id: job_id.clone(),
+            sources: vec![PlannedExecution {
+                source: BOB,
+                start_delay: 0,
+            }],
+        }];
+        let execution_result = ExecutionResult::Success(vec![0u8; 32].try_into().unwrap());
+        let pairing = ProcessorPairing::<_, MultiSignature>::new_with_proof(
+            ALICE,
+            1,
+            MultiSignature::Sr25519(sp_core::sr25519::Signature([0u8; 64])),
+        );
+        let pairing_updates = vec![ListUpdate {
+            operation: ListUpdateOperation::Add,
+            item: pairing.clone(),
+        }];
+
+        let calls: Vec<(ProxyCallFor, RuntimeCall)> = vec![
+            (
+                ProxyCall::Acurast(AcurastCall::Register {
+                    registration: registration(),
+                }),
+                RuntimeCall::Acurast(pallet_acurast::Call::register {
+                    registration: registration(),
+                }),
+            ),
+            (
+                ProxyCall::Acurast(AcurastCall::Deregister {
+                    script: script.clone(),
+                }),
+                RuntimeCall::Acurast(pallet_acurast::Call::deregister {
+                    script: script.clone(),
+                }),
+            ),
+            (
+                ProxyCall::Acurast(AcurastCall::UpdateAllowedSources {
+                    script: script.clone(),
+                    updates: updates.clone(),
+                }),
+                RuntimeCall::Acurast(pallet_acurast::Call::update_allowed_sources {
+                    script: script.clone(),
+                    updates,
+                }),
+            ),
+            (
+                ProxyCall::Acurast(AcurastCall::SubmitAttestation {
+                    attestation_chain: attestation_chain.clone(),
+                }),
+                RuntimeCall::Acurast(pallet_acurast::Call::submit_attestation {
+                    attestation_chain,
+                }),
+            ),
+            (
+                ProxyCall::Marketplace(MarketplaceCall::Advertise {
+                    advertisement: advertisement(10000u128),
+                }),
+                RuntimeCall::AcurastMarketplace(pallet_acurast_marketplace::Call::advertise {
+                    advertisement: advertisement(10000u128),
+                }),
+            ),
+            (
+                ProxyCall::Marketplace(MarketplaceCall::DeleteAdvertisement),
+                RuntimeCall::AcurastMarketplace(
+                    pallet_acurast_marketplace::Call::delete_advertisement {},
+                ),
+            ),
+            (
+                ProxyCall::Marketplace(MarketplaceCall::ProposeMatching {
+                    matches: matches.clone(),
+                }),
+                RuntimeCall::AcurastMarketplace(
+                    pallet_acurast_marketplace::Call::propose_matching { matches },
+                ),
+            ),
+            (
+                ProxyCall::Marketplace(MarketplaceCall::AcknowledgeMatch {
+                    job_id: job_id.clone(),
+                }),
+                RuntimeCall::AcurastMarketplace(
+                    pallet_acurast_marketplace::Call::acknowledge_match {
+                        job_id: job_id.clone(),
+                    },
+                ),
+            ),
+            (
+                ProxyCall::Marketplace(MarketplaceCall::Report {
+                    job_id: job_id.clone(),
+                    last: true,
+                    execution_result: execution_result.clone(),
+                }),
+                RuntimeCall::AcurastMarketplace(pallet_acurast_marketplace::Call::report {
+                    job_id,
+                    last: true,
+                    execution_result,
+                }),
+            ),
+            (
+                ProxyCall::ProcessorManager(ProcessorManagerCall::UpdateProcessorPairings {
+                    pairing_updates: pairing_updates.clone(),
+                }),
+                RuntimeCall::AcurastProcessorManager(
+                    pallet_acurast_processor_manager::Call::update_processor_pairings {
+                        pairing_updates,
+                    },
+                ),
+            ),
+            (
+                ProxyCall::ProcessorManager(ProcessorManagerCall::PairWithManager {
+                    pairing: pairing.clone(),
+                }),
+                RuntimeCall::AcurastProcessorManager(
+                    pallet_acurast_processor_manager::Call::pair_with_manager { pairing },
+                ),
+            ),
+            (
+                ProxyCall::ProcessorManager(ProcessorManagerCall::RecoverFunds {
+                    processor: MultiAddress::Id(ALICE),
+                    destination: MultiAddress::Id(BOB),
+                }),
+                RuntimeCall::AcurastProcessorManager(
+                    pallet_acurast_processor_manager::Call::recover_funds {
+                        processor: MultiAddress::Id(ALICE),
+                        destination: MultiAddress::Id(BOB),
+                    },
+                ),
+            ),
+            (
+                ProxyCall::ProcessorManager(ProcessorManagerCall::Heartbeat),
+                RuntimeCall::AcurastProcessorManager(
+                    pallet_acurast_processor_manager::Call::heartbeat {},
+                ),
+            ),
+        ];
+
+        for (proxy_call, acurast_call) in calls {
+            assert_eq!(
+                acurast_call.encode(),
+                proxy_call.encode_call(),
+                "{:?}",
+                proxy_call
+            );
+        }
+    }
 }